@@ -0,0 +1,149 @@
+//! A `pallet-contracts` chain extension exposing [`verifier::webauthn_verify`] to contracts, so
+//! they can check passkey signatures without verifying P-256 signatures in Wasm.
+//!
+//! The extension has a single function, [`VERIFY_FUNC_ID`], taking the SCALE encoding of
+//! `(authenticator_data, client_data_json, signature_der, public_key_der)`, four byte vectors,
+//! and returning a [`StatusCode`]. From ink!:
+//!
+//! ```ignore
+//! #[ink::chain_extension(extension = 0x5741)]
+//! pub trait WebAuthn {
+//!     type ErrorCode = WebAuthnError;
+//!
+//!     #[ink(function = 1)]
+//!     fn verify(
+//!         authenticator_data: Vec<u8>,
+//!         client_data_json: Vec<u8>,
+//!         signature: Vec<u8>,
+//!         public_key: Vec<u8>,
+//!     ) -> Result<(), WebAuthnError>;
+//! }
+//! ```
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use codec::Decode;
+use frame_support::weights::Weight;
+use pallet_contracts::chain_extension::{
+    ChainExtension, Environment, Ext, InitState, RegisteredChainExtension, Result, RetVal,
+};
+use sp_runtime::DispatchError;
+use verifier::{webauthn_verify, VerifyError};
+
+#[cfg(test)]
+mod tests;
+
+const LOG_TARGET: &str = "webauthn::chain_extension";
+
+/// The id of the extension, to register it along others.
+pub const EXTENSION_ID: u16 = 0x5741;
+
+/// Verifies an assertion signature.
+pub const VERIFY_FUNC_ID: u16 = 1;
+
+/// Longest input accepted, well above any real assertion. Longer ones fail with
+/// [`VerifyError::InputTooLarge`] before any weight is charged for them.
+pub const MAX_INPUT_LEN: u32 = 4096;
+
+/// Weight of verifying a signature, regardless of the input size.
+pub const BASE_WEIGHT: Weight = Weight::from_parts(1_000_000_000, 0);
+
+/// Weight of decoding and hashing each byte of input.
+pub const PER_BYTE_WEIGHT: Weight = Weight::from_parts(10_000, 0);
+
+/// Added to the [`code`](VerifyError::code) of the error a verification failed with, so it
+/// doesn't collide with the codes of the extension itself.
+pub const VERIFY_ERROR_BASE: u32 = 0x100;
+
+/// What [`VERIFY_FUNC_ID`] returns to the contract, as the `u32` it converts into.
+///
+/// The values are part of the contract-facing API, so they never change: `0` on success, `1`
+/// for a malformed input, and [`VERIFY_ERROR_BASE`] plus [`VerifyError::code`] when the
+/// verification fails, so contracts can tell failures apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusCode {
+    Success,
+    /// The input isn't the encoding of four byte vectors.
+    MalformedInput,
+    /// The verification failed with the given error.
+    Failed(VerifyError),
+}
+
+impl From<VerifyError> for StatusCode {
+    fn from(value: VerifyError) -> Self {
+        StatusCode::Failed(value)
+    }
+}
+
+impl From<StatusCode> for u32 {
+    fn from(value: StatusCode) -> Self {
+        match value {
+            StatusCode::Success => 0,
+            StatusCode::MalformedInput => 1,
+            StatusCode::Failed(e) => VERIFY_ERROR_BASE + u32::from(e),
+        }
+    }
+}
+
+/// The weight charged for verifying an input of `input_len` bytes.
+pub fn verify_weight(input_len: u32) -> Weight {
+    BASE_WEIGHT.saturating_add(PER_BYTE_WEIGHT.saturating_mul(input_len.into()))
+}
+
+/// Decodes the input of [`VERIFY_FUNC_ID`] and verifies the signature it carries.
+pub fn verify(input: &[u8]) -> StatusCode {
+    if input.len() > MAX_INPUT_LEN as usize {
+        return VerifyError::InputTooLarge.into();
+    }
+
+    let Ok((authenticator_data, client_data_json, signature, public_key)) =
+        <(Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>)>::decode(&mut &input[..])
+    else {
+        log::debug!(target: LOG_TARGET, "Malformed verify input");
+        return StatusCode::MalformedInput;
+    };
+
+    match webauthn_verify(
+        &authenticator_data,
+        &client_data_json,
+        &signature,
+        &public_key,
+    ) {
+        Ok(()) => StatusCode::Success,
+        Err(e) => e.into(),
+    }
+}
+
+/// The chain extension, to set as (or among) the `ChainExtension` of `pallet-contracts`.
+#[derive(Default)]
+pub struct WebAuthnExtension;
+
+impl<T: pallet_contracts::Config> ChainExtension<T> for WebAuthnExtension {
+    fn call<E: Ext<T = T>>(&mut self, env: Environment<E, InitState>) -> Result<RetVal> {
+        let mut env = env.buf_in_buf_out();
+        if env.func_id() != VERIFY_FUNC_ID {
+            log::debug!(target: LOG_TARGET, "Unknown function id {}", env.func_id());
+            return Err(DispatchError::Other(
+                "Unknown WebAuthn chain extension function",
+            ));
+        }
+
+        let input_len = env.in_len();
+        if input_len > MAX_INPUT_LEN {
+            return Ok(RetVal::Converging(
+                StatusCode::from(VerifyError::InputTooLarge).into(),
+            ));
+        }
+
+        env.charge_weight(verify_weight(input_len))?;
+        let input = env.read(input_len)?;
+        Ok(RetVal::Converging(verify(&input).into()))
+    }
+}
+
+impl<T: pallet_contracts::Config> RegisteredChainExtension<T> for WebAuthnExtension {
+    const ID: u16 = EXTENSION_ID;
+}
@@ -0,0 +1,99 @@
+use super::*;
+use codec::Encode;
+use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+use rand::rngs::OsRng;
+use verifier::{client_data_hash, sec1_public_key_to_der};
+
+fn signed_input(authenticator_data: &[u8], signed_authenticator_data: &[u8]) -> Vec<u8> {
+    let client_data_json = br#"{"type":"webauthn.get","challenge":"dGVzdA","origin":"o"}"#;
+
+    let signing_key = SigningKey::random(&mut OsRng);
+    let public_key = signing_key.verifying_key().to_encoded_point(false);
+    let signature: Signature = signing_key.sign(
+        &[
+            signed_authenticator_data,
+            &client_data_hash(client_data_json),
+        ]
+        .concat(),
+    );
+
+    (
+        authenticator_data.to_vec(),
+        client_data_json.to_vec(),
+        signature.to_der().as_bytes().to_vec(),
+        sec1_public_key_to_der(public_key.as_bytes()).unwrap(),
+    )
+        .encode()
+}
+
+#[test]
+fn verify_reports_the_outcome() {
+    let authenticator_data = b"example authenticator data";
+
+    assert_eq!(
+        verify(&signed_input(authenticator_data, authenticator_data)),
+        StatusCode::Success
+    );
+    assert_eq!(
+        verify(&signed_input(
+            b"altered authenticator data",
+            authenticator_data
+        )),
+        StatusCode::Failed(VerifyError::VerifySignature)
+    );
+}
+
+#[test]
+fn verify_rejects_malformed_and_oversized_inputs() {
+    let authenticator_data = b"example authenticator data";
+    let input = signed_input(authenticator_data, authenticator_data);
+
+    assert_eq!(
+        verify(&input[..input.len() - 1]),
+        StatusCode::MalformedInput
+    );
+    assert_eq!(
+        verify(&[0; MAX_INPUT_LEN as usize + 1]),
+        StatusCode::Failed(VerifyError::InputTooLarge)
+    );
+}
+
+#[test]
+fn status_codes_are_stable() {
+    assert_eq!(u32::from(StatusCode::Success), 0);
+    assert_eq!(u32::from(StatusCode::MalformedInput), 1);
+    // Failures carry the code of the verifier error, which pins its own values.
+    assert_eq!(
+        u32::from(StatusCode::Failed(VerifyError::ExtractPublicKey)),
+        0x105
+    );
+    assert_eq!(
+        u32::from(StatusCode::Failed(VerifyError::InputTooLarge)),
+        0x106
+    );
+    assert_eq!(
+        u32::from(StatusCode::Failed(VerifyError::ParseSignature)),
+        0x10b
+    );
+    assert_eq!(
+        u32::from(StatusCode::Failed(VerifyError::VerifySignature)),
+        0x10e
+    );
+    for code in 0..=u8::MAX {
+        if let Some(e) = VerifyError::from_code(code) {
+            assert_eq!(
+                u32::from(StatusCode::Failed(e)),
+                VERIFY_ERROR_BASE + u32::from(code)
+            );
+        }
+    }
+}
+
+#[test]
+fn weight_grows_with_the_input() {
+    assert_eq!(verify_weight(0), BASE_WEIGHT);
+    assert_eq!(
+        verify_weight(100).ref_time() - verify_weight(10).ref_time(),
+        90 * PER_BYTE_WEIGHT.ref_time()
+    );
+}
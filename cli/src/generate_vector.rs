@@ -0,0 +1,229 @@
+//! `generate-vector`: makes a registration and an assertion for a relying party and challenge,
+//! in the fixture schema of `pass_webauthn::testing::fixture`, for integrators to test their
+//! own pipelines with.
+//!
+//! The authenticator is deterministic: its credential is derived from a seed the way
+//! `pass_webauthn::testing::WebAuthnClient::new_seeded` derives its first one, and ECDSA
+//! signatures are deterministic (RFC 6979), so the same arguments always yield the same vector.
+
+use base64::prelude::BASE64_URL_SAFE_NO_PAD;
+use coset::cbor::{ser::into_writer, value::Value};
+use p256::{
+    ecdsa::{signature::Signer, Signature, SigningKey},
+    elliptic_curve::sec1::ToEncodedPoint,
+};
+use serde::Serialize;
+use serde_json::json;
+use verifier::{
+    client_data_hash, cose_key_from_public_key, sec1_public_key_to_der, sha256_fixed,
+    AttestedCredentialData, AuthenticatorData,
+};
+
+use crate::input::Bytes;
+
+/// `UP` and `UV`.
+const ASSERTION_FLAGS: u8 = 0x05;
+/// `UP`, `UV` and `AT`.
+const REGISTRATION_FLAGS: u8 = 0x45;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// The RP ID the authenticator data is scoped to.
+    #[arg(long)]
+    rp_id: String,
+    /// The origin the client data is collected at.
+    #[arg(long)]
+    origin: String,
+    /// The challenge the registration and the assertion sign.
+    #[arg(long)]
+    challenge: Bytes,
+    #[arg(long, value_enum, default_value_t = Algorithm::Es256)]
+    alg: Algorithm,
+    /// The 32 bytes the credential is derived from, all zeros if not given.
+    #[arg(long)]
+    seed: Option<Bytes>,
+    /// Corrupts the assertion so it fails verification in the given way.
+    #[arg(long, value_enum)]
+    invalid: Option<Corruption>,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Es256,
+    /// Not supported: the verifier only verifies ES256.
+    Eddsa,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Corruption {
+    /// Flips a bit of the signature.
+    Signature,
+    /// Signs another challenge than the given one.
+    Challenge,
+    /// Collects the client data at another origin than the given one.
+    Origin,
+}
+
+/// An assertion in the fixture schema, along with the registration of its credential. Binary
+/// members are base64url-encoded, without padding.
+#[derive(Serialize, Debug)]
+pub struct Vector {
+    pub description: String,
+    pub authenticator_data: String,
+    pub client_data_json: String,
+    pub signature: String,
+    /// The DER-encoded `SubjectPublicKeyInfo` of the credential.
+    pub public_key: String,
+    pub parsed: serde_json::Value,
+    pub policy: serde_json::Value,
+    /// What replaying the fixture yields. Replays don't check the origin, so an assertion
+    /// collected at another origin is still expected to verify.
+    pub expected: serde_json::Value,
+    pub registration: Registration,
+}
+
+/// The registration of the credential, with `packed` self-attestation.
+#[derive(Serialize, Debug)]
+pub struct Registration {
+    pub credential_id: String,
+    pub authenticator_data: String,
+    pub client_data_json: String,
+    pub attestation_object: String,
+}
+
+fn base64url(bytes: &[u8]) -> String {
+    base64::encode_engine(bytes, &BASE64_URL_SAFE_NO_PAD)
+}
+
+/// The client data of a `webauthn.create` or `webauthn.get` ceremony, with its members in the
+/// order browsers serialize them.
+fn client_data(r#type: &str, challenge: &[u8], origin: &str) -> Vec<u8> {
+    format!(
+        r#"{{"type":"{type}","challenge":"{challenge}","origin":{origin},"crossOrigin":false}}"#,
+        challenge = base64url(challenge),
+        origin = serde_json::Value::from(origin),
+    )
+    .into()
+}
+
+fn sign(key: &SigningKey, authenticator_data: &[u8], client_data_json: &[u8]) -> Vec<u8> {
+    let message = [authenticator_data, &client_data_hash(client_data_json)].concat();
+    let signature: Signature = key.sign(&message);
+    signature.to_der().as_bytes().to_vec()
+}
+
+pub fn run(args: &Args) -> Result<Vector, String> {
+    if args.alg == Algorithm::Eddsa {
+        return Err("EdDSA credentials aren't supported: the verifier only verifies ES256".into());
+    }
+    let seed: [u8; 32] = match &args.seed {
+        Some(Bytes(seed)) => seed
+            .as_slice()
+            .try_into()
+            .map_err(|_| format!("the seed is {} bytes, not 32", seed.len()))?,
+        None => [0; 32],
+    };
+
+    // As the seeded test client derives its first credential.
+    let derive = |label: &[u8]| sha256_fixed(&[&seed[..], label, &0u32.to_be_bytes()].concat());
+    let key = SigningKey::from_slice(&derive(b"credential key"))
+        .map_err(|_| "the seed derives an invalid key, try another one")?;
+    let credential_id = &derive(b"credential id")[..16];
+    let public_key = p256::PublicKey::from(key.verifying_key());
+    let rp_id_hash = sha256_fixed(args.rp_id.as_bytes());
+
+    let registration_data = AuthenticatorData {
+        rp_id_hash,
+        flags: REGISTRATION_FLAGS.into(),
+        sign_count: 0,
+        attested_credential_data: Some(AttestedCredentialData {
+            aaguid: [0; 16],
+            credential_id,
+            credential_public_key: cose_key_from_public_key(&public_key),
+        }),
+        extensions: None,
+    }
+    .encode();
+    let registration_client_data = client_data("webauthn.create", &args.challenge.0, &args.origin);
+    let attestation_object = Value::Map(vec![
+        ("fmt".into(), "packed".into()),
+        (
+            "attStmt".into(),
+            Value::Map(vec![
+                ("alg".into(), (-7).into()),
+                (
+                    "sig".into(),
+                    Value::Bytes(sign(&key, &registration_data, &registration_client_data)),
+                ),
+            ]),
+        ),
+        ("authData".into(), Value::Bytes(registration_data.clone())),
+    ]);
+    let mut attestation_object_bytes = Vec::new();
+    into_writer(&attestation_object, &mut attestation_object_bytes)
+        .expect("Encoding CBOR into a `Vec` doesn't fail");
+
+    let mut signed_challenge = args.challenge.0.clone();
+    let mut origin = args.origin.as_str();
+    match args.invalid {
+        Some(Corruption::Challenge) => match signed_challenge.first_mut() {
+            Some(byte) => *byte ^= 1,
+            None => signed_challenge.push(0),
+        },
+        Some(Corruption::Origin) => origin = "https://attacker.example",
+        Some(Corruption::Signature) | None => {}
+    }
+    let authenticator_data = AuthenticatorData {
+        rp_id_hash,
+        flags: ASSERTION_FLAGS.into(),
+        sign_count: 1,
+        attested_credential_data: None,
+        extensions: None,
+    }
+    .encode();
+    let client_data_json = client_data("webauthn.get", &signed_challenge, origin);
+    let mut signature = sign(&key, &authenticator_data, &client_data_json);
+    if args.invalid == Some(Corruption::Signature) {
+        // The last byte of `s`, so the signature stays well-formed DER.
+        *signature.last_mut().expect("Signatures aren't empty") ^= 1;
+    }
+
+    let (description, expected) = match args.invalid {
+        None => ("ES256, user verified", json!("ok")),
+        Some(Corruption::Signature) => (
+            "ES256, user verified, corrupted signature",
+            json!({ "error": "VerifySignature" }),
+        ),
+        Some(Corruption::Challenge) => (
+            "ES256, user verified, wrong challenge",
+            json!({ "error": "ChallengeMismatch" }),
+        ),
+        Some(Corruption::Origin) => ("ES256, user verified, wrong origin", json!("ok")),
+    };
+    Ok(Vector {
+        description: format!("Generated for {}, {description}", args.rp_id),
+        authenticator_data: base64url(&authenticator_data),
+        client_data_json: base64url(&client_data_json),
+        signature: base64url(&signature),
+        public_key: base64url(
+            &sec1_public_key_to_der(public_key.to_encoded_point(false).as_bytes())
+                .expect("The key was derived on the curve"),
+        ),
+        parsed: json!({
+            "flags": ASSERTION_FLAGS,
+            "sign_count": 1,
+        }),
+        policy: json!({
+            "expected_challenge": base64url(&args.challenge.0),
+            "stored_sign_count": 0,
+            "uv_required": true,
+        }),
+        expected,
+        registration: Registration {
+            credential_id: base64url(credential_id),
+            authenticator_data: base64url(&registration_data),
+            client_data_json: base64url(&registration_client_data),
+            attestation_object: base64url(&attestation_object_bytes),
+        },
+    })
+}
@@ -0,0 +1,34 @@
+//! Bytes given on the command line.
+
+use std::{fs, str::FromStr};
+
+use base64::prelude::BASE64_URL_SAFE_NO_PAD;
+
+/// Bytes given as base64url, as hex (optionally `0x`-prefixed) or as the contents of an
+/// `@path`ed file.
+///
+/// A string of hex digits of even length is taken as hex: base64url encodings of WebAuthn
+/// responses are never made of hex digits only in practice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bytes(pub Vec<u8>);
+
+impl FromStr for Bytes {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = s.strip_prefix('@') {
+            return fs::read(path)
+                .map(Bytes)
+                .map_err(|e| format!("can't read {path}: {e}"));
+        }
+
+        let hex = s.strip_prefix("0x").unwrap_or(s);
+        if hex.len().is_multiple_of(2) && hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return hex::decode(hex).map(Bytes).map_err(|e| e.to_string());
+        }
+
+        base64::decode_engine(s.trim_end_matches('='), &BASE64_URL_SAFE_NO_PAD)
+            .map(Bytes)
+            .map_err(|_| "neither base64url, hex nor an @file".into())
+    }
+}
@@ -0,0 +1,153 @@
+//! `key convert`: re-encodes a credential public key in the formats it's kept in, on chain, in
+//! logs and in JS tooling.
+
+use std::{fmt, fs};
+
+use coset::{CborSerializable, CoseKey};
+use p256::{
+    elliptic_curve::sec1::ToEncodedPoint,
+    pkcs8::{DecodePublicKey, EncodePublicKey, LineEnding},
+    PublicKey,
+};
+use serde::Serialize;
+use verifier::{
+    cose_key_from_public_key, sec1_public_key_to_der, sha256_fixed, PublicKeyInput, VerifyError,
+};
+
+use crate::input::Bytes;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum Command {
+    /// Converts a public key from one format to another.
+    Convert(ConvertArgs),
+}
+
+#[derive(clap::ValueEnum, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Format {
+    /// A COSE key, as in attested credential data. Printed as hex.
+    Cose,
+    /// A DER-encoded `SubjectPublicKeyInfo`. Printed as hex.
+    Der,
+    /// A PEM-encoded `SubjectPublicKeyInfo`.
+    Pem,
+    /// A JSON Web Key.
+    Jwk,
+    /// A SEC1 point, printed compressed, as stored on chain. Read compressed or not.
+    Sec1,
+    /// An uncompressed SEC1 point, `04 || x || y`. Printed as hex.
+    Sec1Uncompressed,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ConvertArgs {
+    #[arg(long)]
+    from: Format,
+    #[arg(long)]
+    to: Format,
+    /// Prints the SHA-256 of the DER encoding of the key too, which is the same whatever format
+    /// the key is given in.
+    #[arg(long)]
+    fingerprint: bool,
+    /// The key: base64url, hex or an `@file` for the binary formats, the text or an `@file` for
+    /// PEM and JWK.
+    #[arg(allow_hyphen_values = true)]
+    input: String,
+}
+
+/// A converted key, or why the input isn't one.
+#[derive(Serialize, Debug)]
+pub struct Converted {
+    pub format: Format,
+    pub key: Option<String>,
+    /// Hex-encoded.
+    pub fingerprint: Option<String>,
+    /// The [`VerifyError`] decoding the key failed with, or why the input couldn't be read.
+    pub error: Option<String>,
+}
+
+pub fn run(args: &ConvertArgs) -> Converted {
+    let mut converted = Converted {
+        format: args.to,
+        key: None,
+        fingerprint: None,
+        error: None,
+    };
+    match read(args.from, &args.input) {
+        Ok(public_key) => {
+            converted.key = Some(write(args.to, &public_key));
+            converted.fingerprint = args
+                .fingerprint
+                .then(|| hex::encode(sha256_fixed(&der(&public_key))));
+        }
+        Err(error) => converted.error = Some(error),
+    }
+    converted
+}
+
+fn read(format: Format, input: &str) -> Result<PublicKey, String> {
+    let bytes = |input: &str| input.parse::<Bytes>().map(|Bytes(bytes)| bytes);
+    let text = |input: &str| match input.strip_prefix('@') {
+        Some(path) => fs::read_to_string(path).map_err(|e| format!("can't read {path}: {e}")),
+        None => Ok(input.to_owned()),
+    };
+
+    let public_key = match format {
+        Format::Cose => CoseKey::from_slice(&bytes(input)?)
+            .map_err(VerifyError::from)
+            .and_then(|key| PublicKeyInput::Cose(&key).public_key()),
+        Format::Der => PublicKeyInput::Der(&bytes(input)?).public_key(),
+        Format::Pem => PublicKey::from_public_key_pem(text(input)?.trim()).map_err(Into::into),
+        // Like the library does with the errors of the other encodings.
+        Format::Jwk => {
+            PublicKey::from_jwk_str(&text(input)?).map_err(|_| VerifyError::ExtractPublicKey)
+        }
+        Format::Sec1 => PublicKeyInput::Sec1(&bytes(input)?).public_key(),
+        Format::Sec1Uncompressed => PublicKeyInput::UncompressedPoint(&bytes(input)?).public_key(),
+    };
+    public_key.map_err(|e| format!("{e:?}"))
+}
+
+fn write(format: Format, public_key: &PublicKey) -> String {
+    match format {
+        Format::Cose => hex::encode(
+            cose_key_from_public_key(public_key)
+                .to_vec()
+                .expect("COSE keys of P-256 points encode"),
+        ),
+        Format::Der => hex::encode(der(public_key)),
+        Format::Pem => public_key
+            .to_public_key_pem(LineEnding::LF)
+            .expect("P-256 keys encode as PEM"),
+        Format::Jwk => public_key.to_jwk_string(),
+        Format::Sec1 => hex::encode(public_key.to_encoded_point(true)),
+        Format::Sec1Uncompressed => hex::encode(public_key.to_encoded_point(false)),
+    }
+}
+
+/// The normalized encoding of `public_key`, the one `webauthn_verify` takes.
+fn der(public_key: &PublicKey) -> Vec<u8> {
+    sec1_public_key_to_der(public_key.to_encoded_point(false).as_bytes())
+        .expect("The point of a decoded key is on the curve")
+}
+
+impl fmt::Display for Converted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(key) = &self.key {
+            writeln!(f, "{}", key.trim_end())?;
+        }
+        if let Some(fingerprint) = &self.fingerprint {
+            writeln!(f, "SHA-256 fingerprint  {fingerprint}")?;
+        }
+        if let Some(error) = &self.error {
+            writeln!(f, "Error  {error}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+//! Command line tools for support engineers triaging failed WebAuthn logins.
+//!
+//! Byte inputs are given as base64url, hex or `@path`s of files holding them. Exits with `0`
+//! when every check passed (or the input parsed, converted or generated), `1` when one failed
+//! and `2` on invalid arguments.
+
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+
+mod generate_vector;
+mod input;
+mod key;
+mod parse_authdata;
+mod report;
+mod verify_assertion;
+
+#[derive(Parser, Debug)]
+#[command(name = "webauthn-verifier", version, about)]
+struct Cli {
+    /// Prints a machine-readable JSON report rather than a human-readable one.
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Verifies an assertion, printing what it carries and which checks failed.
+    VerifyAssertion(verify_assertion::Args),
+    /// Decodes an authenticator data field by field.
+    ParseAuthdata(parse_authdata::Args),
+    /// Works with credential public keys.
+    Key(key::Args),
+    /// Generates a registration and an assertion with a deterministic software authenticator,
+    /// as a test fixture.
+    GenerateVector(generate_vector::Args),
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let succeeded = match &cli.command {
+        Command::VerifyAssertion(args) => {
+            let report = verify_assertion::run(args);
+            report::print(&report, cli.json);
+            report.valid
+        }
+        Command::ParseAuthdata(args) => {
+            let dump = parse_authdata::run(args);
+            report::print(&dump, cli.json);
+            dump.error.is_none()
+        }
+        Command::Key(key::Args {
+            command: key::Command::Convert(args),
+        }) => {
+            let converted = key::run(args);
+            report::print(&converted, cli.json);
+            converted.error.is_none()
+        }
+        Command::GenerateVector(args) => match generate_vector::run(args) {
+            Ok(vector) => {
+                let json = serde_json::to_string_pretty(&vector)
+                    .expect("Vectors only have plain data; qed");
+                println!("{json}");
+                true
+            }
+            Err(error) => {
+                eprintln!("error: {error}");
+                return ExitCode::from(2);
+            }
+        },
+    };
+
+    if succeeded {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
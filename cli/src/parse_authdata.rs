@@ -0,0 +1,306 @@
+//! `parse-authdata`: decodes an authenticator data field by field, with the same parser
+//! verification uses, printing as much as parsed when it's truncated or malformed.
+
+use std::fmt;
+
+use coset::{
+    cbor::{de::from_reader, value::Value},
+    AsCborValue,
+};
+use serde::Serialize;
+use verifier::{parse_authenticator_data_partially, AuthenticatorData, PartialAuthenticatorData};
+
+use crate::{input::Bytes, report::FLAGS};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// The `authenticatorData` to decode.
+    auth_data: Bytes,
+}
+
+/// An authenticator data, decoded up to the error if it has one.
+#[derive(Serialize, Debug, Default)]
+pub struct Dump {
+    /// Hex-encoded.
+    pub rp_id_hash: Option<String>,
+    pub flags: Option<u8>,
+    /// Every bit of the flags, by name.
+    pub flag_bits: Vec<FlagBit>,
+    pub sign_count: Option<u32>,
+    pub attested_credential_data: Option<AttestedCredentialDataDump>,
+    /// The extension outputs in CBOR diagnostic notation, or hex if they aren't CBOR.
+    pub extensions: Option<String>,
+    /// The [`VerifyError`](verifier::VerifyError) parsing failed with, past the fields above.
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct FlagBit {
+    pub bit: u8,
+    /// `RFU` for the bits reserved for future use.
+    pub name: &'static str,
+    pub description: &'static str,
+    pub set: bool,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct AttestedCredentialDataDump {
+    /// As a UUID.
+    pub aaguid: Option<String>,
+    /// Hex-encoded.
+    pub credential_id: Option<String>,
+    pub credential_id_len: Option<usize>,
+    /// The fields of the COSE key, in the order they're encoded.
+    pub credential_public_key: Vec<CoseField>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CoseField {
+    /// The label, in CBOR diagnostic notation.
+    pub label: String,
+    /// The name of the label, for those of the key types WebAuthn uses.
+    pub name: Option<&'static str>,
+    pub value: String,
+}
+
+pub fn run(args: &Args) -> Dump {
+    match parse_authenticator_data_partially(&args.auth_data.0) {
+        Ok(authenticator_data) => (&authenticator_data).into(),
+        Err((partial, error)) => Dump {
+            error: Some(format!("{error:?}")),
+            ..(&partial).into()
+        },
+    }
+}
+
+fn flag_bits(flags: u8) -> Vec<FlagBit> {
+    (0..8)
+        .map(|bit| {
+            let (name, description) = FLAGS
+                .into_iter()
+                .find(|(flag, _, _)| flag.bits() == 1 << bit)
+                .map_or(
+                    ("RFU", "reserved for future use"),
+                    |(_, name, description)| (name, description),
+                );
+            FlagBit {
+                bit,
+                name,
+                description,
+                set: flags & 1 << bit != 0,
+            }
+        })
+        .collect()
+}
+
+fn uuid(aaguid: &[u8; 16]) -> String {
+    let hex = hex::encode(aaguid);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+impl From<&PartialAuthenticatorData<'_>> for Dump {
+    fn from(value: &PartialAuthenticatorData<'_>) -> Self {
+        let attested = value.aaguid.is_some() || value.credential_id.is_some();
+        Self {
+            rp_id_hash: value.rp_id_hash.map(hex::encode),
+            flags: value.flags.map(|flags| flags.bits()),
+            flag_bits: value
+                .flags
+                .map(|flags| flag_bits(flags.bits()))
+                .unwrap_or_default(),
+            sign_count: value.sign_count,
+            attested_credential_data: attested.then(|| AttestedCredentialDataDump {
+                aaguid: value.aaguid.as_ref().map(uuid),
+                credential_id: value.credential_id.map(hex::encode),
+                credential_id_len: value.credential_id.map(<[u8]>::len),
+                credential_public_key: Vec::new(),
+            }),
+            extensions: None,
+            error: None,
+        }
+    }
+}
+
+impl From<&AuthenticatorData<'_>> for Dump {
+    fn from(value: &AuthenticatorData<'_>) -> Self {
+        Self {
+            rp_id_hash: Some(hex::encode(value.rp_id_hash)),
+            flags: Some(value.flags.bits()),
+            flag_bits: flag_bits(value.flags.bits()),
+            sign_count: Some(value.sign_count),
+            attested_credential_data: value.attested_credential_data.as_ref().map(|data| {
+                AttestedCredentialDataDump {
+                    aaguid: Some(uuid(&data.aaguid)),
+                    credential_id: Some(hex::encode(data.credential_id)),
+                    credential_id_len: Some(data.credential_id.len()),
+                    credential_public_key: cose_fields(
+                        data.credential_public_key.clone().to_cbor_value(),
+                    ),
+                }
+            }),
+            extensions: value.extensions.map(|mut extensions| {
+                match from_reader::<Value, _>(&mut extensions) {
+                    Ok(value) if extensions.is_empty() => diagnostic(&value),
+                    _ => format!("h'{}' (not a CBOR item)", hex::encode(extensions)),
+                }
+            }),
+            error: None,
+        }
+    }
+}
+
+/// The fields of a COSE key map, named after the labels of
+/// <https://www.iana.org/assignments/cose/cose.xhtml#key-common-parameters> and of the key
+/// type parameters.
+fn cose_fields(key: Result<Value, coset::CoseError>) -> Vec<CoseField> {
+    let Ok(Value::Map(fields)) = key else {
+        return Vec::new();
+    };
+    let kty = fields
+        .iter()
+        .find(|(label, _)| label.as_integer() == Some(1.into()))
+        .and_then(|(_, kty)| kty.as_integer())
+        .and_then(|kty| i64::try_from(kty).ok());
+
+    fields
+        .iter()
+        .map(|(label, value)| {
+            let label_id = label.as_integer().and_then(|l| i64::try_from(l).ok());
+            let value_id = value.as_integer().and_then(|v| i64::try_from(v).ok());
+            let (name, value_name) = match (label_id, kty) {
+                (Some(1), _) => (Some("kty"), value_id.and_then(key_type_name)),
+                (Some(2), _) => (Some("kid"), None),
+                (Some(3), _) => (Some("alg"), value_id.and_then(algorithm_name)),
+                (Some(4), _) => (Some("key_ops"), None),
+                (Some(5), _) => (Some("Base IV"), None),
+                // EC2 and OKP keys.
+                (Some(-1), Some(1 | 2)) => (Some("crv"), value_id.and_then(curve_name)),
+                (Some(-2), Some(1 | 2)) => (Some("x"), None),
+                (Some(-3), Some(2)) => (Some("y"), None),
+                // RSA keys.
+                (Some(-1), Some(3)) => (Some("n"), None),
+                (Some(-2), Some(3)) => (Some("e"), None),
+                _ => (None, None),
+            };
+            CoseField {
+                label: diagnostic(label),
+                name,
+                value: match value_name {
+                    Some(value_name) => format!("{value_name} ({})", diagnostic(value)),
+                    None => diagnostic(value),
+                },
+            }
+        })
+        .collect()
+}
+
+fn key_type_name(kty: i64) -> Option<&'static str> {
+    Some(match kty {
+        1 => "OKP",
+        2 => "EC2",
+        3 => "RSA",
+        _ => return None,
+    })
+}
+
+fn algorithm_name(alg: i64) -> Option<&'static str> {
+    Some(match alg {
+        -7 => "ES256",
+        -8 => "EdDSA",
+        -35 => "ES384",
+        -36 => "ES512",
+        -257 => "RS256",
+        _ => return None,
+    })
+}
+
+fn curve_name(crv: i64) -> Option<&'static str> {
+    Some(match crv {
+        1 => "P-256",
+        2 => "P-384",
+        3 => "P-521",
+        6 => "Ed25519",
+        _ => return None,
+    })
+}
+
+/// `value` in CBOR diagnostic notation (RFC 8949, section 8).
+fn diagnostic(value: &Value) -> String {
+    let join = |values: &mut dyn Iterator<Item = String>| values.collect::<Vec<_>>().join(", ");
+    match value {
+        Value::Integer(integer) => i128::from(*integer).to_string(),
+        Value::Bytes(bytes) => format!("h'{}'", hex::encode(bytes)),
+        Value::Float(float) => float.to_string(),
+        Value::Text(text) => format!("{text:?}"),
+        Value::Bool(bool) => bool.to_string(),
+        Value::Null => "null".into(),
+        Value::Tag(tag, value) => format!("{tag}({})", diagnostic(value)),
+        Value::Array(values) => format!("[{}]", join(&mut values.iter().map(diagnostic))),
+        Value::Map(entries) => format!(
+            "{{{}}}",
+            join(&mut entries.iter().map(|(key, value)| format!(
+                "{}: {}",
+                diagnostic(key),
+                diagnostic(value)
+            )))
+        ),
+        _ => "undefined".into(),
+    }
+}
+
+impl fmt::Display for Dump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(rp_id_hash) = &self.rp_id_hash {
+            writeln!(f, "RP ID hash      {rp_id_hash}")?;
+        }
+        if let Some(flags) = self.flags {
+            writeln!(f, "Flags           {flags:#04x}")?;
+            for bit in &self.flag_bits {
+                writeln!(
+                    f,
+                    "  bit {}  {:<4} {:<26} {}",
+                    bit.bit,
+                    bit.name,
+                    bit.description,
+                    if bit.set { "set" } else { "-" }
+                )?;
+            }
+        }
+        if let Some(sign_count) = self.sign_count {
+            writeln!(f, "Sign count      {sign_count}")?;
+        }
+        if let Some(data) = &self.attested_credential_data {
+            writeln!(f, "Attested credential data")?;
+            if let Some(aaguid) = &data.aaguid {
+                writeln!(f, "  AAGUID          {aaguid}")?;
+            }
+            if let (Some(id), Some(len)) = (&data.credential_id, data.credential_id_len) {
+                writeln!(f, "  Credential ID   {id} ({len} bytes)")?;
+            }
+            if !data.credential_public_key.is_empty() {
+                writeln!(f, "  Public key")?;
+            }
+            for field in &data.credential_public_key {
+                let label = match field.name {
+                    Some(name) => format!("{name} ({})", field.label),
+                    None => field.label.clone(),
+                };
+                writeln!(f, "    {label:<14}{}", field.value)?;
+            }
+        }
+        if let Some(extensions) = &self.extensions {
+            writeln!(f, "Extensions      {extensions}")?;
+        }
+        if let Some(error) = &self.error {
+            writeln!(f, "Error           {error}, after the fields above")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+//! What the commands found, printed for humans or as JSON.
+
+use std::fmt;
+
+use serde::Serialize;
+use verifier::{AuthenticatorData, AuthenticatorDataFlags, ClientDataLite, VerifyError};
+
+/// The outcome of a command: what it parsed, and the checks it ran.
+#[derive(Serialize, Debug, Default)]
+pub struct Report {
+    /// Whether every check passed.
+    pub valid: bool,
+    pub authenticator_data: Option<AuthenticatorDataReport>,
+    pub client_data: Option<ClientDataReport>,
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    /// Records the result of the check `name`.
+    pub fn check(&mut self, name: &'static str, result: Result<(), VerifyError>) {
+        self.checks.push(Check {
+            name,
+            error: result.err().map(|e| format!("{e:?}")),
+        });
+    }
+
+    /// Records the check `name`, which passes if `expected` is what was `found`.
+    pub fn compare(&mut self, name: &'static str, expected: &str, found: &str) {
+        self.checks.push(Check {
+            name,
+            error: (expected != found).then(|| format!("expected {expected}, found {found}")),
+        });
+    }
+
+    /// Sets [`valid`](Self::valid) from the checks run, and returns the report.
+    pub fn finish(mut self) -> Self {
+        self.valid = !self.checks.is_empty() && self.checks.iter().all(|c| c.error.is_none());
+        self
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct Check {
+    pub name: &'static str,
+    /// The [`VerifyError`] the check failed with, if it did.
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct AuthenticatorDataReport {
+    /// Hex-encoded.
+    pub rp_id_hash: String,
+    pub flags: u8,
+    /// The names of the flags set, e.g. `UP` for user present.
+    pub flag_names: Vec<&'static str>,
+    pub sign_count: u32,
+}
+
+/// The authenticator data flags, with their abbreviated and full names.
+pub const FLAGS: [(AuthenticatorDataFlags, &str, &str); 6] = [
+    (AuthenticatorDataFlags::USER_PRESENT, "UP", "user present"),
+    (AuthenticatorDataFlags::USER_VERIFIED, "UV", "user verified"),
+    (
+        AuthenticatorDataFlags::BACKUP_ELIGIBLE,
+        "BE",
+        "backup eligible",
+    ),
+    (AuthenticatorDataFlags::BACKED_UP, "BS", "backed up"),
+    (
+        AuthenticatorDataFlags::ATTESTED_CREDENTIAL_DATA,
+        "AT",
+        "attested credential data",
+    ),
+    (
+        AuthenticatorDataFlags::EXTENSION_DATA,
+        "ED",
+        "extension data",
+    ),
+];
+
+impl From<&AuthenticatorData<'_>> for AuthenticatorDataReport {
+    fn from(value: &AuthenticatorData<'_>) -> Self {
+        Self {
+            rp_id_hash: hex::encode(value.rp_id_hash),
+            flags: value.flags.bits(),
+            flag_names: FLAGS
+                .into_iter()
+                .filter_map(|(flag, name, _)| value.flags.contains(flag).then_some(name))
+                .collect(),
+            sign_count: value.sign_count,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct ClientDataReport {
+    pub r#type: String,
+    /// Base64url-encoded, as in the client data.
+    pub challenge: String,
+    pub origin: String,
+    pub cross_origin: bool,
+}
+
+impl From<&ClientDataLite<'_>> for ClientDataReport {
+    fn from(value: &ClientDataLite<'_>) -> Self {
+        Self {
+            r#type: value.r#type.into(),
+            challenge: value.challenge.into(),
+            origin: value.origin.into(),
+            cross_origin: value.cross_origin,
+        }
+    }
+}
+
+/// Prints `report` to stdout, as JSON when `json` is set.
+pub fn print(report: &(impl Serialize + fmt::Display), json: bool) {
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(report).expect("Reports only have plain data; qed")
+        );
+    } else {
+        print!("{report}");
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(data) = &self.authenticator_data {
+            writeln!(f, "Authenticator data")?;
+            writeln!(f, "  RP ID hash    {}", data.rp_id_hash)?;
+            writeln!(
+                f,
+                "  Flags         {:#04x} ({})",
+                data.flags,
+                data.flag_names.join(", ")
+            )?;
+            writeln!(f, "  Sign count    {}", data.sign_count)?;
+        }
+        if let Some(data) = &self.client_data {
+            writeln!(f, "Client data")?;
+            writeln!(f, "  Type          {}", data.r#type)?;
+            writeln!(f, "  Challenge     {}", data.challenge)?;
+            writeln!(f, "  Origin        {}", data.origin)?;
+            writeln!(f, "  Cross origin  {}", data.cross_origin)?;
+        }
+        writeln!(f, "Checks")?;
+        for check in &self.checks {
+            match &check.error {
+                None => writeln!(f, "  ok      {}", check.name)?,
+                Some(error) => writeln!(f, "  FAILED  {}: {error}", check.name)?,
+            }
+        }
+        writeln!(f, "{}", if self.valid { "Valid" } else { "Invalid" })
+    }
+}
@@ -0,0 +1,104 @@
+//! `verify-assertion`: runs every check of an assertion, rather than stopping at the first
+//! failure, to tell what's wrong with a failed login.
+
+use coset::{CborSerializable, CoseKey};
+use verifier::{
+    parse_authenticator_data, sha256_fixed, verify_challenge, webauthn_verify_public_key,
+    ClientDataLite, PublicKeyInput, VerifyError, VerifyLimits,
+};
+
+use crate::{input::Bytes, report::Report};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// The `authenticatorData` of the response.
+    #[arg(long)]
+    auth_data: Bytes,
+    /// The `clientDataJSON` of the response.
+    #[arg(long)]
+    client_data: Bytes,
+    /// The DER-encoded ECDSA signature of the response.
+    #[arg(long)]
+    signature: Bytes,
+    /// The credential public key: a DER `SubjectPublicKeyInfo`, a SEC1 point or a COSE key.
+    #[arg(long)]
+    public_key: Bytes,
+    /// The origin the client data must have been collected at.
+    #[arg(long)]
+    origin: Option<String>,
+    /// The RP ID the authenticator data must be scoped to.
+    #[arg(long)]
+    rp_id: Option<String>,
+    /// The challenge issued for the ceremony.
+    #[arg(long)]
+    challenge: Option<Bytes>,
+}
+
+/// Tells a DER, SEC1 or COSE key apart by its first byte: a DER `SEQUENCE`, a SEC1 point tag,
+/// or anything else for a CBOR map.
+fn public_key_input<'a>(
+    key: &'a [u8],
+    cose: &'a mut Option<CoseKey>,
+) -> Result<PublicKeyInput<'a>, VerifyError> {
+    Ok(match key.first() {
+        Some(0x30) => PublicKeyInput::Der(key),
+        Some(0x02..=0x04) => PublicKeyInput::Sec1(key),
+        _ => PublicKeyInput::Cose(cose.insert(CoseKey::from_slice(key)?)),
+    })
+}
+
+pub fn run(args: &Args) -> Report {
+    let mut report = Report::default();
+
+    let authenticator_data = parse_authenticator_data(&args.auth_data.0);
+    report.authenticator_data = authenticator_data.as_ref().ok().map(Into::into);
+    report.check("authenticator data", authenticator_data.map(|_| ()));
+
+    let mut unescape_buffer = vec![0; args.client_data.0.len()];
+    let client_data = ClientDataLite::parse(
+        &args.client_data.0,
+        &mut unescape_buffer,
+        &VerifyLimits::DEFAULT,
+    );
+    report.client_data = client_data.as_ref().ok().map(Into::into);
+    report.check("client data", client_data.map(|_| ()));
+
+    let mut cose = None;
+    report.check(
+        "signature",
+        public_key_input(&args.public_key.0, &mut cose).and_then(|public_key| {
+            webauthn_verify_public_key(
+                &args.auth_data.0,
+                &args.client_data.0,
+                &args.signature.0,
+                public_key,
+            )
+        }),
+    );
+
+    if let Some(rp_id) = &args.rp_id {
+        let expected = hex::encode(sha256_fixed(rp_id.as_bytes()));
+        match report
+            .authenticator_data
+            .as_ref()
+            .map(|data| data.rp_id_hash.clone())
+        {
+            Some(found) => report.compare("RP ID hash", &expected, &found),
+            None => report.check("RP ID hash", Err(VerifyError::ParseAuthenticatorData)),
+        }
+    }
+    if let Some(origin) = &args.origin {
+        match report.client_data.as_ref().map(|data| data.origin.clone()) {
+            Some(found) => report.compare("origin", origin, &found),
+            None => report.check("origin", Err(VerifyError::ParseClientData)),
+        }
+    }
+    if let Some(challenge) = &args.challenge {
+        report.check(
+            "challenge",
+            verify_challenge(&args.client_data.0, &challenge.0),
+        );
+    }
+
+    report.finish()
+}
@@ -0,0 +1,96 @@
+//! Feeds the vectors of `generate-vector` back to `verify-assertion`.
+
+use assert_cmd::Command;
+use predicates::str::contains;
+use serde_json::Value;
+
+const RP_ID: &str = "example.com";
+const ORIGIN: &str = "https://example.com";
+const CHALLENGE: &str = "BwcHBwcHBw";
+
+fn generate(extra_args: &[&str]) -> Value {
+    let output = Command::cargo_bin("webauthn-verifier")
+        .unwrap()
+        .args(["generate-vector", "--rp-id", RP_ID, "--origin", ORIGIN])
+        .args(["--challenge", CHALLENGE])
+        .args(extra_args)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    serde_json::from_slice(&output.stdout).expect("The vector is JSON")
+}
+
+fn verify(vector: &Value) -> Command {
+    let field = |name: &str| vector[name].as_str().unwrap().to_owned();
+    let mut command = Command::cargo_bin("webauthn-verifier").unwrap();
+    command
+        .arg("verify-assertion")
+        .args(["--auth-data", &field("authenticator_data")])
+        .args(["--client-data", &field("client_data_json")])
+        .args(["--signature", &field("signature")])
+        .args(["--public-key", &field("public_key")])
+        .args([
+            "--rp-id",
+            RP_ID,
+            "--origin",
+            ORIGIN,
+            "--challenge",
+            CHALLENGE,
+        ]);
+    command
+}
+
+#[test]
+fn vectors_verify() {
+    let vector = generate(&[]);
+    assert_eq!(vector["expected"], "ok");
+    assert_eq!(vector["policy"]["expected_challenge"], CHALLENGE);
+    assert_eq!(vector["parsed"]["flags"], 5);
+    verify(&vector).assert().success();
+}
+
+#[test]
+fn invalid_vectors_fail_as_requested() {
+    let vector = generate(&["--invalid", "signature"]);
+    assert_eq!(vector["expected"]["error"], "VerifySignature");
+    verify(&vector)
+        .assert()
+        .code(1)
+        .stdout(contains("FAILED  signature: VerifySignature"));
+
+    let vector = generate(&["--invalid", "challenge"]);
+    assert_eq!(vector["expected"]["error"], "ChallengeMismatch");
+    verify(&vector)
+        .assert()
+        .code(1)
+        .stdout(contains("FAILED  challenge"));
+
+    verify(&generate(&["--invalid", "origin"]))
+        .assert()
+        .code(1)
+        .stdout(contains("FAILED  origin"));
+}
+
+#[test]
+fn vectors_are_deterministic() {
+    assert_eq!(generate(&[]), generate(&[]));
+
+    let seed = "01".repeat(32);
+    let seeded = generate(&["--seed", &seed]);
+    assert_eq!(seeded, generate(&["--seed", &seed]));
+    assert_ne!(seeded["public_key"], generate(&[])["public_key"]);
+    verify(&seeded).assert().success();
+}
+
+#[test]
+fn unsupported_arguments_are_rejected() {
+    let mut command = Command::cargo_bin("webauthn-verifier").unwrap();
+    command
+        .args(["generate-vector", "--rp-id", RP_ID, "--origin", ORIGIN])
+        .args(["--challenge", CHALLENGE]);
+    command
+        .args(["--alg", "eddsa"])
+        .assert()
+        .code(2)
+        .stderr(contains("EdDSA"));
+}
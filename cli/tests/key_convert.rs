@@ -0,0 +1,128 @@
+//! Drives `key convert` between every pair of formats.
+
+use assert_cmd::Command;
+use predicates::str::contains;
+use serde_json::Value;
+
+const FORMATS: [&str; 6] = ["cose", "der", "pem", "jwk", "sec1", "sec1-uncompressed"];
+
+/// The ES256 credential public key of the `software_es256_*` fixtures.
+const ES256_DER: &str = "MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAE-2tLrNKRZP1Vscjl0lWhO6q3TgXwHauen1By7mOj_YKf6zA2KM0E9jcPgtsNkhr656wy6Z-dr1nF0wlozvj39Q";
+
+/// The COSE key of [`ES256_DER`], without its `alg`.
+const NO_ALGORITHM: &str = "a401022001215820fb6b4bacd29164fd55b1c8e5d255a13baab74e05f01dab9e9f5072ee63a3fd822258209feb303628cd04f6370f82db0d921afae7ac32e99f9daf59c5d30968cef8f7f5";
+
+/// The Ed25519 public key of RFC 8037, appendix A.2, in the formats it has.
+const ED25519: [(&str, &str); 4] = [
+    (
+        "cose",
+        "a4010103272006215820d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+    ),
+    (
+        "der",
+        "302a300506032b6570032100d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+    ),
+    (
+        "pem",
+        "-----BEGIN PUBLIC KEY-----\nMCowBQYDK2VwAyEA11qYAYKxCrfVS/7TyWQHOg7hcvPapiMlrwIaaPcHURo=\n-----END PUBLIC KEY-----\n",
+    ),
+    (
+        "jwk",
+        r#"{"kty":"OKP","crv":"Ed25519","x":"11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"}"#,
+    ),
+];
+
+fn convert(from: &str, to: &str, input: &str) -> Command {
+    let mut command = Command::cargo_bin("webauthn-verifier").unwrap();
+    command
+        .args(["key", "convert", "--from", from, "--to", to])
+        .arg(input);
+    command
+}
+
+fn json_report(command: &mut Command) -> Value {
+    let output = command.args(["--json", "--fingerprint"]).output().unwrap();
+    serde_json::from_slice(&output.stdout).expect("The report is JSON")
+}
+
+#[test]
+fn es256_keys_round_trip_through_every_format() {
+    let encodings: Vec<(&str, Value)> = FORMATS
+        .into_iter()
+        .map(|format| (format, json_report(&mut convert("der", format, ES256_DER))))
+        .collect();
+
+    for (from, from_report) in &encodings {
+        let input = from_report["key"].as_str().unwrap();
+        for (to, to_report) in &encodings {
+            let report = json_report(&mut convert(from, to, input));
+            assert_eq!(report["error"], Value::Null, "{from} to {to}");
+            assert_eq!(report["key"], to_report["key"], "{from} to {to}");
+            assert_eq!(
+                report["fingerprint"], to_report["fingerprint"],
+                "{from} to {to}"
+            );
+        }
+    }
+}
+
+#[test]
+fn keys_are_printed_in_the_requested_format() {
+    convert("der", "sec1", ES256_DER)
+        .arg("--fingerprint")
+        .assert()
+        .success()
+        .stdout(
+            "03fb6b4bacd29164fd55b1c8e5d255a13baab74e05f01dab9e9f5072ee63a3fd82\n\
+             SHA-256 fingerprint  24ee88ae4a7ad3c741fa5d096788c209e3a1e5df74509a4b58cc8d12976624fc\n",
+        );
+    convert("der", "jwk", ES256_DER)
+        .assert()
+        .success()
+        .stdout(contains(r#""kty":"EC","crv":"P-256""#));
+    convert("der", "pem", ES256_DER)
+        .assert()
+        .success()
+        .stdout(contains(
+            "-----BEGIN PUBLIC KEY-----\nMFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAE+2tL",
+        ));
+}
+
+/// The library only verifies ES256, so Ed25519 keys fail to decode, with the error verifying
+/// with them would.
+#[test]
+fn ed25519_keys_are_rejected_with_the_library_error() {
+    for (from, input) in ED25519 {
+        let error = if from == "cose" {
+            "UnsupportedAlgorithm"
+        } else {
+            "ExtractPublicKey"
+        };
+        for to in FORMATS {
+            convert(from, to, input)
+                .assert()
+                .code(1)
+                .stdout(format!("Error  {error}\n"));
+        }
+    }
+}
+
+#[test]
+fn invalid_keys_are_rejected() {
+    // Not on the curve.
+    let off_curve = format!("04{}", "01".repeat(64));
+    convert("sec1-uncompressed", "der", &off_curve)
+        .assert()
+        .code(1)
+        .stdout("Error  ExtractPublicKey\n");
+    // A COSE key without its `alg`.
+    convert("cose", "der", NO_ALGORITHM)
+        .assert()
+        .code(1)
+        .stdout("Error  MissingAlgorithm\n");
+    convert("der", "pem", "not a key!")
+        .assert()
+        .code(1)
+        .stdout("Error  neither base64url, hex nor an @file\n");
+    convert("der", "bogus", ES256_DER).assert().code(2);
+}
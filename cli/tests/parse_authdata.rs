@@ -0,0 +1,75 @@
+//! Snapshots of `parse-authdata` over the fixtures `pass-webauthn` replays, and a registration.
+//!
+//! Run with `UPDATE_SNAPSHOTS=1` to rewrite the snapshots after a deliberate change of output.
+
+use std::{env, fs, path::PathBuf};
+
+use assert_cmd::Command;
+use serde_json::Value;
+
+/// A registration's authenticator data, with an ES256 key and the `credProtect` extension.
+const REGISTRATION: &str = "a379a6f6eeafb9a55e378c118034e2751e682fab9f2d30ab13d2125586ce1947c5\
+    00000000adce000235bcc60a648b0b25f1f0550300100102030405060708090a0b0c0d0e0f10a5010203262001\
+    2158200217e617f0b6443928278f96999e69a23a4f2c152bdf6d6cdf66e5b80282d4ed225820194a7debcb9771\
+    2d2dda3ca85aa8765a56f45fc758599652f2897c65306e5794a16b6372656450726f7465637402";
+
+fn manifest_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Runs `parse-authdata` on `input`, checking its output is the snapshot `name` and that it
+/// exits with `code`.
+fn assert_snapshot(name: &str, input: &str, json: bool, code: i32) {
+    let mut command = Command::cargo_bin("webauthn-verifier").unwrap();
+    command.arg("parse-authdata").arg(input);
+    if json {
+        command.arg("--json");
+    }
+    let output = command.output().unwrap();
+    assert_eq!(output.status.code(), Some(code), "{name}");
+
+    let path = manifest_dir()
+        .join("tests/snapshots/parse_authdata")
+        .join(name)
+        .with_extension(if json { "json" } else { "txt" });
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, &stdout).unwrap();
+    }
+    let snapshot =
+        fs::read_to_string(&path).unwrap_or_else(|_| panic!("No snapshot at {}", path.display()));
+    assert_eq!(stdout, snapshot, "{name}");
+}
+
+#[test]
+fn fixtures() {
+    let fixtures = manifest_dir().join("../pass-webauthn/tests/fixtures");
+    let mut entries: Vec<_> = fs::read_dir(&fixtures)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    entries.sort();
+    assert!(!entries.is_empty());
+
+    for path in entries {
+        let fixture: Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        let name = path.file_stem().unwrap().to_str().unwrap();
+        let authenticator_data = fixture["authenticator_data"].as_str().unwrap();
+        assert_snapshot(name, authenticator_data, false, 0);
+        assert_snapshot(name, authenticator_data, true, 0);
+    }
+}
+
+#[test]
+fn registration() {
+    assert_snapshot("registration", REGISTRATION, false, 0);
+    assert_snapshot("registration", REGISTRATION, true, 0);
+}
+
+#[test]
+fn truncated_input_prints_what_parsed() {
+    // Cut in the middle of the public key's `x` coordinate.
+    let truncated = &REGISTRATION[..2 * 120];
+    assert_snapshot("truncated", truncated, false, 1);
+    assert_snapshot("truncated", truncated, true, 1);
+}
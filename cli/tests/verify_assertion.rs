@@ -0,0 +1,117 @@
+//! Drives `verify-assertion` with the fixtures `pass-webauthn` replays.
+
+use std::{fs, path::PathBuf};
+
+use assert_cmd::Command;
+use predicates::str::contains;
+use serde_json::Value;
+
+fn fixture(name: &str) -> Value {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../pass-webauthn/tests/fixtures")
+        .join(name)
+        .with_extension("json");
+    serde_json::from_slice(&fs::read(&path).expect("The fixture is checked in"))
+        .expect("Fixtures are valid JSON")
+}
+
+/// The command verifying the assertion of the fixture `name`.
+fn verify_assertion(name: &str) -> Command {
+    let fixture = fixture(name);
+    let mut command = Command::cargo_bin("webauthn-verifier").unwrap();
+    command.arg("verify-assertion");
+    for (arg, member) in [
+        ("--auth-data", "authenticator_data"),
+        ("--client-data", "client_data_json"),
+        ("--signature", "signature"),
+        ("--public-key", "public_key"),
+    ] {
+        command.arg(arg).arg(fixture[member].as_str().unwrap());
+    }
+    command
+}
+
+fn json_report(command: &mut Command) -> Value {
+    let output = command.arg("--json").output().unwrap();
+    serde_json::from_slice(&output.stdout).expect("The report is JSON")
+}
+
+#[test]
+fn valid_assertions_pass() {
+    verify_assertion("software_es256_user_verified")
+        .args(["--rp-id", "example.com", "--origin", "https://example.com"])
+        .args(["--challenge", "BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc"])
+        .assert()
+        .success()
+        .stdout(contains("Flags         0x05 (UP, UV)"));
+}
+
+#[test]
+fn failed_checks_are_reported() {
+    verify_assertion("software_es256_corrupted_signature")
+        .assert()
+        .code(1)
+        .stdout(contains("FAILED  signature: VerifySignature"));
+
+    let report = json_report(
+        verify_assertion("software_es256_wrong_challenge")
+            .args(["--challenge", "BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc"])
+            .args(["--rp-id", "example.org"]),
+    );
+    assert_eq!(report["valid"], false);
+    assert_eq!(report["authenticator_data"]["sign_count"], 1);
+    let error = |name: &str| {
+        report["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|check| check["name"] == name)
+            .map(|check| check["error"].clone())
+            .expect("The check ran")
+    };
+    assert_eq!(error("signature"), Value::Null);
+    assert_eq!(error("challenge"), "ChallengeMismatch");
+    assert!(error("RP ID hash")
+        .as_str()
+        .unwrap()
+        .starts_with("expected "));
+}
+
+#[test]
+fn inputs_are_read_as_hex_or_from_files() {
+    let fixture = fixture("software_es256_user_verified");
+    let bytes = |member: &str| {
+        base64::decode_engine(
+            fixture[member].as_str().unwrap(),
+            &base64::prelude::BASE64_URL_SAFE_NO_PAD,
+        )
+        .unwrap()
+    };
+    let client_data = std::env::temp_dir().join("verify_assertion_client_data.json");
+    fs::write(&client_data, bytes("client_data_json")).unwrap();
+
+    Command::cargo_bin("webauthn-verifier")
+        .unwrap()
+        .arg("verify-assertion")
+        .args(["--auth-data", &hex::encode(bytes("authenticator_data"))])
+        .args(["--client-data", &format!("@{}", client_data.display())])
+        .args([
+            "--signature",
+            &format!("0x{}", hex::encode(bytes("signature"))),
+        ])
+        .args(["--public-key", fixture["public_key"].as_str().unwrap()])
+        .assert()
+        .success();
+}
+
+#[test]
+fn invalid_arguments_exit_with_2() {
+    verify_assertion("software_es256_user_verified")
+        .args(["--challenge", "not base64url!"])
+        .assert()
+        .code(2);
+    verify_assertion("software_es256_user_verified")
+        .args(["--challenge", "@/does/not/exist"])
+        .assert()
+        .code(2);
+}
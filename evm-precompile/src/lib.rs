@@ -0,0 +1,146 @@
+//! A Frontier precompile exposing [`verifier::webauthn_verify`] to EVM contracts, so wallets can
+//! check passkey signatures without verifying P-256 signatures in Solidity.
+//!
+//! It implements a single function:
+//!
+//! ```solidity
+//! function webauthnVerify(
+//!     bytes authData,
+//!     bytes clientDataJSON,
+//!     bytes sig,
+//!     bytes pubkey
+//! ) external view returns (bool);
+//! ```
+//!
+//! where `sig` is the DER-encoded ECDSA signature and `pubkey` the DER-encoded P-256 public key.
+//! A signature that doesn't verify returns `false`; calls that can't be decoded fail with an error.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use fp_evm::{
+    ExitError, ExitSucceed, Precompile, PrecompileFailure, PrecompileHandle, PrecompileOutput,
+    PrecompileResult,
+};
+use verifier::webauthn_verify;
+
+#[cfg(test)]
+mod tests;
+
+const LOG_TARGET: &str = "webauthn::evm_precompile";
+
+/// `bytes4(keccak256("webauthnVerify(bytes,bytes,bytes,bytes)"))`.
+pub const WEBAUTHN_VERIFY_SELECTOR: [u8; 4] = [0xab, 0x43, 0xcb, 0x98];
+
+/// Longest call data accepted, well above any real assertion.
+pub const MAX_INPUT_LEN: usize = 4096;
+
+/// Gas of verifying a signature, regardless of the input size. Matches the cost of the
+/// `P256VERIFY` precompile of EIP-7212.
+pub const BASE_GAS: u64 = 3_450;
+
+/// Gas per 32 byte word of call data, as the SHA-256 precompile charges for hashing.
+pub const WORD_GAS: u64 = 12;
+
+/// The gas charged for a call with `input_len` bytes of call data.
+pub fn gas_cost(input_len: usize) -> u64 {
+    BASE_GAS.saturating_add(WORD_GAS.saturating_mul(input_len.div_ceil(32) as u64))
+}
+
+/// Why call data couldn't be decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputError {
+    /// The call data is longer than [`MAX_INPUT_LEN`].
+    TooLarge,
+    /// The selector isn't [`WEBAUTHN_VERIFY_SELECTOR`].
+    UnknownSelector,
+    /// The arguments aren't four ABI-encoded `bytes`.
+    Malformed,
+}
+
+impl From<InputError> for PrecompileFailure {
+    fn from(value: InputError) -> Self {
+        let reason = match value {
+            InputError::TooLarge => "input too large",
+            InputError::UnknownSelector => "unknown selector",
+            InputError::Malformed => "malformed input",
+        };
+        PrecompileFailure::Error {
+            exit_status: ExitError::Other(reason.into()),
+        }
+    }
+}
+
+/// Reads the big-endian word at `offset` as a `usize`.
+fn word_at(data: &[u8], offset: usize) -> Result<usize, InputError> {
+    let word = data
+        .get(offset..offset.checked_add(32).ok_or(InputError::Malformed)?)
+        .ok_or(InputError::Malformed)?;
+    let (high, low) = word.split_at(24);
+    if high.iter().any(|&b| b != 0) {
+        return Err(InputError::Malformed);
+    }
+    usize::try_from(u64::from_be_bytes(
+        low.try_into().expect("Split at 24 of 32 bytes; qed"),
+    ))
+    .map_err(|_| InputError::Malformed)
+}
+
+/// Reads the `index`-th dynamic `bytes` argument of `args`.
+fn bytes_arg(args: &[u8], index: usize) -> Result<&[u8], InputError> {
+    let offset = word_at(args, index * 32)?;
+    let len = word_at(args, offset)?;
+    let start = offset + 32;
+    args.get(start..start.checked_add(len).ok_or(InputError::Malformed)?)
+        .ok_or(InputError::Malformed)
+}
+
+/// Decodes the call data of `webauthnVerify` into its four arguments.
+pub fn decode_input(input: &[u8]) -> Result<[&[u8]; 4], InputError> {
+    if input.len() > MAX_INPUT_LEN {
+        return Err(InputError::TooLarge);
+    }
+    let args = input
+        .strip_prefix(&WEBAUTHN_VERIFY_SELECTOR)
+        .ok_or(InputError::UnknownSelector)?;
+
+    Ok([
+        bytes_arg(args, 0)?,
+        bytes_arg(args, 1)?,
+        bytes_arg(args, 2)?,
+        bytes_arg(args, 3)?,
+    ])
+}
+
+/// ABI-encodes a `bool`.
+fn encode_bool(value: bool) -> Vec<u8> {
+    let mut word = [0u8; 32];
+    word[31] = value.into();
+    word.to_vec()
+}
+
+/// The precompile, to add to the `PrecompileSet` of the runtime at an address of choice.
+pub struct WebAuthnPrecompile;
+
+impl Precompile for WebAuthnPrecompile {
+    fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+        let input_len = handle.input().len();
+        if input_len > MAX_INPUT_LEN {
+            return Err(InputError::TooLarge.into());
+        }
+        handle.record_cost(gas_cost(input_len))?;
+
+        let [authenticator_data, client_data_json, signature, public_key] =
+            decode_input(handle.input())?;
+        let verified = webauthn_verify(authenticator_data, client_data_json, signature, public_key)
+            .inspect_err(|e| log::debug!(target: LOG_TARGET, "Verification failed: {e:?}"))
+            .is_ok();
+
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            output: encode_bool(verified),
+        })
+    }
+}
@@ -0,0 +1,114 @@
+use super::*;
+use fp_evm::Context;
+use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+use precompile_utils::testing::MockHandle;
+use rand::rngs::OsRng;
+use sp_core::{H160, U256};
+use verifier::{client_data_hash, sec1_public_key_to_der};
+
+const AUTHENTICATOR_DATA: &[u8] = b"example authenticator data";
+const CLIENT_DATA_JSON: &[u8] = br#"{"type":"webauthn.get","challenge":"dGVzdA","origin":"o"}"#;
+
+/// ABI-encodes a `webauthnVerify` call.
+fn call_data(args: [&[u8]; 4]) -> Vec<u8> {
+    let word = |value: usize| {
+        let mut word = [0u8; 32];
+        word[24..].copy_from_slice(&(value as u64).to_be_bytes());
+        word
+    };
+
+    let mut head = Vec::new();
+    let mut tail = Vec::new();
+    for arg in args {
+        head.extend(word(4 * 32 + tail.len()));
+        tail.extend(word(arg.len()));
+        tail.extend(arg);
+        tail.resize(tail.len().next_multiple_of(32), 0);
+    }
+    [&WEBAUTHN_VERIFY_SELECTOR[..], &head, &tail].concat()
+}
+
+fn signed_call_data(authenticator_data: &[u8]) -> Vec<u8> {
+    let signing_key = SigningKey::random(&mut OsRng);
+    let public_key = signing_key.verifying_key().to_encoded_point(false);
+    let signature: Signature =
+        signing_key.sign(&[AUTHENTICATOR_DATA, &client_data_hash(CLIENT_DATA_JSON)].concat());
+
+    call_data([
+        authenticator_data,
+        CLIENT_DATA_JSON,
+        signature.to_der().as_bytes(),
+        &sec1_public_key_to_der(public_key.as_bytes()).unwrap(),
+    ])
+}
+
+fn execute(input: Vec<u8>) -> (PrecompileResult, u64) {
+    let mut handle = MockHandle::new(
+        H160::repeat_byte(0x57),
+        Context {
+            address: H160::repeat_byte(0x57),
+            caller: H160::repeat_byte(0x01),
+            apparent_value: U256::zero(),
+        },
+    );
+    handle.input = input;
+    let result = WebAuthnPrecompile::execute(&mut handle);
+    (result, handle.gas_used)
+}
+
+#[test]
+fn valid_assertion_returns_true() {
+    let input = signed_call_data(AUTHENTICATOR_DATA);
+    let gas = gas_cost(input.len());
+
+    let (result, gas_used) = execute(input);
+    assert_eq!(result.map(|output| output.output), Ok(encode_bool(true)));
+    assert_eq!(gas_used, gas);
+}
+
+#[test]
+fn invalid_assertion_returns_false() {
+    let input = signed_call_data(b"altered authenticator data");
+    let gas = gas_cost(input.len());
+
+    let (result, gas_used) = execute(input);
+    assert_eq!(result.map(|output| output.output), Ok(encode_bool(false)));
+    assert_eq!(gas_used, gas);
+}
+
+#[test]
+fn undecodable_calls_fail() {
+    let input = signed_call_data(AUTHENTICATOR_DATA);
+
+    assert_eq!(
+        decode_input(&input[..input.len() - 64]),
+        Err(InputError::Malformed)
+    );
+    assert_eq!(decode_input(&input[4..]), Err(InputError::UnknownSelector));
+
+    let (result, gas_used) = execute(vec![0; MAX_INPUT_LEN + 1]);
+    assert_eq!(result, Err(InputError::TooLarge.into()));
+    assert_eq!(gas_used, 0);
+}
+
+#[test]
+fn words_beyond_32_bits_are_malformed() {
+    let input = signed_call_data(AUTHENTICATOR_DATA);
+
+    // Adds 2^32 to the offset of the first argument, which a 32 bit `usize` would drop.
+    let mut offset_overflow = input.clone();
+    offset_overflow[4 + 27] = 1;
+    assert_eq!(decode_input(&offset_overflow), Err(InputError::Malformed));
+
+    // Same for the length of the first argument, right after the four offsets.
+    let mut length_overflow = input;
+    length_overflow[4 + 4 * 32 + 27] = 1;
+    assert_eq!(decode_input(&length_overflow), Err(InputError::Malformed));
+}
+
+#[test]
+fn gas_grows_with_the_input() {
+    assert_eq!(gas_cost(0), BASE_GAS);
+    assert_eq!(gas_cost(1), BASE_GAS + WORD_GAS);
+    assert_eq!(gas_cost(32 * 10), BASE_GAS + 10 * WORD_GAS);
+}
@@ -0,0 +1,96 @@
+//! Attestations straight from the CBOR attestation object `navigator.credentials.create()`
+//! returns, along with the client data it was made over.
+//!
+//! See <https://www.w3.org/TR/webauthn/#sctn-attestation>.
+
+use core::marker::PhantomData;
+use verifier::{
+    cose_key_to_der, parse_attestation_object, parse_authenticator_data,
+    verify_packed_x5c_attestation_with_revocation, webauthn_verify, VerifyError,
+};
+
+use crate::{
+    Attestation, AttestationMeta, AttestationPolicy, AuthenticatorAttachment, DEREncodedPublicKey,
+    ResponseError, Transports,
+};
+
+/// Builds an attestation from the raw outputs of a registration ceremony.
+pub trait DeviceAttestationFromCbor<Cx>: Sized {
+    /// Decodes `attestation_object`, takes the authenticator data and credential public key from
+    /// it, and verifies its statement over `client_data_json` before returning the attestation.
+    ///
+    /// The parts of the attestation that don't come from the authenticator are given in `meta`.
+    /// The attachment and transports, which browsers report apart from the attestation object,
+    /// are left unknown.
+    fn try_from_cbor(
+        attestation_object: &[u8],
+        client_data_json: &[u8],
+        meta: AttestationMeta<Cx>,
+    ) -> Result<Self, ResponseError>;
+}
+
+impl<Cx, P: AttestationPolicy> DeviceAttestationFromCbor<Cx> for Attestation<Cx, P> {
+    fn try_from_cbor(
+        attestation_object: &[u8],
+        client_data_json: &[u8],
+        meta: AttestationMeta<Cx>,
+    ) -> Result<Self, ResponseError> {
+        let attestation_object =
+            parse_attestation_object(attestation_object).map_err(|e| match e {
+                VerifyError::UnsupportedAttestationFormat | VerifyError::UnsupportedAlgorithm => {
+                    ResponseError::UnsupportedAttestationFormat
+                }
+                _ => ResponseError::InvalidAttestationObject,
+            })?;
+        let authenticator_data = attestation_object.auth_data;
+        let public_key = parse_authenticator_data(&authenticator_data)
+            .map_err(|_| ResponseError::InvalidAuthenticatorData)?
+            .attested_credential_data
+            .ok_or(ResponseError::InvalidAuthenticatorData)
+            .and_then(|data| {
+                cose_key_to_der(&data.credential_public_key)
+                    .and_then(|der| DEREncodedPublicKey::try_from(&der[..]))
+                    .map_err(|_| ResponseError::InvalidPublicKey)
+            })?;
+
+        match &attestation_object.statement {
+            verifier::AttestationStatement::None if P::ALLOW_NONE => Ok(()),
+            verifier::AttestationStatement::None => {
+                log::warn!("`none` attestation is not allowed by the attestation policy");
+                Err(VerifyError::UnsupportedAttestationFormat)
+            }
+            verifier::AttestationStatement::Packed { .. } if !P::ALLOW_SELF_ATTESTATION => {
+                log::warn!("Self-attestation is not allowed by the attestation policy");
+                Err(VerifyError::SelfAttestationNotAllowed)
+            }
+            verifier::AttestationStatement::Packed { sig, .. } => webauthn_verify(
+                &authenticator_data,
+                client_data_json,
+                sig,
+                public_key.as_der(),
+            ),
+            verifier::AttestationStatement::PackedX5c { alg, sig, x5c } => {
+                verify_packed_x5c_attestation_with_revocation(
+                    &P::revocation_checker(),
+                    &authenticator_data,
+                    client_data_json,
+                    *alg,
+                    sig,
+                    x5c,
+                )
+            }
+        }
+        .map_err(ResponseError::InvalidStatement)?;
+
+        Ok(Attestation {
+            meta,
+            authenticator_data,
+            client_data: client_data_json.to_vec(),
+            public_key,
+            authenticator_attachment: AuthenticatorAttachment::Unknown,
+            attestation_statement: attestation_object.statement.into(),
+            transports: Transports::default(),
+            _policy: PhantomData,
+        })
+    }
+}
@@ -0,0 +1,228 @@
+//! A compact CBOR encoding of [`Attestation`] and [`Assertion`], for transports that are
+//! CBOR-native rather than SCALE or JSON.
+//!
+//! Both types are encoded as a map whose keys are the field indices (`0`, `1`, ...) in
+//! ascending order, so the encoding of a given value is canonical. Byte fields are encoded as
+//! CBOR byte strings, and the challenge context as its SCALE encoding.
+
+use alloc::vec::Vec;
+use ciborium::{de::from_reader, ser::into_writer, value::Value};
+use codec::{Decode, DecodeAll, Encode};
+use core::marker::PhantomData;
+
+use crate::{
+    Assertion, AssertionMeta, Attestation, AttestationMeta, AttestationStatement,
+    AuthenticatorAttachment, DEREncodedPublicKey,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CborError {
+    /// The input is not a single CBOR map with the expected keys, in order.
+    Malformed,
+    /// A field holds a value of the wrong type or length.
+    InvalidField,
+}
+
+fn encode<const N: usize>(fields: [Value; N]) -> Vec<u8> {
+    let map = (0u8..).zip(fields).map(|(i, v)| (i.into(), v)).collect();
+
+    let mut encoded = Vec::new();
+    into_writer(&Value::Map(map), &mut encoded).expect("Writing to a vector doesn't fail; qed");
+    encoded
+}
+
+fn decode<const N: usize>(mut cbor: &[u8]) -> Result<[Value; N], CborError> {
+    let map = from_reader::<Value, _>(&mut cbor)
+        .ok()
+        .and_then(|value| value.into_map().ok())
+        .filter(|map| map.len() == N && cbor.is_empty())
+        .ok_or(CborError::Malformed)?;
+
+    let values: Vec<Value> = (0u8..)
+        .zip(map)
+        .map(|(i, (k, v))| (k == i.into()).then_some(v))
+        .collect::<Option<_>>()
+        .ok_or(CborError::Malformed)?;
+
+    values.try_into().map_err(|_| CborError::Malformed)
+}
+
+fn bytes(value: Value) -> Result<Vec<u8>, CborError> {
+    value.into_bytes().map_err(|_| CborError::InvalidField)
+}
+
+fn fixed<const N: usize>(value: Value) -> Result<[u8; N], CborError> {
+    bytes(value)?
+        .try_into()
+        .map_err(|_| CborError::InvalidField)
+}
+
+fn scale_decoded<T: Decode>(value: Value) -> Result<T, CborError> {
+    T::decode_all(&mut bytes(value)?.as_slice()).map_err(|_| CborError::InvalidField)
+}
+
+fn attachment_to_cbor(attachment: AuthenticatorAttachment) -> Value {
+    match attachment {
+        AuthenticatorAttachment::Platform => 0u8,
+        AuthenticatorAttachment::CrossPlatform => 1,
+        AuthenticatorAttachment::Unknown => 2,
+    }
+    .into()
+}
+
+fn attachment_from_cbor(value: Value) -> Result<AuthenticatorAttachment, CborError> {
+    match value.into_integer().map(u8::try_from) {
+        Ok(Ok(0)) => Ok(AuthenticatorAttachment::Platform),
+        Ok(Ok(1)) => Ok(AuthenticatorAttachment::CrossPlatform),
+        Ok(Ok(2)) => Ok(AuthenticatorAttachment::Unknown),
+        _ => Err(CborError::InvalidField),
+    }
+}
+
+/// `none` is encoded as `null`, `packed` as an `[alg, sig]` array, and `packed` with `x5c` as an
+/// `[alg, sig, x5c]` one.
+fn statement_to_cbor(statement: &AttestationStatement) -> Value {
+    match statement {
+        AttestationStatement::None => Value::Null,
+        AttestationStatement::Packed { alg, sig } => {
+            Value::Array(alloc::vec![(*alg).into(), Value::Bytes(sig.clone())])
+        }
+        AttestationStatement::PackedX5c { alg, sig, x5c } => Value::Array(alloc::vec![
+            (*alg).into(),
+            Value::Bytes(sig.clone()),
+            Value::Array(x5c.iter().cloned().map(Value::Bytes).collect()),
+        ]),
+    }
+}
+
+fn statement_from_cbor(value: Value) -> Result<AttestationStatement, CborError> {
+    if value.is_null() {
+        return Ok(AttestationStatement::None);
+    }
+
+    let mut fields = value.into_array().map_err(|_| CborError::InvalidField)?;
+    let x5c = match fields.len() {
+        2 => None,
+        3 => fields.pop(),
+        _ => return Err(CborError::InvalidField),
+    };
+    let [alg, sig]: [Value; 2] = fields.try_into().map_err(|_| CborError::InvalidField)?;
+    let alg = alg
+        .into_integer()
+        .ok()
+        .and_then(|alg| i64::try_from(alg).ok())
+        .ok_or(CborError::InvalidField)?;
+    let sig = bytes(sig)?;
+
+    Ok(match x5c {
+        None => AttestationStatement::Packed { alg, sig },
+        Some(x5c) => AttestationStatement::PackedX5c {
+            alg,
+            sig,
+            x5c: x5c
+                .into_array()
+                .map_err(|_| CborError::InvalidField)?
+                .into_iter()
+                .map(bytes)
+                .collect::<Result<_, _>>()?,
+        },
+    })
+}
+
+impl<Cx: Encode + Decode, P> Attestation<Cx, P> {
+    pub fn to_cbor(&self) -> Vec<u8> {
+        encode([
+            Value::Bytes(self.meta.authority_id.to_vec()),
+            Value::Bytes(self.meta.device_id.to_vec()),
+            Value::Bytes(self.meta.context.encode()),
+            Value::Bytes(self.authenticator_data.clone()),
+            Value::Bytes(self.client_data.clone()),
+            Value::Bytes(self.public_key.as_der().to_vec()),
+            attachment_to_cbor(self.authenticator_attachment),
+            statement_to_cbor(&self.attestation_statement),
+            self.transports.bits().into(),
+            self.meta
+                .user_id
+                .map_or(Value::Null, |user_id| Value::Bytes(user_id.to_vec())),
+        ])
+    }
+
+    /// Decodes an attestation, also accepting those encoded before the user id or the
+    /// transports were carried, without the last field or two.
+    pub fn from_cbor(cbor: &[u8]) -> Result<Self, CborError> {
+        let [authority_id, device_id, context, authenticator_data, client_data, public_key, authenticator_attachment, attestation_statement, transports, user_id] =
+            decode(cbor)
+                .or_else(|_| {
+                    decode(cbor).map(|[a, d, c, ad, cd, pk, aa, s, t]: [Value; 9]| {
+                        [a, d, c, ad, cd, pk, aa, s, t, Value::Null]
+                    })
+                })
+                .or_else(|_| {
+                    decode(cbor).map(|[a, d, c, ad, cd, pk, aa, s]: [Value; 8]| {
+                        [a, d, c, ad, cd, pk, aa, s, 0u8.into(), Value::Null]
+                    })
+                })?;
+
+        Ok(Attestation {
+            meta: AttestationMeta {
+                authority_id: fixed(authority_id)?,
+                device_id: fixed(device_id)?,
+                context: scale_decoded(context)?,
+                user_id: match user_id {
+                    Value::Null => None,
+                    user_id => Some(fixed(user_id)?),
+                },
+            },
+            authenticator_data: bytes(authenticator_data)?,
+            client_data: bytes(client_data)?,
+            public_key: DEREncodedPublicKey::try_from(&bytes(public_key)?[..])
+                .map_err(|_| CborError::InvalidField)?,
+            authenticator_attachment: attachment_from_cbor(authenticator_attachment)?,
+            attestation_statement: statement_from_cbor(attestation_statement)?,
+            transports: transports
+                .into_integer()
+                .ok()
+                .and_then(|bits| u8::try_from(bits).ok())
+                .ok_or(CborError::InvalidField)?
+                .into(),
+            _policy: PhantomData,
+        })
+    }
+}
+
+impl<Cx: Encode + Decode> Assertion<Cx> {
+    pub fn to_cbor(&self) -> Vec<u8> {
+        encode([
+            Value::Bytes(self.meta.authority_id.to_vec()),
+            Value::Bytes(self.meta.user_id.to_vec()),
+            Value::Bytes(self.meta.context.encode()),
+            Value::Bytes(self.authenticator_data.clone()),
+            Value::Bytes(self.client_data.clone()),
+            Value::Bytes(self.signature.clone()),
+            Value::Bytes(self.credential_id.clone()),
+        ])
+    }
+
+    /// Decodes an assertion, also accepting those encoded before the credential ID was carried,
+    /// without the last field.
+    pub fn from_cbor(cbor: &[u8]) -> Result<Self, CborError> {
+        let [authority_id, user_id, context, authenticator_data, client_data, signature, credential_id] =
+            decode(cbor).or_else(|_| {
+                decode(cbor).map(|[a, u, c, ad, cd, s]: [Value; 6]| {
+                    [a, u, c, ad, cd, s, Value::Bytes(Vec::new())]
+                })
+            })?;
+
+        Ok(Assertion {
+            meta: AssertionMeta {
+                authority_id: fixed(authority_id)?,
+                user_id: fixed(user_id)?,
+                context: scale_decoded(context)?,
+            },
+            authenticator_data: bytes(authenticator_data)?,
+            client_data: bytes(client_data)?,
+            signature: bytes(signature)?,
+            credential_id: bytes(credential_id)?,
+        })
+    }
+}
@@ -0,0 +1,172 @@
+//! The JSON bodies a browser sends after `navigator.credentials.create()` and
+//! `navigator.credentials.get()`, and their conversions into [`Attestation`] and [`Assertion`].
+//!
+//! Binary fields are expected to be base64url-encoded (without padding), as produced by
+//! `PublicKeyCredential.toJSON()`. The parts of [`Attestation`] and [`Assertion`] that come
+//! from the ceremony options rather than the response are passed in as meta.
+
+use alloc::{string::String, vec::Vec};
+use base64::prelude::BASE64_URL_SAFE_NO_PAD;
+use core::marker::PhantomData;
+use serde::Deserialize;
+use traits_authn::HashedUserId;
+use verifier::{cose_key_to_der, parse_attestation_object, parse_authenticator_data, VerifyError};
+
+use crate::{
+    ids, Assertion, AssertionMeta, Attestation, AttestationMeta, AuthenticatorAttachment,
+    DEREncodedPublicKey, ResponseError, Transports,
+};
+
+const PUBLIC_KEY_CREDENTIAL_TYPE: &str = "public-key";
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialCreationResponse {
+    pub id: String,
+    pub raw_id: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub response: AuthenticatorAttestationResponse,
+    pub authenticator_attachment: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticatorAttestationResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    /// The CBOR-encoded attestation object.
+    pub attestation_object: String,
+    pub transports: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialRequestResponse {
+    pub id: String,
+    pub raw_id: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub response: AuthenticatorAssertionResponse,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticatorAssertionResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub signature: String,
+    pub user_handle: Option<String>,
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, ResponseError> {
+    base64::decode_engine(value.as_bytes(), &BASE64_URL_SAFE_NO_PAD)
+        .map_err(|_| ResponseError::InvalidEncoding)
+}
+
+impl CredentialRequestResponse {
+    /// The user the response is for, as given by its `userHandle`.
+    ///
+    /// The handle is optional: authenticators may leave it out for credentials that aren't
+    /// discoverable, so `None` is returned rather than failing, and the caller tells the user
+    /// from the credential it asked for instead. Handles that aren't a [`HashedUserId`] yield
+    /// `None` too.
+    pub fn user_id(&self) -> Result<Option<HashedUserId>, ResponseError> {
+        Ok(match &self.response.user_handle {
+            Some(user_handle) => {
+                ids::HashedUserId::from_user_handle(&decode(user_handle)?).map(Into::into)
+            }
+            None => None,
+        })
+    }
+}
+
+impl<Cx, P> TryFrom<(CredentialCreationResponse, AttestationMeta<Cx>)> for Attestation<Cx, P> {
+    type Error = ResponseError;
+
+    fn try_from(
+        (credential, meta): (CredentialCreationResponse, AttestationMeta<Cx>),
+    ) -> Result<Self, Self::Error> {
+        if credential.ty != PUBLIC_KEY_CREDENTIAL_TYPE {
+            return Err(ResponseError::InvalidType);
+        }
+
+        let attestation_object = parse_attestation_object(&decode(
+            &credential.response.attestation_object,
+        )?)
+        .map_err(|e| match e {
+            VerifyError::UnsupportedAttestationFormat | VerifyError::UnsupportedAlgorithm => {
+                ResponseError::UnsupportedAttestationFormat
+            }
+            _ => ResponseError::InvalidAttestationObject,
+        })?;
+        let authenticator_data = attestation_object.auth_data;
+        let public_key = parse_authenticator_data(&authenticator_data)
+            .map_err(|_| ResponseError::InvalidAuthenticatorData)?
+            .attested_credential_data
+            .ok_or(ResponseError::InvalidAuthenticatorData)
+            .and_then(|data| {
+                cose_key_to_der(&data.credential_public_key)
+                    .and_then(|der| DEREncodedPublicKey::try_from(&der[..]))
+                    .map_err(|_| ResponseError::InvalidPublicKey)
+            })?;
+
+        let authenticator_attachment = match credential.authenticator_attachment.as_deref() {
+            Some("platform") => AuthenticatorAttachment::Platform,
+            Some("cross-platform") => AuthenticatorAttachment::CrossPlatform,
+            _ => AuthenticatorAttachment::from_transports(
+                credential
+                    .response
+                    .transports
+                    .iter()
+                    .flatten()
+                    .map(String::as_str),
+            ),
+        };
+
+        Ok(Attestation {
+            meta,
+            authenticator_data,
+            client_data: decode(&credential.response.client_data_json)?,
+            public_key,
+            authenticator_attachment,
+            attestation_statement: attestation_object.statement.into(),
+            transports: Transports::from_names(
+                credential
+                    .response
+                    .transports
+                    .iter()
+                    .flatten()
+                    .map(String::as_str),
+            ),
+            _policy: PhantomData,
+        })
+    }
+}
+
+impl<Cx> TryFrom<(CredentialRequestResponse, AssertionMeta<Cx>)> for Assertion<Cx> {
+    type Error = ResponseError;
+
+    fn try_from(
+        (credential, meta): (CredentialRequestResponse, AssertionMeta<Cx>),
+    ) -> Result<Self, Self::Error> {
+        if credential.ty != PUBLIC_KEY_CREDENTIAL_TYPE {
+            return Err(ResponseError::InvalidType);
+        }
+
+        if let Some(user_handle) = &credential.response.user_handle {
+            if decode(user_handle)? != meta.user_id.as_slice() {
+                return Err(ResponseError::UserHandleMismatch);
+            }
+        }
+
+        Ok(Assertion {
+            meta,
+            authenticator_data: decode(&credential.response.authenticator_data)?,
+            client_data: decode(&credential.response.client_data_json)?,
+            signature: decode(&credential.response.signature)?,
+            credential_id: decode(&credential.raw_id)?,
+        })
+    }
+}
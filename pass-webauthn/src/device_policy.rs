@@ -0,0 +1,125 @@
+//! Blocking authenticator models with known vulnerabilities from registering.
+//!
+//! Models are told apart by the AAGUID of the attested credential data. Unless the attestation
+//! is signed with an attestation key the relying party trusts, the authenticator could claim any
+//! AAGUID, so a blocklist only keeps honest authenticators of a compromised model out. Even
+//! [`RequireX5cAttestation`](crate::RequireX5cAttestation) doesn't tell which attestation keys
+//! are trusted.
+
+use alloc::collections::BTreeSet;
+use core::{fmt, marker::PhantomData};
+#[cfg(any(feature = "runtime", test))]
+use frame_support::traits::Get;
+#[cfg(any(feature = "runtime", test))]
+use verifier::RevocationChecker;
+
+use crate::AttestationPolicy;
+
+/// The status of an authenticator model, as the FIDO Metadata Service reports it.
+///
+/// See <https://fidoalliance.org/specs/mds/fido-metadata-service-v3.0-ps-20210518.html#authenticatorstatus-enum>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthenticatorStatus {
+    NotFidoCertified,
+    FidoCertified,
+    UserVerificationBypass,
+    AttestationKeyCompromise,
+    UserKeyRemoteCompromise,
+    UserKeyPhysicalCompromise,
+    UpdateAvailable,
+    Revoked,
+    SelfAssertionSubmitted,
+    FidoCertifiedL1,
+    FidoCertifiedL1Plus,
+    FidoCertifiedL2,
+    FidoCertifiedL2Plus,
+    FidoCertifiedL3,
+    FidoCertifiedL3Plus,
+}
+
+/// A status report of the metadata of the authenticator model `aaguid`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusReport {
+    pub aaguid: [u8; 16],
+    pub status: AuthenticatorStatus,
+}
+
+/// Which authenticator models may register, all but those of a blocklist.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DevicePolicyEngine {
+    blocked: BTreeSet<[u8; 16]>,
+}
+
+impl DevicePolicyEngine {
+    /// Blocks the models whose keys the metadata reports compromised: those with an
+    /// [`AttestationKeyCompromise`](AuthenticatorStatus::AttestationKeyCompromise) or a
+    /// [`UserKeyPhysicalCompromise`](AuthenticatorStatus::UserKeyPhysicalCompromise) report.
+    pub fn from_mds_status_reports(reports: &[StatusReport]) -> Self {
+        Self {
+            blocked: reports
+                .iter()
+                .filter(|report| {
+                    matches!(
+                        report.status,
+                        AuthenticatorStatus::AttestationKeyCompromise
+                            | AuthenticatorStatus::UserKeyPhysicalCompromise
+                    )
+                })
+                .map(|report| report.aaguid)
+                .collect(),
+        }
+    }
+
+    /// Blocks the model `aaguid` too.
+    pub fn block(mut self, aaguid: [u8; 16]) -> Self {
+        self.blocked.insert(aaguid);
+        self
+    }
+
+    pub fn is_allowed(&self, aaguid: [u8; 16]) -> bool {
+        !self.blocked.contains(&aaguid)
+    }
+}
+
+/// The attestation policy `P`, also rejecting the authenticator models `E` blocks with
+/// [`VerifyError::AuthenticatorBlocked`](verifier::VerifyError::AuthenticatorBlocked).
+pub struct WithDevicePolicy<P, E>(PhantomData<(P, E)>);
+
+#[cfg(any(feature = "runtime", test))]
+impl<P, E> AttestationPolicy for WithDevicePolicy<P, E>
+where
+    P: AttestationPolicy,
+    E: Get<DevicePolicyEngine> + 'static,
+{
+    const ALLOW_NONE: bool = P::ALLOW_NONE;
+    const ALLOW_SELF_ATTESTATION: bool = P::ALLOW_SELF_ATTESTATION;
+
+    fn is_device_allowed(aaguid: [u8; 16]) -> bool {
+        E::get().is_allowed(aaguid) && P::is_device_allowed(aaguid)
+    }
+
+    fn revocation_checker() -> impl RevocationChecker {
+        P::revocation_checker()
+    }
+}
+
+// Not derived, as `E` is usually a `parameter_types!` type implementing none of these.
+impl<P, E> Clone for WithDevicePolicy<P, E> {
+    fn clone(&self) -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<P, E> PartialEq for WithDevicePolicy<P, E> {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl<P, E> Eq for WithDevicePolicy<P, E> {}
+
+impl<P, E> fmt::Debug for WithDevicePolicy<P, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WithDevicePolicy")
+    }
+}
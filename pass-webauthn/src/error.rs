@@ -0,0 +1,240 @@
+//! Errors surfaced while checking WebAuthn ceremonies.
+
+use alloc::string::String;
+use codec::{Decode, Encode, MaxEncodedLen};
+use core::fmt;
+use scale_info::TypeInfo;
+use traits_authn::AuthorityId;
+use verifier::VerifyError;
+
+use crate::ReplayError;
+
+/// Why a registration or authentication response was rejected.
+///
+/// Its SCALE encoding is a stable ABI for events and RPC responses: the variant indices never
+/// change, and new variants only take new ones. Details only meant for messages, like those of
+/// [`AuthorityMismatch`](Self::AuthorityMismatch), aren't encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub enum WebAuthnError {
+    /// The signature (or the key or data it covers) failed cryptographic verification.
+    #[codec(index = 0)]
+    Verify(VerifyError),
+    /// The client data can't be used to check the ceremony.
+    #[codec(index = 1)]
+    InvalidClientData(ClientDataError),
+    /// The challenge isn't the one issued for this ceremony.
+    #[codec(index = 2)]
+    InvalidChallenge,
+    /// The challenge was issued for a context that is no longer valid.
+    #[codec(index = 3)]
+    ChallengeExpired,
+    /// The relying party the response was produced for isn't the expected one.
+    #[codec(index = 4)]
+    RpIdMismatch,
+    /// The response targets a different authority than the expected one.
+    ///
+    /// An authority id is a hash, so the origin the response was produced at, when it can be
+    /// read from the client data, tells which relying party it was actually for.
+    #[codec(index = 5)]
+    AuthorityMismatch {
+        #[codec(skip)]
+        expected: AuthorityId,
+        #[codec(skip)]
+        actual_origin: Option<String>,
+    },
+    /// The response is for a different device than the expected one.
+    #[codec(index = 6)]
+    DeviceMismatch,
+    /// The assertion was already used, or its signature counter didn't advance.
+    #[codec(index = 7)]
+    Replayed,
+    /// Reading or writing the stored credential failed.
+    #[codec(index = 8)]
+    StorageError(StoreError),
+    /// The credential requires user verification, and the user was only checked to be present.
+    #[codec(index = 9)]
+    UserVerificationRequired,
+    /// The authenticator didn't report the user as present (`UP`).
+    #[codec(index = 10)]
+    UserNotPresent,
+    /// The response is for a different user than the expected one, or for an unknown one.
+    #[codec(index = 11)]
+    UserMismatch,
+    /// The assertion was made with another credential than the stored one.
+    #[codec(index = 12)]
+    CredentialIdMismatch,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub enum ClientDataError {
+    /// No base64url-encoded challenge could be extracted from the client data.
+    MissingChallenge = 0,
+    /// The `type` of the client data isn't the one of the ceremony: `webauthn.create` for
+    /// registrations, `webauthn.get` for authentications.
+    TypeMismatch = 1,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub enum StoreError {
+    /// No credential is stored for the device.
+    NotFound = 0,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub enum MergeError {
+    /// The credentials are for different devices.
+    DeviceIdMismatch = 0,
+}
+
+/// Why a response, as browsers send it or as passkey-rs and the attestation object give it,
+/// can't be turned into an [`Attestation`](crate::Attestation) or
+/// [`Assertion`](crate::Assertion).
+#[derive(Debug, PartialEq, Eq)]
+pub enum ResponseError {
+    /// The credential `type` is not `public-key`.
+    InvalidType,
+    /// A binary field is not valid base64url.
+    InvalidEncoding,
+    /// The attestation object is not a CBOR map carrying `fmt`, `attStmt` and `authData`.
+    InvalidAttestationObject,
+    /// The attestation format isn't `none` or `packed`, or its algorithm isn't supported.
+    UnsupportedAttestationFormat,
+    /// The authenticator data is malformed, or carries no attested credential data.
+    InvalidAuthenticatorData,
+    /// The attestation response carries no public key.
+    MissingPublicKey,
+    /// The credential public key is not a supported COSE key, or not a DER-encoded,
+    /// uncompressed P-256 key.
+    InvalidPublicKey,
+    /// The public key is for an algorithm the verifier doesn't support.
+    UnsupportedAlgorithm,
+    /// The attestation statement doesn't verify, or the policy doesn't accept its kind.
+    InvalidStatement(VerifyError),
+    /// The response's user handle is not the `user_id` given in the meta.
+    UserHandleMismatch,
+}
+
+impl From<VerifyError> for WebAuthnError {
+    fn from(value: VerifyError) -> Self {
+        Self::Verify(value)
+    }
+}
+
+impl From<ReplayError> for WebAuthnError {
+    fn from(_: ReplayError) -> Self {
+        Self::Replayed
+    }
+}
+
+impl From<ClientDataError> for WebAuthnError {
+    fn from(value: ClientDataError) -> Self {
+        Self::InvalidClientData(value)
+    }
+}
+
+impl From<StoreError> for WebAuthnError {
+    fn from(value: StoreError) -> Self {
+        Self::StorageError(value)
+    }
+}
+
+impl fmt::Display for WebAuthnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Verify(e) => write!(f, "verification failed: {e:?}"),
+            Self::InvalidClientData(e) => write!(f, "invalid client data: {e:?}"),
+            Self::InvalidChallenge => f.write_str("invalid challenge"),
+            Self::ChallengeExpired => f.write_str("challenge expired"),
+            Self::RpIdMismatch => f.write_str("relying party id mismatch"),
+            Self::AuthorityMismatch {
+                expected,
+                actual_origin,
+            } => {
+                f.write_str("authority mismatch: expected 0x")?;
+                for byte in expected {
+                    write!(f, "{byte:02x}")?;
+                }
+                match actual_origin {
+                    Some(origin) => write!(f, ", got a response from {origin}"),
+                    None => f.write_str(", got a response from an unknown origin"),
+                }
+            }
+            Self::DeviceMismatch => f.write_str("device mismatch"),
+            Self::Replayed => f.write_str("assertion replayed"),
+            Self::StorageError(e) => write!(f, "storage error: {e:?}"),
+            Self::UserVerificationRequired => f.write_str("user verification required"),
+            Self::UserNotPresent => f.write_str("user not present"),
+            Self::UserMismatch => f.write_str("user mismatch"),
+            Self::CredentialIdMismatch => f.write_str("credential id mismatch"),
+        }
+    }
+}
+
+#[cfg(any(feature = "runtime", test))]
+impl WebAuthnError {
+    /// The `pallet-pass` error to fail a call with: [`DeviceAttestationInvalid`] for responses
+    /// that can't be read, and [`CredentialInvalid`] for well-formed ones that don't check out.
+    ///
+    /// [`DeviceAttestationInvalid`]: pallet_pass::Error::DeviceAttestationInvalid
+    /// [`CredentialInvalid`]: pallet_pass::Error::CredentialInvalid
+    pub fn into_pass_error<T: pallet_pass::Config<I>, I: 'static>(
+        self,
+    ) -> pallet_pass::Error<T, I> {
+        use VerifyError::*;
+        match self {
+            Self::Verify(
+                AlgorithmNotOffered
+                | AttestationCertificateRevoked
+                | AuthenticatorBlocked
+                | ClientDataMalformed
+                | CredentialAlreadyRegistered
+                | ExtractPublicKey
+                | InputTooLarge
+                | MissingAlgorithm
+                | ParseAttestationCertificate
+                | ParseAttestationObject
+                | ParseAuthenticatorData
+                | ParseClientData
+                | ParseCtap2Response
+                | ParseSignature
+                | SelfAttestationNotAllowed
+                | UnsupportedAlgorithm
+                | UnsupportedAttestationFormat,
+            )
+            | Self::InvalidClientData(_) => pallet_pass::Error::DeviceAttestationInvalid,
+            Self::Verify(
+                AaguidMismatch
+                | AlgorithmMismatch
+                | BackupStateInconsistency
+                | ChallengeMismatch
+                | ClientDataModified
+                | InvalidAttestationCertificate
+                | PublicKeyMismatch
+                | VerifySignature,
+            )
+            | Self::InvalidChallenge
+            | Self::ChallengeExpired
+            | Self::RpIdMismatch
+            | Self::AuthorityMismatch { .. }
+            | Self::DeviceMismatch
+            | Self::Replayed
+            | Self::StorageError(_)
+            | Self::UserVerificationRequired
+            | Self::UserNotPresent
+            | Self::UserMismatch
+            | Self::CredentialIdMismatch => pallet_pass::Error::CredentialInvalid,
+        }
+    }
+}
+
+/// Maps a verification failure straight to a `pallet-pass` error, as
+/// [`WebAuthnError::into_pass_error`] does.
+#[cfg(any(feature = "runtime", test))]
+pub fn pass_error<T: pallet_pass::Config<I>, I: 'static>(
+    error: VerifyError,
+) -> pallet_pass::Error<T, I> {
+    WebAuthnError::from(error).into_pass_error()
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for WebAuthnError {}
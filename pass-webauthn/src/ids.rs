@@ -0,0 +1,119 @@
+//! Typed wrappers of the ids `traits-authn` passes around as plain `[u8; 32]`s.
+//!
+//! `traits_authn::{AuthorityId, DeviceId, HashedUserId}` are aliases of the same array, so one
+//! passed where another is expected still compiles. The newtypes here can only be mixed up
+//! explicitly, and each has a constructor for the way that id is meant to be derived. They encode
+//! like the arrays they wrap, with SCALE and (with the `serde` feature) serde alike, and convert
+//! to and from them with [`From`] where `traits-authn` wants the aliases.
+
+#[cfg(any(feature = "runtime", test))]
+use codec::MaxEncodedLen;
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+
+use crate::{
+    rp_id::{is_label, RpIdError},
+    runtime_helpers::strict_decode_fixed,
+};
+
+macro_rules! id {
+    ($(#[$meta:meta])* $name:ident => $alias:ident) => {
+        $(#[$meta])*
+        #[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+        #[cfg_attr(any(feature = "runtime", test), derive(MaxEncodedLen))]
+        #[cfg_attr(
+            any(feature = "serde", test),
+            derive(serde::Serialize, serde::Deserialize),
+            serde(transparent)
+        )]
+        #[repr(transparent)]
+        pub struct $name([u8; 32]);
+
+        impl $name {
+            pub const fn from_bytes(bytes: [u8; 32]) -> Self {
+                Self(bytes)
+            }
+
+            pub const fn as_bytes(&self) -> &[u8; 32] {
+                &self.0
+            }
+        }
+
+        impl From<traits_authn::$alias> for $name {
+            fn from(value: traits_authn::$alias) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for traits_authn::$alias {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+    };
+}
+
+id! {
+    /// The id of a device, derived from the id of its credential.
+    DeviceId => DeviceId
+}
+
+id! {
+    /// The id of an authority, its name padded with zero bytes.
+    AuthorityId => AuthorityId
+}
+
+id! {
+    /// The id of a user, given to authenticators as the `user.id` of the credentials they create
+    /// and returned by them as the `userHandle` of assertions.
+    HashedUserId => HashedUserId
+}
+
+impl DeviceId {
+    /// The id of the device holding the credential `credential_id`: its Blake2-256 hash, the way
+    /// the pallet keys the devices it registers.
+    #[cfg(any(feature = "runtime", test))]
+    pub fn from_credential_id(credential_id: &[u8]) -> Self {
+        use frame_support::sp_runtime::traits::{BlakeTwo256, Hash};
+        Self(BlakeTwo256::hash(credential_id).0)
+    }
+}
+
+impl AuthorityId {
+    /// The authority whose [`expected_rp_id`](crate::expected_rp_id) under `base_domain` is
+    /// `rp_id`.
+    ///
+    /// The leftmost label of `rp_id` is the name of the authority. As `_` and `-`, and upper and
+    /// lowercase letters, make the same label, several authorities share an RP ID; this is the
+    /// one spelt like the label, lowercase and with `-`.
+    pub fn from_rp_id(rp_id: &str, base_domain: &str) -> Result<Self, RpIdError> {
+        if base_domain.is_empty() || !base_domain.split('.').all(is_label) {
+            return Err(RpIdError::InvalidBaseDomain);
+        }
+        let label = rp_id
+            .strip_suffix(base_domain)
+            .and_then(|label| label.strip_suffix('.'))
+            .ok_or(RpIdError::NotUnderBaseDomain)?;
+        if !is_label(label) || label.len() > 32 {
+            return Err(RpIdError::InvalidAuthority);
+        }
+
+        let mut id = [0; 32];
+        id[..label.len()].copy_from_slice(label.as_bytes());
+        Ok(Self(id))
+    }
+}
+
+impl HashedUserId {
+    /// The user of a `userHandle`, which is the id the credential was created with. `None` if
+    /// it isn't a [`HashedUserId`].
+    pub fn from_user_handle(user_handle: &[u8]) -> Option<Self> {
+        strict_decode_fixed(user_handle).map(Self)
+    }
+}
@@ -4,27 +4,170 @@ extern crate alloc;
 
 use alloc::vec::Vec;
 use codec::{Decode, Encode};
+use core::marker::PhantomData;
+use p256::{ecdsa::VerifyingKey, pkcs8::DecodePublicKey};
 use traits_authn::{
     util::{Auth, Dev},
     AuthorityId, Challenger, DeviceId, HashedUserId,
 };
 
 #[cfg(any(feature = "runtime", test))]
-use ::{codec::MaxEncodedLen, scale_info::TypeInfo};
+use ::{codec::MaxEncodedLen, scale_info::TypeInfo, verifier::AuthenticatorDataFlags};
+
+use verifier::{
+    compress_public_key_der, sec1_public_key_to_der, NoOpRevocationChecker, RevocationChecker,
+    VerifyError, COMPRESSED_P256_KEY_LEN,
+};
 
 type CxOf<Ch> = <Ch as Challenger>::Context;
 
+#[cfg(any(feature = "cbor", test))]
+pub mod attestation_object;
+#[cfg(any(feature = "cbor", test))]
+pub mod cbor;
+#[cfg(any(feature = "serde", test))]
+pub mod credential_response;
+pub mod device_policy;
+mod error;
+pub mod ids;
+mod meta_builder;
+#[cfg(any(feature = "runtime", test))]
+pub mod migration;
+#[cfg(any(feature = "passkey-compat", test))]
+pub mod passkey_compat;
+#[cfg(any(feature = "runtime", test))]
+pub mod registration;
+pub mod replay;
+pub mod revocation;
+pub mod rp_id;
+#[cfg(any(feature = "runtime", test))]
+pub mod runtime_config;
 mod runtime_helpers;
 #[cfg(any(feature = "runtime", test))]
 pub mod runtime_impls;
+#[cfg(any(feature = "testing", test))]
+pub mod testing;
+#[cfg(any(feature = "runtime", test))]
+pub mod timestamp_challenger;
 
 #[cfg(test)]
 mod tests;
 
-pub type DEREncodedPublicKey = [u8; 91];
+pub use device_policy::{AuthenticatorStatus, DevicePolicyEngine, StatusReport, WithDevicePolicy};
+#[cfg(any(feature = "runtime", test))]
+pub use error::pass_error;
+pub use error::{ClientDataError, MergeError, ResponseError, StoreError, WebAuthnError};
+#[cfg(any(feature = "runtime", test))]
+pub use meta_builder::{AssertionMetaBuilder, AttestationMetaBuilder, BuildError};
+#[cfg(any(feature = "runtime", test))]
+pub use migration::{migrate_credential, LegacyCredential, CREDENTIAL_STORAGE_VERSION};
+#[cfg(any(feature = "runtime", test))]
+pub use registration::{
+    register_none_attestation, register_none_attestation_with_transports, CredentialRecord,
+};
+pub use revocation::WithRevocationChecker;
+pub use rp_id::{expected_rp_id, expected_rp_id_hash, RpId, RpIdError};
+#[cfg(any(feature = "runtime", test))]
+pub use runtime_config::{
+    AssertionOf, AttestationOf, AuthenticatorOf, DeviceOf, SubstrateWebAuthnRuntime,
+};
+#[cfg(any(feature = "runtime", test))]
+pub use runtime_impls::batch::{batch_challenge, BatchAttestation, MAX_BATCH_SIZE};
+#[cfg(any(feature = "runtime", test))]
+pub use runtime_impls::options::{AuthenticationOptions, RegistrationOptions};
+#[cfg(any(feature = "runtime", test))]
+pub use timestamp_challenger::TimestampChallenger;
+
+/// Length of a DER-encoded P-256 public key.
+pub const DER_ENCODED_PUBLIC_KEY_LEN: usize = 91;
+
+/// `SubjectPublicKeyInfo` header of a DER-encoded, uncompressed P-256 public key: the
+/// `id-ecPublicKey` algorithm and `prime256v1` curve OIDs, then the SEC1 point tag.
+const P256_SPKI_PREFIX: [u8; 27] = [
+    0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a,
+    0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00, 0x04,
+];
+
+/// A DER-encoded, uncompressed P-256 `SubjectPublicKeyInfo`, as attestations carry public keys.
+///
+/// Its header is checked when built or decoded; whether the point is on the curve is only known
+/// once [`verifying_key`](Self::verifying_key) is called. Encoded as its 91 bytes, as the array
+/// it wraps.
+#[derive(Encode, TypeInfo, Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(any(feature = "runtime", test), derive(MaxEncodedLen))]
+#[repr(transparent)]
+pub struct DEREncodedPublicKey([u8; DER_ENCODED_PUBLIC_KEY_LEN]);
+
+impl DEREncodedPublicKey {
+    pub fn as_der(&self) -> &[u8; DER_ENCODED_PUBLIC_KEY_LEN] {
+        &self.0
+    }
+
+    /// The key, failing with [`VerifyError::ExtractPublicKey`] when the point isn't on the curve.
+    pub fn verifying_key(&self) -> Result<VerifyingKey, VerifyError> {
+        Ok(VerifyingKey::from_public_key_der(&self.0)?)
+    }
+}
+
+impl TryFrom<&[u8]> for DEREncodedPublicKey {
+    type Error = VerifyError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let der: [u8; DER_ENCODED_PUBLIC_KEY_LEN] = value
+            .try_into()
+            .map_err(|_| VerifyError::ExtractPublicKey)?;
+        if !der.starts_with(&P256_SPKI_PREFIX) {
+            log::warn!("Public key is not a DER-encoded, uncompressed P-256 key");
+            return Err(VerifyError::ExtractPublicKey);
+        }
+        Ok(Self(der))
+    }
+}
+
+impl Decode for DEREncodedPublicKey {
+    fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+        let der = <[u8; DER_ENCODED_PUBLIC_KEY_LEN]>::decode(input)?;
+        Self::try_from(&der[..]).map_err(|_| "Invalid DER-encoded P-256 public key".into())
+    }
+}
+
+/// A credential public key as stored on-chain: the compressed SEC1 point, tagged with the
+/// algorithm it's used with. A third of the size of its [`DEREncodedPublicKey`].
+#[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(any(feature = "runtime", test), derive(MaxEncodedLen))]
+pub enum CredentialPublicKey {
+    /// A P-256 key, used with ES256.
+    Es256([u8; COMPRESSED_P256_KEY_LEN]),
+}
+
+impl CredentialPublicKey {
+    /// The SEC1 encoding of the key.
+    pub fn as_sec1(&self) -> &[u8] {
+        match self {
+            CredentialPublicKey::Es256(point) => point,
+        }
+    }
+}
+
+impl TryFrom<&DEREncodedPublicKey> for CredentialPublicKey {
+    type Error = VerifyError;
+
+    fn try_from(value: &DEREncodedPublicKey) -> Result<Self, Self::Error> {
+        compress_public_key_der(value.as_der()).map(CredentialPublicKey::Es256)
+    }
+}
+
+impl TryFrom<&CredentialPublicKey> for DEREncodedPublicKey {
+    type Error = VerifyError;
+
+    fn try_from(value: &CredentialPublicKey) -> Result<Self, Self::Error> {
+        DEREncodedPublicKey::try_from(&sec1_public_key_to_der(value.as_sec1())?[..])
+    }
+}
 
 #[cfg(any(feature = "runtime", test))]
-pub type Authenticator<Ch, A> = Auth<Device<Ch, A>, Attestation<CxOf<Ch>>>;
+pub type Authenticator<Ch, A, P = AllowNoneAttestation> =
+    Auth<Device<Ch, A>, Attestation<CxOf<Ch>, P>>;
 #[cfg(any(feature = "runtime", test))]
 pub type Device<Ch, A> = Dev<Credential, A, Ch, Assertion<CxOf<Ch>>>;
 
@@ -32,8 +175,222 @@ pub type Device<Ch, A> = Dev<Credential, A, Ch, Assertion<CxOf<Ch>>>;
 #[derive(MaxEncodedLen, TypeInfo, Decode, Encode)]
 pub struct Credential {
     device_id: DeviceId,
-    //. A DER-encoded public key
-    public_key: DEREncodedPublicKey,
+    /// The device id derived from the current credential ID, to tell assertions made with
+    /// another credential apart. It is the device id itself unless the credential was rotated.
+    credential_id_hash: DeviceId,
+    public_key: CredentialPublicKey,
+    authenticator_attachment: AuthenticatorAttachment,
+    sign_count: SignCount,
+    /// The COSE algorithm the credential was registered with.
+    algorithm: i64,
+    /// The authenticator data flags at registration, to check later assertions against.
+    registration_flags: AuthenticatorDataFlags,
+    /// Whether assertions must carry the user verified (`UV`) flag, rather than only the user
+    /// present one.
+    uv_required: bool,
+    /// The transports the authenticator reported at registration, empty when unknown.
+    transports: Transports,
+}
+
+#[cfg(any(feature = "runtime", test))]
+impl Credential {
+    pub fn public_key(&self) -> &CredentialPublicKey {
+        &self.public_key
+    }
+
+    /// The COSE algorithm assertions for this credential must be signed with.
+    pub fn algorithm(&self) -> i64 {
+        self.algorithm
+    }
+
+    pub fn registration_flags(&self) -> AuthenticatorDataFlags {
+        self.registration_flags
+    }
+
+    /// Whether assertions for this credential must have the user verified.
+    pub fn uv_required(&self) -> bool {
+        self.uv_required
+    }
+
+    /// Requires (or not) user verification for this credential only, e.g. for a passkey
+    /// registered alongside security keys that can only check user presence.
+    pub fn with_uv_required(self, uv_required: bool) -> Self {
+        Self {
+            uv_required,
+            ..self
+        }
+    }
+
+    /// The highest signature counter seen for this credential.
+    pub fn sign_count(&self) -> SignCount {
+        self.sign_count
+    }
+
+    /// Applies what a successful [`verify_assertion`](Self::verify_assertion) yielded, for the
+    /// caller to store the credential again, so later assertions must advance past its counter.
+    pub fn record(&mut self, outcome: VerifyOutcome) {
+        self.sign_count = outcome.new_sign_count;
+    }
+
+    /// Whether `other` is the same credential, i.e. has the same device id, public key and
+    /// algorithm, whatever the state that changes with its use (e.g. the signature counter).
+    pub fn same_credential(&self, other: &Self) -> bool {
+        self.device_id == other.device_id
+            && self.public_key == other.public_key
+            && self.algorithm == other.algorithm
+    }
+
+    /// Whether the credential lives in a platform authenticator (e.g. "remove this device") or
+    /// in a roaming one (e.g. "remove your security key").
+    pub fn authenticator_attachment(&self) -> AuthenticatorAttachment {
+        self.authenticator_attachment
+    }
+
+    /// The transports to reach the authenticator over, e.g. to tell users how to present it.
+    pub fn transports(&self) -> Transports {
+        self.transports
+    }
+}
+
+/// What a successful assertion verification yields, for the caller to persist.
+#[cfg(any(feature = "runtime", test))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct VerifyOutcome {
+    /// The counter the stored credential should be advanced to.
+    pub new_sign_count: SignCount,
+}
+
+/// The signature counter an authenticator reports in its authenticator data.
+///
+/// See <https://www.w3.org/TR/webauthn/#sctn-sign-counter>.
+#[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default)]
+#[cfg_attr(any(feature = "runtime", test), derive(MaxEncodedLen))]
+pub struct SignCount(u32);
+
+/// A signature counter didn't advance, hinting at a cloned authenticator or a replayed response.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ReplayError;
+
+impl SignCount {
+    /// Checks `new` advances over this counter, returning the counter to store next.
+    ///
+    /// `new` must be strictly greater, except for `0`: authenticators without a counter always
+    /// report `0`, in which case the current high-water mark is kept.
+    pub fn advance(self, new: SignCount) -> Result<SignCount, ReplayError> {
+        match new.0 {
+            0 => Ok(self),
+            n if n > self.0 => Ok(new),
+            _ => Err(ReplayError),
+        }
+    }
+}
+
+impl From<u32> for SignCount {
+    fn from(value: u32) -> Self {
+        Self(value)
+    }
+}
+
+impl From<SignCount> for u32 {
+    fn from(value: SignCount) -> Self {
+        value.0
+    }
+}
+
+/// The attachment modality of the authenticator that created a credential.
+#[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(any(feature = "runtime", test), derive(MaxEncodedLen))]
+pub enum AuthenticatorAttachment {
+    Platform,
+    CrossPlatform,
+    #[default]
+    Unknown,
+}
+
+impl AuthenticatorAttachment {
+    /// Guesses the attachment from the transports an authenticator reports, given by their
+    /// WebAuthn names (`"usb"`, `"internal"`, ...): an `internal` transport means a platform
+    /// authenticator, and any other known transport a roaming one.
+    pub fn from_transports<'a>(transports: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut attachment = Self::Unknown;
+        for transport in transports {
+            match transport {
+                "internal" => return Self::Platform,
+                "usb" | "nfc" | "ble" | "hybrid" | "smart-card" => attachment = Self::CrossPlatform,
+                _ => {}
+            }
+        }
+        attachment
+    }
+}
+
+/// The transports an authenticator reports it can be reached over, for wallet UIs to tell e.g.
+/// "tap your security key" from "use your phone".
+///
+/// See <https://www.w3.org/TR/webauthn/#enum-transport>.
+#[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(any(feature = "runtime", test), derive(MaxEncodedLen))]
+pub struct Transports(u8);
+
+impl Transports {
+    pub const INTERNAL: Self = Self(0b0000_0001);
+    pub const USB: Self = Self(0b0000_0010);
+    pub const NFC: Self = Self(0b0000_0100);
+    pub const BLE: Self = Self(0b0000_1000);
+    pub const HYBRID: Self = Self(0b0001_0000);
+    pub const SMART_CARD: Self = Self(0b0010_0000);
+    /// A transport this crate doesn't know of, added to the spec after it.
+    pub const OTHER: Self = Self(0b0100_0000);
+
+    /// The transports given by their WebAuthn names (`"usb"`, `"internal"`, ...).
+    pub fn from_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Self {
+        names.into_iter().fold(Self::default(), |transports, name| {
+            transports
+                | match name {
+                    "internal" => Self::INTERNAL,
+                    "usb" => Self::USB,
+                    "nfc" => Self::NFC,
+                    "ble" => Self::BLE,
+                    "hybrid" => Self::HYBRID,
+                    "smart-card" => Self::SMART_CARD,
+                    _ => Self::OTHER,
+                }
+        })
+    }
+
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Whether all the transports set in `other` are set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Whether no transport was reported.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl core::ops::BitOr for Transports {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl From<u8> for Transports {
+    fn from(value: u8) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Transports> for u8 {
+    fn from(value: Transports) -> Self {
+        value.0
+    }
 }
 
 #[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, Clone, Copy)]
@@ -41,14 +398,142 @@ pub struct AttestationMeta<Cx> {
     pub(crate) authority_id: AuthorityId,
     pub(crate) device_id: DeviceId,
     pub(crate) context: Cx,
+    /// The `user.id` the credential was created for, which authenticators return as the user
+    /// handle of later assertions. `None` when unknown.
+    pub(crate) user_id: Option<HashedUserId>,
+}
+
+impl<Cx> AttestationMeta<Cx> {
+    pub fn new(authority_id: AuthorityId, device_id: DeviceId, context: Cx) -> Self {
+        Self {
+            authority_id,
+            device_id,
+            context,
+            user_id: None,
+        }
+    }
+
+    /// Records the `user.id` of the creation options the credential was created with.
+    pub fn with_user_id(self, user_id: HashedUserId) -> Self {
+        Self {
+            user_id: Some(user_id),
+            ..self
+        }
+    }
+
+    /// The user the credential was created for, to check later assertions are made by the
+    /// same one.
+    pub fn user_id(&self) -> Option<HashedUserId> {
+        self.user_id
+    }
+}
+
+/// The attestation statement (`attStmt`) an authenticator returns along with a new credential.
+#[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, Clone)]
+pub enum AttestationStatement {
+    /// `none` attestation: nothing binds the public key to the registration ceremony.
+    None,
+    /// `packed` self-attestation: `sig` is made with the credential private key over
+    /// `authenticator_data || sha256(client_data)`, using the COSE algorithm `alg`.
+    Packed { alg: i64, sig: Vec<u8> },
+    /// `packed` basic attestation: `sig` is made with the key of the first DER-encoded `x5c`
+    /// certificate, an attestation key shared by a batch of authenticators of the same model.
+    PackedX5c {
+        alg: i64,
+        sig: Vec<u8>,
+        x5c: Vec<Vec<u8>>,
+    },
+}
+
+impl AttestationStatement {
+    /// Whether the statement is signed with the credential key itself rather than with an
+    /// attestation key, as [`verifier::AttestationStatement::is_self_attested`] tells.
+    pub fn is_self_attested(&self) -> bool {
+        matches!(self, Self::Packed { .. })
+    }
+}
+
+impl From<verifier::AttestationStatement> for AttestationStatement {
+    fn from(value: verifier::AttestationStatement) -> Self {
+        match value {
+            verifier::AttestationStatement::None => Self::None,
+            verifier::AttestationStatement::Packed { alg, sig } => Self::Packed {
+                alg: alg.cose_id(),
+                sig,
+            },
+            verifier::AttestationStatement::PackedX5c { alg, sig, x5c } => Self::PackedX5c {
+                alg: alg.cose_id(),
+                sig,
+                x5c,
+            },
+        }
+    }
+}
+
+/// Which attestation statements a relying party accepts when registering a device.
+///
+/// Signed statements are always verified; the policy only decides whether `none`
+/// attestation is acceptable.
+pub trait AttestationPolicy: Clone + Eq + core::fmt::Debug + 'static {
+    const ALLOW_NONE: bool;
+    /// Whether `packed` self-attestation is acceptable, or only statements made with an
+    /// attestation key (with an `x5c` chain) are.
+    const ALLOW_SELF_ATTESTATION: bool = true;
+
+    /// Whether authenticators of the model `aaguid` may register. All may, unless the policy is
+    /// wrapped in a [`WithDevicePolicy`].
+    fn is_device_allowed(_aaguid: [u8; 16]) -> bool {
+        true
+    }
+
+    /// The checker asked whether the certificates of `x5c` chains were revoked. None are, unless
+    /// the policy is wrapped in a [`WithRevocationChecker`].
+    fn revocation_checker() -> impl RevocationChecker {
+        NoOpRevocationChecker
+    }
+}
+
+/// Accepts `none` attestation as well as valid signed statements.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct AllowNoneAttestation;
+
+impl AttestationPolicy for AllowNoneAttestation {
+    const ALLOW_NONE: bool = true;
+}
+
+/// Only accepts valid signed attestation statements.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct RequireSignedAttestation;
+
+impl AttestationPolicy for RequireSignedAttestation {
+    const ALLOW_NONE: bool = false;
+}
+
+/// Only accepts valid statements signed with an attestation key, i.e. with an `x5c` certificate
+/// chain, rejecting self-attestation with [`VerifyError::SelfAttestationNotAllowed`].
+///
+/// This is no guarantee the authenticator is of the model it claims: the chain isn't checked
+/// against any trusted attestation root, so anyone can make up a certificate for a key of theirs.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct RequireX5cAttestation;
+
+impl AttestationPolicy for RequireX5cAttestation {
+    const ALLOW_NONE: bool = false;
+    const ALLOW_SELF_ATTESTATION: bool = false;
 }
 
 #[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, Clone)]
-pub struct Attestation<Cx> {
+#[scale_info(skip_type_params(P))]
+pub struct Attestation<Cx, P = AllowNoneAttestation> {
     pub(crate) meta: AttestationMeta<Cx>,
     pub(crate) authenticator_data: Vec<u8>,
     pub(crate) client_data: Vec<u8>,
     pub(crate) public_key: DEREncodedPublicKey,
+    pub(crate) authenticator_attachment: AuthenticatorAttachment,
+    pub(crate) attestation_statement: AttestationStatement,
+    pub(crate) transports: Transports,
+    #[codec(skip)]
+    pub(crate) _policy: PhantomData<P>,
 }
 
 #[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, Clone, Copy)]
@@ -58,10 +543,22 @@ pub struct AssertionMeta<Cx> {
     pub(crate) context: Cx,
 }
 
+impl<Cx> AssertionMeta<Cx> {
+    pub fn new(authority_id: AuthorityId, user_id: HashedUserId, context: Cx) -> Self {
+        Self {
+            authority_id,
+            user_id,
+            context,
+        }
+    }
+}
+
 #[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, Clone)]
 pub struct Assertion<Cx> {
     pub(crate) meta: AssertionMeta<Cx>,
     pub(crate) authenticator_data: Vec<u8>,
     pub(crate) client_data: Vec<u8>,
     pub(crate) signature: Vec<u8>,
+    /// The `rawId` of the credential, empty when unknown.
+    pub(crate) credential_id: Vec<u8>,
 }
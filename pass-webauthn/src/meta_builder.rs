@@ -0,0 +1,162 @@
+//! Builders of [`AttestationMeta`] and [`AssertionMeta`] that check every field was set, for
+//! server code filling them in from several sources.
+
+use alloc::vec::Vec;
+use core::fmt;
+use traits_authn::{AuthorityId, DeviceId, HashedUserId};
+
+use crate::{AssertionMeta, AttestationMeta};
+
+/// Why a meta couldn't be built.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildError {
+    /// The required fields that weren't set, or were set to all zeros, in declaration order.
+    pub missing: Vec<&'static str>,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "missing {}", self.missing.join(", "))
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BuildError {}
+
+/// Collects the names of missing fields.
+#[derive(Default)]
+struct Missing(Vec<&'static str>);
+
+impl Missing {
+    /// The value of an id, which counts as missing when all zeros, as ids are hashes.
+    fn id(&mut self, name: &'static str, id: Option<[u8; 32]>) -> [u8; 32] {
+        match id {
+            Some(id) if id != [0; 32] => id,
+            _ => {
+                self.0.push(name);
+                [0; 32]
+            }
+        }
+    }
+
+    fn value<T>(&mut self, name: &'static str, value: Option<T>) -> Option<T> {
+        if value.is_none() {
+            self.0.push(name);
+        }
+        value
+    }
+
+    fn finish<T>(self, value: Option<T>) -> Result<T, BuildError> {
+        match value {
+            Some(value) if self.0.is_empty() => Ok(value),
+            _ => Err(BuildError { missing: self.0 }),
+        }
+    }
+}
+
+/// Builds an [`AttestationMeta`].
+#[derive(Debug, Clone)]
+pub struct AttestationMetaBuilder<Cx> {
+    authority_id: Option<AuthorityId>,
+    device_id: Option<DeviceId>,
+    context: Option<Cx>,
+    user_id: Option<HashedUserId>,
+}
+
+impl<Cx> Default for AttestationMetaBuilder<Cx> {
+    fn default() -> Self {
+        Self {
+            authority_id: None,
+            device_id: None,
+            context: None,
+            user_id: None,
+        }
+    }
+}
+
+impl<Cx> AttestationMetaBuilder<Cx> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn authority_id(mut self, authority_id: AuthorityId) -> Self {
+        self.authority_id = Some(authority_id);
+        self
+    }
+
+    pub fn device_id(mut self, device_id: DeviceId) -> Self {
+        self.device_id = Some(device_id);
+        self
+    }
+
+    pub fn context(mut self, context: Cx) -> Self {
+        self.context = Some(context);
+        self
+    }
+
+    /// Optional, unlike the other fields.
+    pub fn user_id(mut self, user_id: HashedUserId) -> Self {
+        self.user_id = Some(user_id);
+        self
+    }
+
+    /// Fails listing the required fields that weren't set, or are all zeros.
+    pub fn build(self) -> Result<AttestationMeta<Cx>, BuildError> {
+        let mut missing = Missing::default();
+        let authority_id = missing.id("authority_id", self.authority_id);
+        let device_id = missing.id("device_id", self.device_id);
+        let context = missing.value("context", self.context);
+        missing.finish(context.map(|context| AttestationMeta {
+            user_id: self.user_id,
+            ..AttestationMeta::new(authority_id, device_id, context)
+        }))
+    }
+}
+
+/// Builds an [`AssertionMeta`].
+#[derive(Debug, Clone)]
+pub struct AssertionMetaBuilder<Cx> {
+    authority_id: Option<AuthorityId>,
+    user_id: Option<HashedUserId>,
+    context: Option<Cx>,
+}
+
+impl<Cx> Default for AssertionMetaBuilder<Cx> {
+    fn default() -> Self {
+        Self {
+            authority_id: None,
+            user_id: None,
+            context: None,
+        }
+    }
+}
+
+impl<Cx> AssertionMetaBuilder<Cx> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn authority_id(mut self, authority_id: AuthorityId) -> Self {
+        self.authority_id = Some(authority_id);
+        self
+    }
+
+    pub fn user_id(mut self, user_id: HashedUserId) -> Self {
+        self.user_id = Some(user_id);
+        self
+    }
+
+    pub fn context(mut self, context: Cx) -> Self {
+        self.context = Some(context);
+        self
+    }
+
+    /// Fails listing the fields that weren't set, or are all zeros.
+    pub fn build(self) -> Result<AssertionMeta<Cx>, BuildError> {
+        let mut missing = Missing::default();
+        let authority_id = missing.id("authority_id", self.authority_id);
+        let user_id = missing.id("user_id", self.user_id);
+        let context = missing.value("context", self.context);
+        missing.finish(context.map(|context| AssertionMeta::new(authority_id, user_id, context)))
+    }
+}
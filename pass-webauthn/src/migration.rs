@@ -0,0 +1,60 @@
+//! Storage migrations for [`Credential`].
+//!
+//! [`CREDENTIAL_STORAGE_VERSION`] is the version of the current layout. Runtimes storing
+//! credentials bump the storage version of the pallet holding them to it, and `translate`
+//! entries stored before it from the [`LegacyCredential`] layout with [`migrate_credential`].
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::traits::StorageVersion;
+use scale_info::TypeInfo;
+use traits_authn::DeviceId;
+use verifier::{AuthenticatorDataFlags, CoseAlgorithm, VerifyError};
+
+use crate::{
+    AuthenticatorAttachment, Credential, CredentialPublicKey, DEREncodedPublicKey, SignCount,
+    Transports, DER_ENCODED_PUBLIC_KEY_LEN,
+};
+
+/// The version of the [`Credential`] layout, bumped on every change of it.
+pub const CREDENTIAL_STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+/// A [`Credential`] as first stored: the device id and the 91 byte DER-encoded public key,
+/// before keys were compressed and the credential tracked anything else.
+#[derive(MaxEncodedLen, TypeInfo, Decode, Encode)]
+pub struct LegacyCredential {
+    pub device_id: DeviceId,
+    pub public_key: [u8; DER_ENCODED_PUBLIC_KEY_LEN],
+}
+
+impl TryFrom<LegacyCredential> for Credential {
+    type Error = VerifyError;
+
+    /// Legacy credentials could only hold P-256 keys, so they're taken as ES256 ones. What they
+    /// didn't track is left at the values that check nothing: an unknown attachment and
+    /// transports, no signature counter seen yet, no backup flags at registration, and only
+    /// user presence required, as it was then. Their credential ID is taken to hash to their
+    /// device id, the way the pallet keys the devices it registers.
+    fn try_from(value: LegacyCredential) -> Result<Self, Self::Error> {
+        let public_key = DEREncodedPublicKey::try_from(&value.public_key[..])?;
+        Ok(Credential {
+            device_id: value.device_id,
+            credential_id_hash: value.device_id,
+            public_key: CredentialPublicKey::try_from(&public_key)?,
+            authenticator_attachment: AuthenticatorAttachment::Unknown,
+            sign_count: SignCount::default(),
+            algorithm: CoseAlgorithm::ES256.cose_id(),
+            registration_flags: AuthenticatorDataFlags::default(),
+            uv_required: false,
+            transports: Transports::default(),
+        })
+    }
+}
+
+/// Maps a stored [`LegacyCredential`] to the current layout, suitable as the closure of a
+/// storage `translate`. Entries whose key doesn't decode are dropped, as they could never
+/// verify an assertion anyway.
+pub fn migrate_credential(legacy: LegacyCredential) -> Option<Credential> {
+    Credential::try_from(legacy)
+        .inspect_err(|e| log::warn!("Dropping credential with an invalid public key: {e:?}"))
+        .ok()
+}
@@ -0,0 +1,143 @@
+//! Conversions from [`passkey_types`] WebAuthn responses into [`Attestation`] and [`Assertion`].
+//!
+//! These allow anyone driving an authenticator through passkey-rs to build the pallet types
+//! without destructuring the responses by hand. The parts that cannot be derived from the
+//! response itself (authority, device and user ids, challenge context) are passed in as meta.
+
+use core::marker::PhantomData;
+use passkey_types::webauthn::{
+    self, AuthenticatedPublicKeyCredential, AuthenticatorTransport, CreatedPublicKeyCredential,
+};
+
+use traits_authn::HashedUserId;
+use verifier::{parse_attestation_object, CoseAlgorithm, VerifyError};
+
+use crate::{
+    ids, Assertion, AssertionMeta, Attestation, AttestationMeta, AttestationStatement,
+    AuthenticatorAttachment, DEREncodedPublicKey, ResponseError, Transports,
+};
+
+/// Decodes `public_key` as the key type of the COSE `algorithm` it's used with.
+fn der_encoded_public_key(
+    algorithm: i64,
+    public_key: &[u8],
+) -> Result<DEREncodedPublicKey, ResponseError> {
+    match CoseAlgorithm::try_from(algorithm) {
+        Ok(CoseAlgorithm::ES256) => {
+            DEREncodedPublicKey::try_from(public_key).map_err(|_| ResponseError::InvalidPublicKey)
+        }
+        Err(_) => Err(ResponseError::UnsupportedAlgorithm),
+    }
+}
+
+impl From<webauthn::AuthenticatorAttachment> for AuthenticatorAttachment {
+    fn from(value: webauthn::AuthenticatorAttachment) -> Self {
+        match value {
+            webauthn::AuthenticatorAttachment::Platform => Self::Platform,
+            webauthn::AuthenticatorAttachment::CrossPlatform => Self::CrossPlatform,
+        }
+    }
+}
+
+fn attestation_statement(attestation_object: &[u8]) -> Result<AttestationStatement, ResponseError> {
+    parse_attestation_object(attestation_object)
+        .map(|attestation_object| attestation_object.statement.into())
+        .map_err(|e| match e {
+            VerifyError::UnsupportedAttestationFormat | VerifyError::UnsupportedAlgorithm => {
+                ResponseError::UnsupportedAttestationFormat
+            }
+            _ => ResponseError::InvalidAttestationObject,
+        })
+}
+
+fn transport_name(transport: &AuthenticatorTransport) -> &'static str {
+    match transport {
+        AuthenticatorTransport::Usb => "usb",
+        AuthenticatorTransport::Nfc => "nfc",
+        AuthenticatorTransport::Ble => "ble",
+        AuthenticatorTransport::Hybrid => "hybrid",
+        AuthenticatorTransport::Internal => "internal",
+    }
+}
+
+impl<Cx, P> TryFrom<(CreatedPublicKeyCredential, AttestationMeta<Cx>)> for Attestation<Cx, P> {
+    type Error = ResponseError;
+
+    fn try_from(
+        (credential, meta): (CreatedPublicKeyCredential, AttestationMeta<Cx>),
+    ) -> Result<Self, Self::Error> {
+        let public_key = credential
+            .response
+            .public_key
+            .ok_or(ResponseError::MissingPublicKey)?;
+        let authenticator_attachment = credential
+            .authenticator_attachment
+            .map(Into::into)
+            .unwrap_or_else(|| {
+                AuthenticatorAttachment::from_transports(
+                    credential
+                        .response
+                        .transports
+                        .iter()
+                        .flatten()
+                        .map(transport_name),
+                )
+            });
+        let transports = Transports::from_names(
+            credential
+                .response
+                .transports
+                .iter()
+                .flatten()
+                .map(transport_name),
+        );
+        let attestation_statement = attestation_statement(&credential.response.attestation_object)?;
+
+        Ok(Attestation {
+            meta,
+            authenticator_data: credential.response.authenticator_data.into(),
+            client_data: credential.response.client_data_json.into(),
+            public_key: der_encoded_public_key(
+                credential.response.public_key_algorithm,
+                &public_key,
+            )?,
+            authenticator_attachment,
+            attestation_statement,
+            transports,
+            _policy: PhantomData,
+        })
+    }
+}
+
+/// The user an assertion response is for, as given by its `user_handle`.
+///
+/// The handle is optional: authenticators may leave it out for credentials that aren't
+/// discoverable, so `None` is returned rather than failing, and the caller tells the user from
+/// the credential it asked for instead. Handles that aren't a [`HashedUserId`] yield `None` too.
+pub fn user_id_from_user_handle(
+    credential: &AuthenticatedPublicKeyCredential,
+) -> Option<HashedUserId> {
+    ids::HashedUserId::from_user_handle(credential.response.user_handle.as_ref()?).map(Into::into)
+}
+
+impl<Cx> TryFrom<(AuthenticatedPublicKeyCredential, AssertionMeta<Cx>)> for Assertion<Cx> {
+    type Error = ResponseError;
+
+    fn try_from(
+        (credential, meta): (AuthenticatedPublicKeyCredential, AssertionMeta<Cx>),
+    ) -> Result<Self, Self::Error> {
+        if let Some(user_handle) = &credential.response.user_handle {
+            if user_handle.as_slice() != meta.user_id.as_slice() {
+                return Err(ResponseError::UserHandleMismatch);
+            }
+        }
+
+        Ok(Assertion {
+            meta,
+            authenticator_data: credential.response.authenticator_data.into(),
+            client_data: credential.response.client_data_json.into(),
+            signature: credential.response.signature.into(),
+            credential_id: credential.raw_id.into(),
+        })
+    }
+}
@@ -0,0 +1,118 @@
+//! Registration straight from the raw responses of `navigator.credentials.create()`.
+
+use alloc::vec::Vec;
+use traits_authn::{Challenger, DeviceId};
+use verifier::{
+    compress_public_key_der, cose_key_algorithm, cose_key_to_der, parse_authenticator_data,
+    AuthenticatorDataFlags, VerifyError,
+};
+
+use crate::{
+    runtime_helpers::ClientData, AuthenticatorAttachment, ClientDataError, Credential,
+    CredentialPublicKey, CxOf, SignCount, Transports, WebAuthnError,
+};
+
+/// Everything a relying party learns about a credential when registering it.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CredentialRecord {
+    pub device_id: DeviceId,
+    /// Identifies the authenticator model; all zeroes for authenticators that don't disclose it.
+    pub aaguid: [u8; 16],
+    pub credential_id: Vec<u8>,
+    pub public_key: CredentialPublicKey,
+    /// The COSE algorithm of the public key.
+    pub algorithm: i64,
+    pub sign_count: SignCount,
+    pub flags: AuthenticatorDataFlags,
+    /// The transports the browser reported along with the response, if it did.
+    pub transports: Option<Transports>,
+}
+
+/// Registers a credential from an attestation with a `none` statement, the format most passkey
+/// providers use: nothing but the challenge binds the key to the ceremony, so only the
+/// authenticator data and the client data are needed.
+///
+/// The credential ID, its AAGUID and its COSE public key are read from the attested credential
+/// data, the device id derived from the credential ID with `device_id_of`, and the challenge in
+/// the client data checked against the one `Ch` issued for `context`.
+pub fn register_none_attestation<Ch: Challenger>(
+    context: &CxOf<Ch>,
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    device_id_of: impl FnOnce(&[u8]) -> DeviceId,
+) -> Result<CredentialRecord, WebAuthnError> {
+    register::<Ch>(
+        context,
+        authenticator_data,
+        client_data_json,
+        None,
+        device_id_of,
+    )
+}
+
+/// Like [`register_none_attestation`], also recording the transports the browser reported
+/// with `getTransports()`, by their names (e.g. `"usb"`), so later authentications can hint
+/// them.
+pub fn register_none_attestation_with_transports<'a, Ch: Challenger>(
+    context: &CxOf<Ch>,
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    transports: impl IntoIterator<Item = &'a str>,
+    device_id_of: impl FnOnce(&[u8]) -> DeviceId,
+) -> Result<CredentialRecord, WebAuthnError> {
+    register::<Ch>(
+        context,
+        authenticator_data,
+        client_data_json,
+        Some(Transports::from_names(transports)),
+        device_id_of,
+    )
+}
+
+fn register<Ch: Challenger>(
+    context: &CxOf<Ch>,
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    transports: Option<Transports>,
+    device_id_of: impl FnOnce(&[u8]) -> DeviceId,
+) -> Result<CredentialRecord, WebAuthnError> {
+    let challenge = ClientData::parse(client_data_json)
+        .challenge
+        .ok_or(ClientDataError::MissingChallenge)?;
+    Ch::check_challenge(context, &challenge).ok_or(WebAuthnError::InvalidChallenge)?;
+
+    let parsed = parse_authenticator_data(authenticator_data)?;
+    let attested = parsed.attested_credential_data.ok_or_else(|| {
+        log::warn!("The authenticator data has no attested credential data");
+        VerifyError::ParseAuthenticatorData
+    })?;
+    let algorithm = cose_key_algorithm(&attested.credential_public_key)?;
+    let public_key_der = cose_key_to_der(&attested.credential_public_key)?;
+
+    Ok(CredentialRecord {
+        device_id: device_id_of(attested.credential_id),
+        aaguid: attested.aaguid,
+        credential_id: attested.credential_id.to_vec(),
+        public_key: CredentialPublicKey::Es256(compress_public_key_der(&public_key_der)?),
+        algorithm: algorithm.cose_id(),
+        sign_count: parsed.sign_count.into(),
+        flags: parsed.flags,
+        transports,
+    })
+}
+
+impl From<CredentialRecord> for Credential {
+    fn from(value: CredentialRecord) -> Self {
+        Credential {
+            device_id: value.device_id,
+            credential_id_hash: value.device_id,
+            public_key: value.public_key,
+            authenticator_attachment: AuthenticatorAttachment::Unknown,
+            sign_count: value.sign_count,
+            algorithm: value.algorithm,
+            registration_flags: value.flags,
+            uv_required: false,
+            transports: value.transports.unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! Building blocks to reject replayed assertions.
+//!
+//! A challenge stays valid for as long as its challenger accepts its context (e.g. a window of
+//! blocks), so a captured assertion could be submitted again within that window. Recording a
+//! nonce for every accepted assertion, and refusing those already seen, closes that gap.
+
+use sha2::{Digest, Sha256};
+use traits_authn::{Challenge, DeviceId};
+
+/// Identifies an assertion for replay protection purposes.
+pub type ReplayNonce = [u8; 32];
+
+/// Derives the [`ReplayNonce`] of an assertion made with `device_id` over `challenge`.
+///
+/// The signature is included so different assertions for the same challenge (which a
+/// challenger may allow) get different nonces.
+pub fn replay_nonce(device_id: &DeviceId, challenge: &Challenge, signature: &[u8]) -> ReplayNonce {
+    Sha256::new()
+        .chain_update(device_id)
+        .chain_update(challenge)
+        .chain_update(Sha256::digest(signature))
+        .finalize()
+        .into()
+}
+
+/// Records the nonces of accepted assertions, grouped by the challenge context they were made
+/// for, so they can be pruned once no challenge for that context is valid anymore.
+///
+/// Typically backed by a storage double map of `context => nonce => ()`.
+pub trait ReplayGuard<Cx> {
+    fn was_used(context: &Cx, nonce: &ReplayNonce) -> bool;
+
+    fn mark_used(context: &Cx, nonce: ReplayNonce);
+
+    /// Forgets every nonce recorded for `context`.
+    fn prune(context: &Cx);
+}
@@ -0,0 +1,53 @@
+//! Rejecting attestations whose certificates were revoked.
+//!
+//! Runtimes can't query the OCSP responders of certificate authorities, so the checker usually
+//! reads what was brought on chain instead, e.g. the revoked certificates an off-chain worker
+//! or a governance call stored.
+
+use core::{fmt, marker::PhantomData};
+use verifier::RevocationChecker;
+
+use crate::AttestationPolicy;
+
+/// The attestation policy `P`, also asking the checker `R` about every certificate of `x5c`
+/// chains, and rejecting those it reports revoked with
+/// [`VerifyError::AttestationCertificateRevoked`](verifier::VerifyError::AttestationCertificateRevoked).
+pub struct WithRevocationChecker<P, R>(PhantomData<(P, R)>);
+
+impl<P, R> AttestationPolicy for WithRevocationChecker<P, R>
+where
+    P: AttestationPolicy,
+    R: RevocationChecker + Default + 'static,
+{
+    const ALLOW_NONE: bool = P::ALLOW_NONE;
+    const ALLOW_SELF_ATTESTATION: bool = P::ALLOW_SELF_ATTESTATION;
+
+    fn is_device_allowed(aaguid: [u8; 16]) -> bool {
+        P::is_device_allowed(aaguid)
+    }
+
+    fn revocation_checker() -> impl RevocationChecker {
+        R::default()
+    }
+}
+
+// Not derived, as checkers needn't implement any of these.
+impl<P, R> Clone for WithRevocationChecker<P, R> {
+    fn clone(&self) -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<P, R> PartialEq for WithRevocationChecker<P, R> {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl<P, R> Eq for WithRevocationChecker<P, R> {}
+
+impl<P, R> fmt::Debug for WithRevocationChecker<P, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WithRevocationChecker")
+    }
+}
@@ -0,0 +1,140 @@
+//! The WebAuthn relying party an authority stands for.
+//!
+//! Each authority is its own relying party, a subdomain of the base domain the runtime's
+//! frontends are served from: the authority `pass_web` under `pass.int` is the RP ID
+//! `pass-web.pass.int`, at the origin `https://pass-web.pass.int`.
+
+use alloc::{format, string::String};
+use core::fmt;
+use sha2::{Digest, Sha256};
+use traits_authn::AuthorityId;
+
+/// Longest DNS label.
+const MAX_LABEL_LEN: usize = 63;
+
+/// Longest domain name.
+const MAX_DOMAIN_LEN: usize = 253;
+
+/// Why an authority or base domain can't make an RP ID, or an origin isn't the one of an RP ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpIdError {
+    /// The authority id is all zeros.
+    EmptyAuthority,
+    /// The authority id has a byte that isn't an ASCII letter, digit, `-` or `_`, or a zero byte
+    /// before its end.
+    InvalidAuthority,
+    /// The base domain isn't a lowercase domain name, e.g. it has a scheme or a port.
+    InvalidBaseDomain,
+    /// The RP ID would be longer than a domain name can be.
+    TooLong,
+    /// The RP ID isn't a subdomain of the base domain.
+    NotUnderBaseDomain,
+    /// The origin doesn't use `https`.
+    InsecureOrigin,
+    /// The origin is for another host.
+    OriginMismatch,
+}
+
+impl fmt::Display for RpIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::EmptyAuthority => "empty authority id",
+            Self::InvalidAuthority => "authority id isn't a DNS label",
+            Self::InvalidBaseDomain => "invalid base domain",
+            Self::TooLong => "relying party id too long",
+            Self::NotUnderBaseDomain => "relying party id isn't under the base domain",
+            Self::InsecureOrigin => "origin doesn't use https",
+            Self::OriginMismatch => "origin is for another relying party",
+        })
+    }
+}
+
+/// A relying party id, a domain name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpId(String);
+
+impl RpId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The `rpIdHash` authenticators put at the start of the authenticator data.
+    pub fn hash(&self) -> [u8; 32] {
+        Sha256::digest(self.0.as_bytes()).into()
+    }
+
+    /// The origin ceremonies for this relying party are run at.
+    pub fn origin(&self) -> String {
+        format!("https://{}", self.0)
+    }
+
+    /// Checks `origin`, as found in a client data, is [`Self::origin`].
+    pub fn check_origin(&self, origin: &str) -> Result<(), RpIdError> {
+        let host = origin
+            .strip_prefix("https://")
+            .ok_or(RpIdError::InsecureOrigin)?;
+        (host == self.0)
+            .then_some(())
+            .ok_or(RpIdError::OriginMismatch)
+    }
+}
+
+impl fmt::Display for RpId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The RP ID of `authority` under `base_domain`.
+///
+/// An authority id is a name (like the `PalletId` it's usually taken from) padded with zero
+/// bytes, which becomes the leftmost label of the RP ID after trimming the padding. As DNS labels
+/// can't have underscores, `_` is replaced with `-`, and uppercase letters are lowercased; any
+/// other byte that isn't an ASCII letter, digit or `-` is rejected rather than escaped, so no two
+/// authorities share an RP ID by accident.
+pub fn expected_rp_id(authority: &AuthorityId, base_domain: &str) -> Result<RpId, RpIdError> {
+    let len = authority
+        .iter()
+        .rposition(|&byte| byte != 0)
+        .ok_or(RpIdError::EmptyAuthority)?
+        + 1;
+    let label: String = authority[..len]
+        .iter()
+        .map(|&byte| match byte {
+            b'a'..=b'z' | b'0'..=b'9' | b'-' => Ok(char::from(byte)),
+            b'A'..=b'Z' => Ok(char::from(byte.to_ascii_lowercase())),
+            b'_' => Ok('-'),
+            _ => Err(RpIdError::InvalidAuthority),
+        })
+        .collect::<Result<_, _>>()?;
+    if !is_label(&label) {
+        return Err(RpIdError::InvalidAuthority);
+    }
+    if base_domain.is_empty() || !base_domain.split('.').all(is_label) {
+        return Err(RpIdError::InvalidBaseDomain);
+    }
+
+    let rp_id = format!("{label}.{base_domain}");
+    if rp_id.len() > MAX_DOMAIN_LEN {
+        return Err(RpIdError::TooLong);
+    }
+    Ok(RpId(rp_id))
+}
+
+/// The `rpIdHash` of the [`expected_rp_id`] of `authority` under `base_domain`.
+pub fn expected_rp_id_hash(
+    authority: &AuthorityId,
+    base_domain: &str,
+) -> Result<[u8; 32], RpIdError> {
+    expected_rp_id(authority, base_domain).map(|rp_id| rp_id.hash())
+}
+
+/// Whether `label` is a lowercase DNS label: letters, digits and hyphens, not at either end.
+pub(crate) fn is_label(label: &str) -> bool {
+    (1..=MAX_LABEL_LEN).contains(&label.len())
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+        && label
+            .bytes()
+            .all(|byte| matches!(byte, b'a'..=b'z' | b'0'..=b'9' | b'-'))
+}
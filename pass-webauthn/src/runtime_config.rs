@@ -0,0 +1,32 @@
+//! The types a runtime verifies WebAuthn responses with, named once.
+
+use frame_support::traits::Get;
+use frame_system::pallet_prelude::BlockNumberFor;
+use traits_authn::{AuthorityId, Challenger};
+
+use crate::{Assertion, Attestation, AttestationPolicy, Authenticator, Device};
+
+/// The challenger, authority and attestation policy of a runtime, with the bounds the generic
+/// types of this crate put on them, so a runtime states them once and names the types through
+/// the aliases below, e.g. `type Authenticator = AuthenticatorOf<Runtime>` in its
+/// `pallet_pass::Config`.
+///
+/// Challenges are issued for block numbers: runtimes using another context, like the buckets of
+/// a [`TimestampChallenger`](crate::TimestampChallenger), spell the generic types out.
+pub trait SubstrateWebAuthnRuntime: frame_system::Config {
+    type Challenger: Challenger<Context = BlockNumberFor<Self>>;
+    /// The authority credentials are registered with, usually the one of the pass pallet.
+    type Authority: Get<AuthorityId>;
+    type AttestationPolicy: AttestationPolicy;
+}
+
+pub type AuthenticatorOf<T> = Authenticator<
+    <T as SubstrateWebAuthnRuntime>::Challenger,
+    <T as SubstrateWebAuthnRuntime>::Authority,
+    <T as SubstrateWebAuthnRuntime>::AttestationPolicy,
+>;
+pub type DeviceOf<T> =
+    Device<<T as SubstrateWebAuthnRuntime>::Challenger, <T as SubstrateWebAuthnRuntime>::Authority>;
+pub type AttestationOf<T> =
+    Attestation<BlockNumberFor<T>, <T as SubstrateWebAuthnRuntime>::AttestationPolicy>;
+pub type AssertionOf<T> = Assertion<BlockNumberFor<T>>;
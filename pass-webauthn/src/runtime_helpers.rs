@@ -1,32 +1,139 @@
-use codec::Decode;
-use frame_support::sp_runtime::traits::TrailingZeroInput;
-use scale_info::prelude::{string::String, vec::Vec};
+use alloc::string::String;
+use traits_authn::{AuthorityId, Challenge};
+use verifier::{
+    parse_authenticator_data, AuthenticatorDataFlags, ClientDataLite, VerifyError, VerifyLimits,
+};
 
-use traits_authn::Challenge;
+use crate::{expected_rp_id, ClientDataError, WebAuthnError};
 
 use base64::prelude::BASE64_URL_SAFE_NO_PAD;
 
-pub fn find_challenge_from_client_data(client_data: Vec<u8>) -> Option<Challenge> {
-    get_from_json_then_map(client_data, "challenge", |challenge| {
-        base64::decode_engine(challenge.as_bytes(), &BASE64_URL_SAFE_NO_PAD).ok()
-    })
+/// Reported as the used challenge of a response whose challenge couldn't be extracted.
+///
+/// Such responses are never valid anyway; unlike `Default::default()`, this is not a value a
+/// misconfigured challenger is likely to fall back to.
+pub const UNEXTRACTABLE_CHALLENGE: Challenge = [0xff; 32];
+
+/// Longest decoded challenge accepted when extracting it from the client data.
+const MAX_CHALLENGE_LEN: usize = 64;
+
+/// Room for the escaped strings of a client data, which real clients rarely produce. Kept on the
+/// stack, so extracting the challenge doesn't allocate.
+const MAX_UNESCAPED_LEN: usize = 512;
+
+/// The fields of a client data the runtime checks, extracted from a [`ClientDataLite`].
+pub struct ClientData {
+    pub challenge: Option<Challenge>,
+}
+
+impl ClientData {
+    pub fn parse(client_data: &[u8]) -> Self {
+        Self {
+            challenge: find_challenge_from_client_data(client_data),
+        }
+    }
+}
+
+/// Logs the type of a client data and the lengths of its origin and challenge at debug level,
+/// enough to tell a mangled client data apart without logging its contents. Nothing is parsed
+/// unless the log level is enabled for `target`.
+pub fn log_client_data(target: &str, client_data: &[u8]) {
+    if !log::log_enabled!(target: target, log::Level::Debug) {
+        return;
+    }
+    let mut unescape_buffer = [0u8; MAX_UNESCAPED_LEN];
+    match ClientDataLite::parse(client_data, &mut unescape_buffer, &VerifyLimits::DEFAULT) {
+        Ok(client_data) => log::debug!(
+            target: target,
+            "Client data of type {:?}, with a {} byte origin and a {} byte challenge",
+            client_data.r#type,
+            client_data.origin.len(),
+            client_data.challenge.len()
+        ),
+        Err(e) => log::debug!(
+            target: target,
+            "Client data of {} bytes can't be parsed: {e:?}",
+            client_data.len()
+        ),
+    }
+}
+
+/// The origin the client data was produced at, for error messages.
+pub fn find_origin_from_client_data(client_data: &[u8]) -> Option<String> {
+    let mut unescape_buffer = [0u8; MAX_UNESCAPED_LEN];
+    ClientDataLite::parse(client_data, &mut unescape_buffer, &VerifyLimits::DEFAULT)
+        .ok()
+        .map(|client_data| client_data.origin.into())
 }
 
-pub fn get_from_json_then_map<T>(
-    json: Vec<u8>,
-    key: &str,
-    map: impl FnOnce(&str) -> Option<Vec<u8>>,
-) -> Option<T>
-where
-    T: Decode,
-{
-    let json = String::from_utf8(json).ok()?;
+pub fn find_challenge_from_client_data(client_data: &[u8]) -> Option<Challenge> {
+    let mut unescape_buffer = [0u8; MAX_UNESCAPED_LEN];
+    let client_data =
+        ClientDataLite::parse(client_data, &mut unescape_buffer, &VerifyLimits::DEFAULT).ok()?;
 
-    let value = json
-        .split(",")
-        .find_map(|kv| kv.contains(key).then_some(kv.split_once(":")?.1))
-        .map(|v| v.trim_matches(|c: char| c.eq(&' ') || c.eq(&'"')))
-        .and_then(map)?;
+    let mut decoded = [0u8; MAX_CHALLENGE_LEN];
+    let len =
+        base64::decode_engine_slice(client_data.challenge, &mut decoded, &BASE64_URL_SAFE_NO_PAD)
+            .ok()?;
+    strict_decode_fixed(&decoded[..len])
+}
+
+/// Checks the `type` of `client_data` is `expected`, so a response made for one ceremony can't be
+/// passed off as one for the other.
+pub fn check_client_data_type(client_data: &[u8], expected: &str) -> Result<(), WebAuthnError> {
+    let mut unescape_buffer = [0u8; MAX_UNESCAPED_LEN];
+    let client_data =
+        ClientDataLite::parse(client_data, &mut unescape_buffer, &VerifyLimits::DEFAULT)?;
+    if client_data.r#type != expected {
+        log::warn!(
+            "Client data of type {:?}, not {expected}",
+            client_data.r#type
+        );
+        return Err(ClientDataError::TypeMismatch.into());
+    }
+    Ok(())
+}
+
+/// Checks the authenticator reported the user as present, which every ceremony requires, whether
+/// or not the user was also verified.
+pub fn check_user_present(flags: AuthenticatorDataFlags) -> Result<(), WebAuthnError> {
+    if !flags.contains(AuthenticatorDataFlags::USER_PRESENT) {
+        log::warn!("The authenticator didn't report the user as present");
+        return Err(WebAuthnError::UserNotPresent);
+    }
+    Ok(())
+}
 
-    Decode::decode(&mut TrailingZeroInput::new(value.as_ref())).ok()
+/// The `N` bytes of `input`, if it has exactly that many.
+///
+/// Unlike decoding through a `TrailingZeroInput`, shorter inputs aren't zero-extended and longer
+/// ones aren't truncated, so distinct inputs never yield the same array.
+pub fn strict_decode_fixed<const N: usize>(input: &[u8]) -> Option<[u8; N]> {
+    input.try_into().ok()
+}
+
+/// Checks a response was produced for the relying party of `authority` under `base_domain`: that
+/// the `rpIdHash` of `authenticator_data` is the one of its RP ID, and the origin in
+/// `client_data` is its `https` origin.
+pub fn check_rp_id(
+    authority: &AuthorityId,
+    base_domain: &str,
+    authenticator_data: &[u8],
+    client_data: &[u8],
+) -> Result<(), WebAuthnError> {
+    let rp_id = expected_rp_id(authority, base_domain).map_err(|e| {
+        log::warn!("No relying party for the authority under {base_domain}: {e}");
+        WebAuthnError::RpIdMismatch
+    })?;
+
+    if parse_authenticator_data(authenticator_data)?.rp_id_hash != rp_id.hash() {
+        log::warn!("The authenticator data isn't for {rp_id}");
+        return Err(WebAuthnError::RpIdMismatch);
+    }
+
+    let origin = find_origin_from_client_data(client_data).ok_or(VerifyError::ParseClientData)?;
+    rp_id.check_origin(&origin).map_err(|e| {
+        log::warn!("Unexpected origin {origin} for {rp_id}: {e}");
+        WebAuthnError::RpIdMismatch
+    })
 }
@@ -1,12 +1,86 @@
 use super::*;
 use traits_authn::{HashedUserId, UserChallengeResponse};
+use verifier::credential_id_from_authenticator_data;
+
+use crate::{
+    replay::{replay_nonce, ReplayGuard, ReplayNonce},
+    ClientDataError, WebAuthnError,
+};
+
+/// Checks run on assertions are logged at debug level under this target, and the keys and
+/// signatures they involve only at trace level, as for attestations.
+pub(super) const LOG_TARGET: &str = "pass_webauthn::assertion";
+
+impl<Cx> Assertion<Cx> {
+    /// The ID of the credential that made this assertion, falling back to the one in the
+    /// attested credential data of the authenticator data for assertions that don't carry it.
+    pub fn try_credential_id(&self) -> Option<&[u8]> {
+        if self.credential_id.is_empty() {
+            credential_id_from_authenticator_data(&self.authenticator_data)
+        } else {
+            Some(&self.credential_id)
+        }
+    }
+}
 
 impl<Cx> Assertion<Cx>
 where
     Cx: Parameter,
 {
-    fn challenge(&self) -> Challenge {
-        find_challenge_from_client_data(self.client_data.clone()).unwrap_or_default()
+    fn challenge(&self) -> Option<Challenge> {
+        let challenge = ClientData::parse(&self.client_data).challenge;
+        if challenge.is_none() {
+            log::warn!(target: LOG_TARGET, "Failed to extract `challenge` from client data");
+        }
+        challenge
+    }
+
+    /// Checks the assertion carries a challenge. The signature is checked against the stored
+    /// [`Credential`](crate::Credential) instead.
+    #[must_use = "the assertion is only validated if the result is checked"]
+    pub fn validate(&self) -> Result<(), WebAuthnError> {
+        log_client_data(LOG_TARGET, &self.client_data);
+        log::debug!(target: LOG_TARGET, "Checking the challenge");
+        self.challenge()
+            .map(|_| ())
+            .ok_or(ClientDataError::MissingChallenge.into())
+    }
+
+    /// Checks the assertion was produced for the relying party of its authority under
+    /// `base_domain`, as [`expected_rp_id`](crate::expected_rp_id) names it.
+    #[must_use = "a response for another relying party is only rejected if the result is checked"]
+    pub fn check_rp_id(&self, base_domain: &str) -> Result<(), WebAuthnError> {
+        check_rp_id(
+            &self.meta.authority_id,
+            base_domain,
+            &self.authenticator_data,
+            &self.client_data,
+        )
+    }
+
+    /// The [`ReplayNonce`] of this assertion, when made with `device_id`.
+    pub fn replay_nonce(&self, device_id: &DeviceId) -> ReplayNonce {
+        replay_nonce(
+            device_id,
+            &self.challenge().unwrap_or(UNEXTRACTABLE_CHALLENGE),
+            &self.signature,
+        )
+    }
+
+    /// Whether this assertion, made with `device_id`, was already used.
+    #[must_use = "a replayed assertion is only rejected if the result is checked"]
+    pub fn was_used<G: ReplayGuard<Cx>>(&self, device_id: &DeviceId) -> bool {
+        G::was_used(&self.meta.context, &self.replay_nonce(device_id))
+    }
+
+    /// Records this assertion, made with `device_id`, as used; fails if it already was.
+    pub fn mark_used<G: ReplayGuard<Cx>>(&self, device_id: &DeviceId) -> Result<(), WebAuthnError> {
+        let nonce = self.replay_nonce(device_id);
+        if G::was_used(&self.meta.context, &nonce) {
+            return Err(WebAuthnError::Replayed);
+        }
+        G::mark_used(&self.meta.context, nonce);
+        Ok(())
     }
 }
 
@@ -15,11 +89,14 @@ where
     Cx: Parameter + Copy + 'static,
 {
     fn is_valid(&self) -> bool {
-        true
+        logging_failure(LOG_TARGET, self.validate()).is_some()
     }
 
     fn used_challenge(&self) -> (Cx, Challenge) {
-        (self.meta.context, self.challenge())
+        (
+            self.meta.context,
+            self.challenge().unwrap_or(UNEXTRACTABLE_CHALLENGE),
+        )
     }
 
     fn authority(&self) -> AuthorityId {
@@ -1,28 +1,256 @@
 use super::*;
 
-impl<Cx> Attestation<Cx>
+use coset::iana;
+use frame_support::sp_runtime::traits::Saturating;
+use verifier::{
+    cose_key_to_der, parse_authenticator_data, verify_algorithm_offered,
+    verify_packed_x5c_attestation_with_revocation, webauthn_verify, CoseAlgorithm, VerifyError,
+};
+
+use crate::{
+    AttestationPolicy, AttestationStatement, ClientDataError, CredentialPublicKey, WebAuthnError,
+};
+
+/// Checks run on attestations are logged at debug level under this target, and the keys and
+/// signatures they involve only at trace level. Runtimes enabling the `max_level_off` (or
+/// `release_max_level_off`) feature of `log` compile all of it out.
+pub(super) const LOG_TARGET: &str = "pass_webauthn::attestation";
+
+impl<Cx, P> Attestation<Cx, P>
 where
     Cx: Parameter,
+    P: AttestationPolicy,
 {
-    fn challenge(&self) -> Challenge {
-        find_challenge_from_client_data(self.client_data.clone()).unwrap_or_default()
+    fn challenge(&self) -> Option<Challenge> {
+        let challenge = ClientData::parse(&self.client_data).challenge;
+        if challenge.is_none() {
+            log::warn!(target: LOG_TARGET, "Failed to extract `challenge` from client data");
+        }
+        challenge
+    }
+
+    /// Checks the attestation statement binds `public_key` (or, for basic attestation, the
+    /// authenticator data carrying it) to this registration ceremony, or that the policy accepts
+    /// an unsigned (`none`) statement.
+    fn verify_attestation_statement(&self) -> Result<(), WebAuthnError> {
+        log::debug!(target: LOG_TARGET, "Checking the attestation statement");
+        match &self.attestation_statement {
+            AttestationStatement::None if P::ALLOW_NONE => Ok(()),
+            AttestationStatement::None => {
+                log::warn!(target: LOG_TARGET, "`none` attestation is not allowed by the attestation policy");
+                Err(VerifyError::UnsupportedAttestationFormat.into())
+            }
+            AttestationStatement::Packed { .. } if !P::ALLOW_SELF_ATTESTATION => {
+                log::warn!(
+                    target: LOG_TARGET,
+                    "Self-attestation is not allowed by the attestation policy"
+                );
+                Err(VerifyError::SelfAttestationNotAllowed.into())
+            }
+            AttestationStatement::Packed { alg, sig } => {
+                CoseAlgorithm::try_from(*alg).inspect_err(|_| {
+                    log::warn!(target: LOG_TARGET, "Unsupported attestation statement algorithm: {alg}");
+                })?;
+                log::debug!(target: LOG_TARGET, "Verifying packed self-attestation, COSE algorithm {alg}");
+                log::trace!(target: LOG_TARGET, "Self-attestation signature {sig:?} with public key {:?}", self.public_key);
+                Ok(webauthn_verify(
+                    &self.authenticator_data,
+                    &self.client_data,
+                    sig,
+                    self.public_key.as_der(),
+                )?)
+            }
+            AttestationStatement::PackedX5c { alg, sig, x5c } => {
+                let alg = CoseAlgorithm::try_from(*alg).inspect_err(|_| {
+                    log::warn!(target: LOG_TARGET, "Unsupported attestation statement algorithm: {alg}");
+                })?;
+                log::debug!(
+                    target: LOG_TARGET,
+                    "Verifying packed basic attestation, COSE algorithm {}, with {} certificates",
+                    alg.cose_id(),
+                    x5c.len()
+                );
+                log::trace!(target: LOG_TARGET, "Basic attestation signature {sig:?}");
+                Ok(verify_packed_x5c_attestation_with_revocation(
+                    &P::revocation_checker(),
+                    &self.authenticator_data,
+                    &self.client_data,
+                    alg,
+                    sig,
+                    x5c,
+                )?)
+            }
+        }
+    }
+
+    /// Checks `public_key` is the credential public key the authenticator data attests, so the
+    /// key that gets stored is the one the attestation statement vouches for.
+    fn check_public_key(&self) -> Result<(), WebAuthnError> {
+        log::debug!(target: LOG_TARGET, "Checking the public key is the attested one");
+        let attested = parse_authenticator_data(&self.authenticator_data)?
+            .attested_credential_data
+            .ok_or(VerifyError::ParseAuthenticatorData)?;
+        if cose_key_to_der(&attested.credential_public_key)? != self.public_key.as_der() {
+            log::warn!(target: LOG_TARGET, "The public key isn't the attested credential public key");
+            return Err(VerifyError::PublicKeyMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// Checks the policy allows the model of the authenticator, by the AAGUID of the attested
+    /// credential data. Authenticator data without one count as the all-zero AAGUID of
+    /// authenticators that don't disclose their model.
+    fn check_device(&self) -> Result<(), WebAuthnError> {
+        log::debug!(target: LOG_TARGET, "Checking the authenticator model is allowed");
+        let aaguid = parse_authenticator_data(&self.authenticator_data)?
+            .attested_credential_data
+            .map_or([0; 16], |data| data.aaguid);
+        if !P::is_device_allowed(aaguid) {
+            log::warn!(target: LOG_TARGET, "The authenticator model {aaguid:?} is blocked");
+            return Err(VerifyError::AuthenticatorBlocked.into());
+        }
+        Ok(())
+    }
+
+    /// Checks the attestation carries a challenge and client data for a registration, a public
+    /// key that can be stored and is the attested one, the user presence flag, a statement
+    /// acceptable under the policy, and comes from an authenticator model the policy allows.
+    #[must_use = "the attestation is only validated if the result is checked"]
+    pub fn validate(&self) -> Result<(), WebAuthnError> {
+        let result = self.run_checks();
+        match &result {
+            Ok(()) => log::debug!(target: LOG_TARGET, "The attestation is valid"),
+            Err(e) => log::debug!(target: LOG_TARGET, "The attestation is invalid: {e:?}"),
+        }
+        result
+    }
+
+    fn run_checks(&self) -> Result<(), WebAuthnError> {
+        log_client_data(LOG_TARGET, &self.client_data);
+        log::debug!(target: LOG_TARGET, "Checking the challenge");
+        self.challenge().ok_or(ClientDataError::MissingChallenge)?;
+        log::debug!(target: LOG_TARGET, "Checking the client data is for a registration");
+        check_client_data_type(&self.client_data, "webauthn.create")?;
+        log::debug!(target: LOG_TARGET, "Checking the public key is a DER-encoded P-256 key");
+        log::trace!(target: LOG_TARGET, "Public key {:?}", self.public_key);
+        CredentialPublicKey::try_from(&self.public_key)?;
+        self.check_public_key()?;
+        log::debug!(target: LOG_TARGET, "Checking the user was present");
+        check_user_present(parse_authenticator_data(&self.authenticator_data)?.flags)?;
+        self.verify_attestation_statement()?;
+        self.check_device()
+    }
+
+    /// Checks the attestation targets `expected`, failing with an
+    /// [`AuthorityMismatch`](WebAuthnError::AuthorityMismatch) that names the origin it was
+    /// produced at otherwise.
+    #[must_use = "a mismatched authority is only rejected if the result is checked"]
+    pub fn check_authority(&self, expected: AuthorityId) -> Result<(), WebAuthnError> {
+        if self.meta.authority_id == expected {
+            return Ok(());
+        }
+        let actual_origin = find_origin_from_client_data(&self.client_data);
+        log::warn!(
+            target: LOG_TARGET,
+            "The attestation targets another authority, from origin {:?}",
+            actual_origin
+        );
+        Err(WebAuthnError::AuthorityMismatch {
+            expected,
+            actual_origin,
+        })
+    }
+
+    /// Checks the attestation was produced for the relying party of its authority under
+    /// `base_domain`, as [`expected_rp_id`](crate::expected_rp_id) names it.
+    #[must_use = "a response for another relying party is only rejected if the result is checked"]
+    pub fn check_rp_id(&self, base_domain: &str) -> Result<(), WebAuthnError> {
+        check_rp_id(
+            &self.meta.authority_id,
+            base_domain,
+            &self.authenticator_data,
+            &self.client_data,
+        )
+    }
+
+    /// The last context the attestation is accepted in: `max_age` after the one its challenge
+    /// was issued for, e.g. the block it was issued at plus the `MaxSessionDuration` of
+    /// `pallet-pass`.
+    pub fn expires_at(&self, max_age: Cx) -> Cx
+    where
+        Cx: Saturating + Copy,
+    {
+        self.meta.context.saturating_add(max_age)
+    }
+
+    /// Checks the attestation hasn't expired by `now`, failing with
+    /// [`ChallengeExpired`](WebAuthnError::ChallengeExpired) when `now` is past
+    /// [`expires_at`](Self::expires_at), so old attestations can't be replayed to register.
+    #[must_use = "an expired attestation is only rejected if the result is checked"]
+    pub fn check_not_expired(&self, now: Cx, max_age: Cx) -> Result<(), WebAuthnError>
+    where
+        Cx: Saturating + Copy + PartialOrd,
+    {
+        if now > self.expires_at(max_age) {
+            log::warn!(target: LOG_TARGET, "The attestation expired");
+            return Err(WebAuthnError::ChallengeExpired);
+        }
+        Ok(())
+    }
+
+    /// Checks the credential was made with one of the `allowed_algs` the relying party offered
+    /// in the `pubKeyCredParams` of its creation options, failing with
+    /// [`VerifyError::AlgorithmNotOffered`] otherwise.
+    #[must_use = "a credential of an algorithm not offered is only rejected if the result is checked"]
+    pub fn check_algorithm(&self, allowed_algs: &[iana::Algorithm]) -> Result<(), WebAuthnError> {
+        log::debug!(target: LOG_TARGET, "Checking the credential algorithm was offered");
+        Ok(verify_algorithm_offered(
+            &self.authenticator_data,
+            allowed_algs,
+        )?)
+    }
+
+    /// Like [`Self::validate`], but also rejects credentials that are already registered, as
+    /// the relying party's `excludeCredentials` asks the client to do.
+    ///
+    /// The device id checked is the one derived from the attested credential ID, and the one in
+    /// the meta must be it, so a registered credential can't come back under another device id.
+    #[must_use = "the credential is only validated if the result is checked"]
+    pub fn validate_new_credential(
+        &self,
+        is_credential_registered: impl FnOnce(&DeviceId) -> bool,
+    ) -> Result<(), WebAuthnError> {
+        let device_id = credential_id_hash_of(&self.authenticator_data)?;
+        if device_id != self.meta.device_id {
+            log::warn!(
+                target: LOG_TARGET,
+                "The device id isn't the one of the attested credential"
+            );
+            return Err(WebAuthnError::DeviceMismatch);
+        }
+        if is_credential_registered(&device_id) {
+            log::warn!(target: LOG_TARGET, "The credential is already registered");
+            return Err(VerifyError::CredentialAlreadyRegistered.into());
+        }
+        self.validate()
     }
 }
 
 #[cfg(any(feature = "runtime", test))]
-impl<Cx> DeviceChallengeResponse<Cx> for Attestation<Cx>
+impl<Cx, P> DeviceChallengeResponse<Cx> for Attestation<Cx, P>
 where
     Cx: Parameter + Copy + 'static,
+    P: AttestationPolicy,
 {
-    // TODO: @pandres95, considering that DeviceChallengeResponse is used for creating a new
-    // authentication device, webauth_verify wouldn't work here. We need to implement a new
-    // verification method exclusively for credential creation.
     fn is_valid(&self) -> bool {
-        true
+        logging_failure(LOG_TARGET, self.validate()).is_some()
     }
 
     fn used_challenge(&self) -> (Cx, Challenge) {
-        (self.meta.context, self.challenge())
+        (
+            self.meta.context,
+            self.challenge().unwrap_or(UNEXTRACTABLE_CHALLENGE),
+        )
     }
 
     /// WebAuthn RpID should be a subdomain of the origin that is calling the create credentials request.
@@ -0,0 +1,103 @@
+use super::*;
+
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use frame_support::{traits::ConstU32, BoundedVec};
+use scale_info::TypeInfo;
+use sha2::{Digest, Sha256};
+use traits_authn::Challenger;
+use verifier::VerifyError;
+
+use crate::{AllowNoneAttestation, AttestationPolicy, WebAuthnError};
+
+/// Most attestations a [`BatchAttestation`] can carry.
+pub const MAX_BATCH_SIZE: u32 = 16;
+
+/// The challenge the `index`-th authenticator of a batch signs, derived from the one the
+/// challenger issued for the batch context, so no two items of a batch share a challenge.
+pub fn batch_challenge(challenge: &Challenge, index: u32) -> Challenge {
+    Sha256::new()
+        .chain_update(challenge)
+        .chain_update(index.to_le_bytes())
+        .finalize()
+        .into()
+}
+
+/// Several attestations registering authenticators for one user at once, e.g. when onboarding
+/// the security keys of an enterprise user.
+///
+/// Items share the context of the batch, and each signs the [`batch_challenge`] for its index.
+/// The batch is only valid if every item is.
+#[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(P))]
+pub struct BatchAttestation<Cx, P = AllowNoneAttestation> {
+    pub(crate) context: Cx,
+    pub(crate) attestations: BoundedVec<Attestation<Cx, P>, ConstU32<MAX_BATCH_SIZE>>,
+}
+
+impl<Cx, P> BatchAttestation<Cx, P>
+where
+    Cx: Parameter + Copy + 'static,
+    P: AttestationPolicy,
+{
+    /// Fails if there are more than [`MAX_BATCH_SIZE`] attestations.
+    pub fn new(context: Cx, attestations: Vec<Attestation<Cx, P>>) -> Option<Self> {
+        Some(Self {
+            context,
+            attestations: attestations.try_into().ok()?,
+        })
+    }
+
+    pub fn attestations(&self) -> &[Attestation<Cx, P>] {
+        &self.attestations
+    }
+
+    /// Validates every item as for a single registration, checking it signs the challenge for
+    /// its index and that no device is registered twice. Which item failed, and why, is logged.
+    #[must_use = "the batch is only validated if the result is checked"]
+    pub fn validate<Ch>(&self) -> Result<(), WebAuthnError>
+    where
+        Ch: Challenger<Context = Cx>,
+    {
+        let challenge = Ch::generate(&self.context);
+
+        for (index, attestation) in (0u32..).zip(self.attestations.iter()) {
+            self.validate_item(&challenge, index, attestation)
+                .inspect_err(|e| {
+                    log::warn!("Item {index} of the batch attestation is invalid: {e:?}");
+                })?;
+        }
+        Ok(())
+    }
+
+    fn validate_item(
+        &self,
+        challenge: &Challenge,
+        index: u32,
+        attestation: &Attestation<Cx, P>,
+    ) -> Result<(), WebAuthnError> {
+        let (context, used_challenge) = attestation.used_challenge();
+        if context != self.context || used_challenge != batch_challenge(challenge, index) {
+            return Err(WebAuthnError::InvalidChallenge);
+        }
+
+        let position = index as usize;
+        if self.attestations[..position]
+            .iter()
+            .any(|other| other.device_id() == attestation.device_id())
+        {
+            return Err(VerifyError::CredentialAlreadyRegistered.into());
+        }
+
+        attestation.validate()
+    }
+
+    /// The credentials to store, in the order of the batch, failing on the first attestation
+    /// that doesn't make one. Batches [`validate`](Self::validate) accepts never fail.
+    pub fn into_credentials(self) -> Result<Vec<Credential>, WebAuthnError> {
+        self.attestations
+            .into_iter()
+            .map(Credential::try_from)
+            .collect()
+    }
+}
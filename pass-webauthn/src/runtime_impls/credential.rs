@@ -1,38 +1,260 @@
 use super::*;
 
-use traits_authn::{util::VerifyCredential, Challenger};
-use verifier::webauthn_verify;
+use traits_authn::{util::VerifyCredential, Challenger, DeviceChallengeResponse, HashedUserId};
+use verifier::{
+    check_signature_encoding, cose_key_algorithm, parse_authenticator_data,
+    verify_backup_state_consistency, webauthn_verify_sec1, AuthenticatorDataFlags, CoseAlgorithm,
+    VerifyError, COMPRESSED_P256_KEY_LEN,
+};
 
-use crate::{CxOf, Device};
+use super::{
+    assertion::LOG_TARGET as ASSERTION_TARGET, attestation::LOG_TARGET as ATTESTATION_TARGET,
+};
+use crate::{
+    ids, AttestationPolicy, AttestationStatement, AuthenticatorAttachment, CredentialPublicKey,
+    CxOf, Device, MergeError, SignCount, Transports, VerifyOutcome, WebAuthnError,
+};
+
+fn flags_and_sign_count_of(
+    authenticator_data: &[u8],
+) -> Result<(AuthenticatorDataFlags, SignCount), WebAuthnError> {
+    let authenticator_data = parse_authenticator_data(authenticator_data)?;
+    Ok((
+        authenticator_data.flags,
+        authenticator_data.sign_count.into(),
+    ))
+}
+
+/// The algorithm a credential was registered with: the one of its self-attestation statement,
+/// or else the one declared by its COSE key, as basic attestation is made with another key.
+fn algorithm_of<Cx, P>(attestation: &Attestation<Cx, P>) -> Result<CoseAlgorithm, VerifyError> {
+    match attestation.attestation_statement {
+        AttestationStatement::Packed { alg, .. } => CoseAlgorithm::try_from(alg),
+        AttestationStatement::None | AttestationStatement::PackedX5c { .. } => {
+            let attested = parse_authenticator_data(&attestation.authenticator_data)?
+                .attested_credential_data
+                .ok_or(VerifyError::ParseAuthenticatorData)?;
+            cose_key_algorithm(&attested.credential_public_key)
+        }
+    }
+}
+
+/// The credential an attestation registers, failing when its authenticator data, public key or
+/// algorithm can't be read. Attestations [`validate`](Attestation::validate) accepts always make
+/// one.
+#[cfg(any(feature = "runtime", test))]
+impl<Cx, P> TryFrom<Attestation<Cx, P>> for Credential {
+    type Error = WebAuthnError;
+
+    fn try_from(value: Attestation<Cx, P>) -> Result<Self, Self::Error> {
+        let (registration_flags, sign_count) = flags_and_sign_count_of(&value.authenticator_data)?;
+        Ok(Credential {
+            algorithm: algorithm_of(&value)?.cose_id(),
+            credential_id_hash: credential_id_hash_of(&value.authenticator_data)?,
+            device_id: value.meta.device_id,
+            public_key: CredentialPublicKey::try_from(&value.public_key)?,
+            authenticator_attachment: value.authenticator_attachment,
+            sign_count,
+            registration_flags,
+            uv_required: false,
+            transports: value.transports,
+        })
+    }
+}
 
 #[cfg(any(feature = "runtime", test))]
-impl<Ch, A> From<Attestation<CxOf<Ch>>> for Device<Ch, A>
+impl<Ch, A, P> From<Attestation<CxOf<Ch>, P>> for Device<Ch, A>
 where
     Ch: Challenger,
     CxOf<Ch>: Parameter + Copy + 'static,
+    P: AttestationPolicy,
 {
-    fn from(value: Attestation<CxOf<Ch>>) -> Self {
-        Device::new(Credential {
-            device_id: *value.device_id(),
-            public_key: value.public_key,
-        })
+    /// `pallet-pass` only unpacks the device of attestations [`is_valid`] accepted, which always
+    /// make a credential. See [`Credential::unpacked_from`] for the others.
+    ///
+    /// [`is_valid`]: DeviceChallengeResponse::is_valid
+    fn from(value: Attestation<CxOf<Ch>, P>) -> Self {
+        Device::new(Credential::unpacked_from(value))
     }
 }
 
-impl<Cx> VerifyCredential<Assertion<Cx>> for Credential {
-    fn verify(&self, credential: &Assertion<Cx>) -> Option<()> {
+impl Credential {
+    /// The credential `attestation` registers, for conversions that can't fail. When it makes
+    /// none, as attestations [`validate`](Attestation::validate) rejects may not, the
+    /// credential has an unusable key and algorithm, so no assertion verifies against it.
+    pub(crate) fn unpacked_from<Cx, P>(attestation: Attestation<Cx, P>) -> Self {
+        let device_id = attestation.meta.device_id;
+        Self::try_from(attestation).unwrap_or_else(|e| {
+            log::error!(
+                target: ATTESTATION_TARGET,
+                "Unpacking a device from an attestation that makes no credential: {e:?}"
+            );
+            Credential {
+                device_id,
+                credential_id_hash: device_id,
+                // Not a SEC1 point, and not a COSE algorithm.
+                public_key: CredentialPublicKey::Es256([0; COMPRESSED_P256_KEY_LEN]),
+                authenticator_attachment: AuthenticatorAttachment::Unknown,
+                sign_count: SignCount::default(),
+                algorithm: 0,
+                registration_flags: AuthenticatorDataFlags::default(),
+                uv_required: false,
+                transports: Transports::default(),
+            }
+        })
+    }
+
+    /// Replaces the public key of this credential with the one in a fresh attestation for the
+    /// same device, e.g. after the authenticator migrated the credential.
+    ///
+    /// The attestation is verified as for a registration, and it must be for this device, the
+    /// `user_id` the device belongs to and the given `authority_id`. The device id and the
+    /// signature counter high-water mark are preserved.
+    pub fn rotate<Ch, P>(
+        &self,
+        attestation: Attestation<CxOf<Ch>, P>,
+        user_id: HashedUserId,
+        authority_id: AuthorityId,
+    ) -> Result<Self, WebAuthnError>
+    where
+        Ch: Challenger,
+        CxOf<Ch>: Parameter + Copy + 'static,
+        P: AttestationPolicy,
+    {
+        if attestation.device_id() != &self.device_id {
+            return Err(WebAuthnError::DeviceMismatch);
+        }
+        if attestation.meta.user_id != Some(user_id) {
+            return Err(WebAuthnError::UserMismatch);
+        }
+        attestation.check_authority(authority_id)?;
+        attestation.validate()?;
+
+        let (context, challenge) = attestation.used_challenge();
+        Ch::check_challenge(&context, &challenge).ok_or(WebAuthnError::InvalidChallenge)?;
+
+        let rotated = Self::try_from(attestation)?;
+        Ok(Self {
+            sign_count: self.sign_count.max(rotated.sign_count),
+            ..rotated
+        })
+    }
+
+    /// Combines the stored credential of a device with the one from its re-registration, e.g.
+    /// after a firmware update replaced its key, without verifying anything (see
+    /// [`rotate`](Self::rotate) for that).
+    ///
+    /// The key, algorithm and registration flags are the new ones. The signature counter is the
+    /// old one if the new credential is fresh (its counter is zero). The attachment and
+    /// transports are the new ones unless they are unknown, and whether user verification is
+    /// required is kept from the old credential.
+    pub fn merge(old: Credential, new: Credential) -> Result<Credential, MergeError> {
+        if old.device_id != new.device_id {
+            return Err(MergeError::DeviceIdMismatch);
+        }
+
+        Ok(Credential {
+            sign_count: if new.sign_count == SignCount::default() {
+                old.sign_count
+            } else {
+                new.sign_count
+            },
+            authenticator_attachment: match new.authenticator_attachment {
+                AuthenticatorAttachment::Unknown => old.authenticator_attachment,
+                known => known,
+            },
+            transports: if new.transports.is_empty() {
+                old.transports
+            } else {
+                new.transports
+            },
+            uv_required: old.uv_required,
+            ..new
+        })
+    }
+
+    /// Verifies the client data of an assertion is for an authentication, its signature against
+    /// this credential's public key, using the algorithm the credential was registered with, that
+    /// the user was present, that its backup flags are consistent with those at registration,
+    /// that the user was verified if the credential requires it, and that its signature counter
+    /// advances over the stored one.
+    pub fn verify_assertion<Cx>(
+        &self,
+        credential: &Assertion<Cx>,
+    ) -> Result<VerifyOutcome, WebAuthnError> {
+        let result = self.run_assertion_checks(credential);
+        match &result {
+            Ok(outcome) => log::debug!(
+                target: ASSERTION_TARGET,
+                "The assertion is valid, advancing the sign count to {:?}",
+                outcome.new_sign_count
+            ),
+            Err(e) => log::debug!(target: ASSERTION_TARGET, "The assertion is invalid: {e:?}"),
+        }
+        result
+    }
+
+    fn run_assertion_checks<Cx>(
+        &self,
+        credential: &Assertion<Cx>,
+    ) -> Result<VerifyOutcome, WebAuthnError> {
+        log_client_data(ASSERTION_TARGET, &credential.client_data);
+        log::debug!(target: ASSERTION_TARGET, "Checking the client data is for an authentication");
+        check_client_data_type(&credential.client_data, "webauthn.get")?;
+        if let Some(credential_id) = credential.try_credential_id() {
+            log::debug!(target: ASSERTION_TARGET, "Checking the assertion is for this credential");
+            let credential_id_hash: DeviceId =
+                ids::DeviceId::from_credential_id(credential_id).into();
+            if credential_id_hash != self.credential_id_hash {
+                return Err(WebAuthnError::CredentialIdMismatch);
+            }
+        }
         log::trace!(
+            target: ASSERTION_TARGET,
             "Verifying credentials with public key {:?} and signature {:?}",
             &self.public_key,
             &credential.signature
         );
-        webauthn_verify(
+        log::debug!(
+            target: ASSERTION_TARGET,
+            "Checking the signature encoding for COSE algorithm {}",
+            self.algorithm
+        );
+        check_signature_encoding(
+            CoseAlgorithm::try_from(self.algorithm)?,
+            &credential.signature,
+        )?;
+        log::debug!(target: ASSERTION_TARGET, "Verifying the signature");
+        webauthn_verify_sec1(
             &credential.authenticator_data,
             &credential.client_data,
             &credential.signature,
-            &self.public_key,
-        )
-        .ok()
+            self.public_key.as_sec1(),
+        )?;
+
+        let (flags, sign_count) = flags_and_sign_count_of(&credential.authenticator_data)?;
+        log::debug!(
+            target: ASSERTION_TARGET,
+            "Checking the flags {:#010b} against those at registration, {:#010b}",
+            flags.bits(),
+            self.registration_flags.bits()
+        );
+        check_user_present(flags)?;
+        verify_backup_state_consistency(self.registration_flags, flags)?;
+        if self.uv_required && !flags.contains(AuthenticatorDataFlags::USER_VERIFIED) {
+            return Err(WebAuthnError::UserVerificationRequired);
+        }
+
+        log::debug!(target: ASSERTION_TARGET, "Checking the sign count advances");
+        Ok(VerifyOutcome {
+            new_sign_count: self.sign_count.advance(sign_count)?,
+        })
+    }
+}
+
+impl<Cx> VerifyCredential<Assertion<Cx>> for Credential {
+    fn verify(&self, credential: &Assertion<Cx>) -> Option<()> {
+        logging_failure(ASSERTION_TARGET, self.verify_assertion(credential)).map(|_| ())
     }
 }
 
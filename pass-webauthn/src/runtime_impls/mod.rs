@@ -1,8 +1,32 @@
 use frame_support::Parameter;
 use traits_authn::{AuthorityId, Challenge, DeviceChallengeResponse, DeviceId};
+use verifier::{parse_authenticator_data, VerifyError};
 
-use crate::{runtime_helpers::*, Assertion, Attestation, Credential};
+use crate::{ids, runtime_helpers::*, Assertion, Attestation, Credential, WebAuthnError};
 
 pub mod assertion;
 pub mod attestation;
+pub mod batch;
 pub mod credential;
+pub mod options;
+
+/// The value of `result`, for the `traits-authn` methods that only tell whether a response is
+/// valid. With the `log-failures` feature, the error is logged under `target` first, so the
+/// reason a call failed with `CredentialInvalid` shows in the node logs.
+#[cfg_attr(not(any(feature = "log-failures", test)), allow(unused_variables))]
+fn logging_failure<T>(target: &str, result: Result<T, WebAuthnError>) -> Option<T> {
+    #[cfg(any(feature = "log-failures", test))]
+    if let Err(e) = &result {
+        log::warn!(target: target, "Verification failed: {e:?}");
+    }
+    result.ok()
+}
+
+/// The device id derived from the ID of the credential an attestation registers, the way the
+/// pallet keys the devices it registers.
+fn credential_id_hash_of(authenticator_data: &[u8]) -> Result<DeviceId, VerifyError> {
+    let attested = parse_authenticator_data(authenticator_data)?
+        .attested_credential_data
+        .ok_or(VerifyError::ParseAuthenticatorData)?;
+    Ok(ids::DeviceId::from_credential_id(attested.credential_id).into())
+}
@@ -0,0 +1,128 @@
+use super::*;
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{traits::ConstU32, BoundedVec};
+use scale_info::TypeInfo;
+use sha2::{Digest, Sha256};
+use verifier::{parse_authenticator_data, VerifyError};
+
+use crate::ClientDataError;
+
+/// Longest RP ID the options hold, the length of the longest domain name.
+pub const MAX_RP_ID_LEN: u32 = 253;
+/// Longest origin the options hold: `https://`, the longest RP ID and a port.
+pub const MAX_ORIGIN_LEN: u32 = 8 + MAX_RP_ID_LEN + 6;
+
+pub type MaxRpIdLen = ConstU32<MAX_RP_ID_LEN>;
+pub type MaxOriginLen = ConstU32<MAX_ORIGIN_LEN>;
+
+/// What a registration response must have been produced for, kept on chain (e.g. in a map of
+/// `HashedUserId => RegistrationOptions`) from the moment the ceremony starts, so the response
+/// is checked against it instead of re-deriving it.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq, Clone)]
+pub struct RegistrationOptions {
+    pub rp_id: BoundedVec<u8, MaxRpIdLen>,
+    pub expected_origin: BoundedVec<u8, MaxOriginLen>,
+    pub challenge: Challenge,
+}
+
+/// Like [`RegistrationOptions`], for authentication ceremonies.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq, Clone)]
+pub struct AuthenticationOptions {
+    pub rp_id: BoundedVec<u8, MaxRpIdLen>,
+    pub expected_origin: BoundedVec<u8, MaxOriginLen>,
+    pub challenge: Challenge,
+}
+
+type Bounded = (BoundedVec<u8, MaxRpIdLen>, BoundedVec<u8, MaxOriginLen>);
+
+/// Fails if `rp_id` or `expected_origin` is longer than [`MAX_RP_ID_LEN`] or
+/// [`MAX_ORIGIN_LEN`].
+fn bounded(rp_id: &str, expected_origin: &str) -> Option<Bounded> {
+    Some((
+        rp_id.as_bytes().to_vec().try_into().ok()?,
+        expected_origin.as_bytes().to_vec().try_into().ok()?,
+    ))
+}
+
+impl RegistrationOptions {
+    /// Fails if `rp_id` or `expected_origin` is longer than [`MAX_RP_ID_LEN`] or
+    /// [`MAX_ORIGIN_LEN`].
+    pub fn new(rp_id: &str, expected_origin: &str, challenge: Challenge) -> Option<Self> {
+        let (rp_id, expected_origin) = bounded(rp_id, expected_origin)?;
+        Some(Self {
+            rp_id,
+            expected_origin,
+            challenge,
+        })
+    }
+}
+
+impl AuthenticationOptions {
+    /// Fails if `rp_id` or `expected_origin` is longer than [`MAX_RP_ID_LEN`] or
+    /// [`MAX_ORIGIN_LEN`].
+    pub fn new(rp_id: &str, expected_origin: &str, challenge: Challenge) -> Option<Self> {
+        let (rp_id, expected_origin) = bounded(rp_id, expected_origin)?;
+        Some(Self {
+            rp_id,
+            expected_origin,
+            challenge,
+        })
+    }
+}
+
+/// Checks the `rpIdHash` of `authenticator_data` is the one of `rp_id`, and that `client_data`
+/// was produced at `expected_origin` for `challenge`.
+fn check_options(
+    target: &str,
+    (rp_id, expected_origin, challenge): (&[u8], &[u8], &Challenge),
+    authenticator_data: &[u8],
+    client_data: &[u8],
+) -> Result<(), WebAuthnError> {
+    log::debug!(target: target, "Checking the response against the stored options");
+    let rp_id_hash: [u8; 32] = Sha256::digest(rp_id).into();
+    if parse_authenticator_data(authenticator_data)?.rp_id_hash != rp_id_hash {
+        log::warn!(target: target, "The authenticator data isn't for the RP ID of the options");
+        return Err(WebAuthnError::RpIdMismatch);
+    }
+
+    let origin = find_origin_from_client_data(client_data).ok_or(VerifyError::ParseClientData)?;
+    if origin.as_bytes() != expected_origin {
+        log::warn!(target: target, "Unexpected origin {origin}");
+        return Err(WebAuthnError::RpIdMismatch);
+    }
+
+    match find_challenge_from_client_data(client_data) {
+        Some(signed) if signed == *challenge => Ok(()),
+        Some(_) => Err(WebAuthnError::InvalidChallenge),
+        None => Err(ClientDataError::MissingChallenge.into()),
+    }
+}
+
+impl<Cx, P> Attestation<Cx, P> {
+    /// Checks the attestation was produced for the relying party, origin and challenge of
+    /// `options`. The attestation itself is checked by [`validate`](Self::validate).
+    #[must_use = "a response for other options is only rejected if the result is checked"]
+    pub fn check_options(&self, options: &RegistrationOptions) -> Result<(), WebAuthnError> {
+        check_options(
+            super::attestation::LOG_TARGET,
+            (&options.rp_id, &options.expected_origin, &options.challenge),
+            &self.authenticator_data,
+            &self.client_data,
+        )
+    }
+}
+
+impl<Cx> Assertion<Cx> {
+    /// Checks the assertion was produced for the relying party, origin and challenge of
+    /// `options`. The signature is checked against the stored [`Credential`] instead.
+    #[must_use = "a response for other options is only rejected if the result is checked"]
+    pub fn check_options(&self, options: &AuthenticationOptions) -> Result<(), WebAuthnError> {
+        check_options(
+            super::assertion::LOG_TARGET,
+            (&options.rp_id, &options.expected_origin, &options.challenge),
+            &self.authenticator_data,
+            &self.client_data,
+        )
+    }
+}
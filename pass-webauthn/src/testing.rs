@@ -0,0 +1,863 @@
+//! A software authenticator making valid attestations and assertions, for the tests of crates
+//! depending on pass webauthn.
+//!
+//! Add `pass-webauthn` with the `testing` feature to the `[dev-dependencies]`, so the
+//! authenticator (and its std-only dependencies) never makes it into runtime builds:
+//!
+//! ```ignore
+//! use pass_webauthn::testing::WebAuthnClient;
+//!
+//! let mut client = WebAuthnClient::<MyChallenger>::new("https://pass.example", 2);
+//! let (credential_id, attestation) = client.attestation(user_id, context, authority_id);
+//! let assertion = client.assertion(credential_id, context, authority_id);
+//! ```
+
+use base64::prelude::BASE64_URL_SAFE_NO_PAD;
+use core::marker::PhantomData;
+use coset::{
+    cbor::{ser::into_writer, value::Value},
+    iana, CborSerializable, CoseKeyBuilder, Label,
+};
+use futures::executor::block_on;
+use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+use sha2::{Digest, Sha256};
+
+use passkey_authenticator::{Authenticator, MockUserValidationMethod, UserCheck};
+use passkey_client::{Client, DefaultClientData, WebauthnError};
+use passkey_types::{ctap2::Aaguid, webauthn::*, Bytes, Passkey};
+
+use traits_authn::{AuthorityId, Challenge, Challenger, HashedUserId};
+use url_evil::Url;
+use verifier::AuthenticatorDataFlags;
+
+use crate::{
+    batch_challenge, ids, passkey_compat::user_id_from_user_handle, AssertionMeta, AttestationMeta,
+    CxOf,
+};
+
+pub mod fixture;
+
+/// The CTAP2 status of an authenticator holding one of the credentials a creation excludes
+/// (`CTAP2_ERR_CREDENTIAL_EXCLUDED`).
+const CREDENTIAL_EXCLUDED: u8 = 0x19;
+
+/// Why a [`WebAuthnClient`] couldn't make a credential or an assertion.
+#[derive(Debug)]
+pub enum ClientError {
+    /// The authenticator holds one of the credentials the relying party excluded, as when a
+    /// user registers the same authenticator again.
+    CredentialExcluded,
+    /// Any other failure of the client or the authenticator.
+    Other(WebauthnError),
+}
+
+impl From<WebauthnError> for ClientError {
+    fn from(e: WebauthnError) -> Self {
+        match e {
+            WebauthnError::AuthenticatorError(CREDENTIAL_EXCLUDED) => Self::CredentialExcluded,
+            e => Self::Other(e),
+        }
+    }
+}
+
+/// How the authenticator of a [`WebAuthnClient`] checks the user before signing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserVerification {
+    /// The user is verified, e.g. with biometrics: responses carry the `UP` and `UV` flags, even
+    /// when the relying party discourages verification.
+    Verified,
+    /// Only the presence of the user is checked, as by security keys without a PIN: responses
+    /// carry the `UP` flag alone.
+    PresenceOnly,
+}
+
+/// A browser with a single passkey authenticator, signing the challenges `Ch` generates.
+///
+/// The authenticator holds one credential at a time: creating a credential replaces the last
+/// one.
+pub struct WebAuthnClient<Ch> {
+    origin: Url,
+    client: Client<Option<Passkey>, MockUserValidationMethod, public_suffix::PublicSuffixList>,
+    /// The transports reported along with the last created credential.
+    transports: Vec<AuthenticatorTransport>,
+    /// The COSE algorithm the authenticator picked for the last created credential.
+    algorithm: Option<i64>,
+    /// What credential keys and ids are derived from, instead of the OS randomness passkey-rs
+    /// draws them from.
+    seed: Option<[u8; 32]>,
+    /// How many credentials were created, so each one derived from the seed is different.
+    created: u32,
+    /// Whether assertions leave out the user handle, as authenticators may for credentials
+    /// that aren't discoverable.
+    omit_user_handle: bool,
+    /// The user verification the client asks the authenticator for.
+    user_verification: UserVerificationRequirement,
+    _challenger: PhantomData<Ch>,
+}
+
+impl<Ch> WebAuthnClient<Ch>
+where
+    Ch: Challenger,
+    CxOf<Ch>: Copy,
+{
+    /// A client for the relying party at `origin`, whose authenticator verifies the user up to
+    /// `times` times.
+    pub fn new(origin: &'static str, times: usize) -> Self {
+        Self::new_with_user_verification(origin, times, UserVerification::Verified)
+    }
+
+    /// Like [`new`](Self::new), but with an authenticator checking the user as `user_verification`
+    /// says. Presence-only clients discourage user verification in their requests, as relying
+    /// parties expecting such authenticators do.
+    pub fn new_with_user_verification(
+        origin: &'static str,
+        times: usize,
+        user_verification: UserVerification,
+    ) -> Self {
+        let verified = user_verification == UserVerification::Verified;
+        let mut user_validation = MockUserValidationMethod::new();
+        user_validation
+            .expect_is_presence_enabled()
+            .returning(|| true);
+        user_validation
+            .expect_is_verification_enabled()
+            .returning(move || verified.then_some(true));
+        user_validation
+            .expect_check_user()
+            .returning(move |_, presence, _| {
+                Ok(UserCheck {
+                    presence,
+                    verification: verified,
+                })
+            })
+            .times(times);
+
+        // Create Authenticator
+        let authenticator = Authenticator::new(Aaguid::new_empty(), None, user_validation);
+        Self {
+            origin: Url::parse(origin).expect("invalid url provided"),
+            client: Client::new(authenticator),
+            transports: Vec::new(),
+            algorithm: None,
+            seed: None,
+            created: 0,
+            omit_user_handle: false,
+            user_verification: if verified {
+                UserVerificationRequirement::default()
+            } else {
+                UserVerificationRequirement::Discouraged
+            },
+            _challenger: PhantomData,
+        }
+    }
+
+    /// Like [`new`](Self::new), but with credential keys and ids derived from `seed`, so the
+    /// same seed always yields the same credentials, and the same signatures for the same
+    /// challenges.
+    pub fn new_seeded(origin: &'static str, times: usize, seed: [u8; 32]) -> Self {
+        Self {
+            seed: Some(seed),
+            ..Self::new(origin, times)
+        }
+    }
+
+    /// Makes the authenticator leave the user handle out of its assertions.
+    pub fn without_user_handle(self) -> Self {
+        Self {
+            omit_user_handle: true,
+            ..self
+        }
+    }
+
+    /// Makes the client ask the authenticator for `requirement` in its requests, whatever the
+    /// authenticator does.
+    pub fn requesting_user_verification(self, requirement: UserVerificationRequirement) -> Self {
+        Self {
+            user_verification: requirement,
+            ..self
+        }
+    }
+
+    /// The transports the authenticator reported when it last created a credential.
+    pub fn transports(&self) -> &[AuthenticatorTransport] {
+        &self.transports
+    }
+
+    /// The COSE algorithm the authenticator picked when it last created a credential.
+    pub fn algorithm(&self) -> Option<i64> {
+        self.algorithm
+    }
+
+    /// Creates an ES256 credential for `user_id`, returning the raw response of the
+    /// authenticator with its attestation object re-encoded as `packed` self-attestation.
+    ///
+    /// The credential ids in `exclude` go in the `excludeCredentials` of the creation options,
+    /// failing with [`ClientError::CredentialExcluded`] if the authenticator holds one of them.
+    pub fn create_credential_sync(
+        &mut self,
+        user_id: HashedUserId,
+        challenge: impl Into<Bytes>,
+        exclude: &[Vec<u8>],
+    ) -> Result<CreatedPublicKeyCredential, ClientError> {
+        self.create_credential(user_id, challenge, &[iana::Algorithm::ES256], exclude)
+    }
+
+    /// Like [`create_credential_sync`](Self::create_credential_sync), but offering the
+    /// authenticator `algorithms`, in order of preference. Fails if it supports none of them.
+    pub fn create_credential_with_algorithms(
+        &mut self,
+        user_id: HashedUserId,
+        challenge: impl Into<Bytes>,
+        algorithms: &[iana::Algorithm],
+    ) -> Result<CreatedPublicKeyCredential, ClientError> {
+        self.create_credential(user_id, challenge, algorithms, &[])
+    }
+
+    fn create_credential(
+        &mut self,
+        user_id: HashedUserId,
+        challenge: impl Into<Bytes>,
+        algorithms: &[iana::Algorithm],
+        exclude: &[Vec<u8>],
+    ) -> Result<CreatedPublicKeyCredential, ClientError> {
+        let creation_options = CredentialCreationOptions {
+            public_key: PublicKeyCredentialCreationOptions {
+                rp: PublicKeyCredentialRpEntity {
+                    id: None,
+                    name: self.origin.domain().unwrap().into(),
+                },
+                user: PublicKeyCredentialUserEntity {
+                    id: user_id.as_slice().into(),
+                    display_name: "".into(),
+                    name: "".into(),
+                },
+                challenge: challenge.into(),
+                pub_key_cred_params: algorithms
+                    .iter()
+                    .map(|&alg| PublicKeyCredentialParameters {
+                        ty: PublicKeyCredentialType::PublicKey,
+                        alg,
+                    })
+                    .collect(),
+                timeout: None,
+                exclude_credentials: (!exclude.is_empty()).then(|| {
+                    exclude
+                        .iter()
+                        .map(|id| PublicKeyCredentialDescriptor {
+                            ty: PublicKeyCredentialType::PublicKey,
+                            id: id.clone().into(),
+                            transports: None,
+                        })
+                        .collect()
+                }),
+                authenticator_selection: Some(AuthenticatorSelectionCriteria {
+                    authenticator_attachment: None,
+                    resident_key: None,
+                    require_resident_key: false,
+                    user_verification: self.user_verification,
+                }),
+                hints: None,
+                attestation: AttestationConveyancePreference::Direct,
+                attestation_formats: Some(vec![AttestationStatementFormatIdentifiers::Packed]),
+                extensions: None,
+            },
+        };
+
+        // Register the credential and block until result
+        let mut credential = block_on(self.client.register(
+            &self.origin,
+            creation_options,
+            DefaultClientData,
+        ))?;
+
+        if let Some(seed) = self.seed {
+            self.use_seeded_credential(&mut credential, seed);
+        }
+        self.created += 1;
+
+        self.transports = credential.response.transports.clone().unwrap_or_default();
+        self.algorithm = Some(credential.response.public_key_algorithm);
+        credential.response.attestation_object = self.packed_self_attestation(&credential).into();
+        Ok(credential)
+    }
+
+    /// Replaces the key and id passkey-rs generated for a freshly created credential with ones
+    /// derived from `seed`, both in the stored passkey, which later assertions are signed with,
+    /// and in the attested credential data of the response.
+    fn use_seeded_credential(
+        &mut self,
+        credential: &mut CreatedPublicKeyCredential,
+        seed: [u8; 32],
+    ) {
+        let derive = |label: &[u8]| -> [u8; 32] {
+            Sha256::new()
+                .chain_update(seed)
+                .chain_update(label)
+                .chain_update(self.created.to_be_bytes())
+                .finalize()
+                .into()
+        };
+        let signing_key = SigningKey::from_slice(&derive(b"credential key"))
+            .expect("A hash is a valid P-256 scalar but with negligible probability; qed");
+        let credential_id = derive(b"credential id")[..16].to_vec();
+
+        let public_key = signing_key.verifying_key().to_encoded_point(false);
+        let (x, y) = (
+            public_key
+                .x()
+                .expect("The point is uncompressed; qed")
+                .to_vec(),
+            public_key
+                .y()
+                .expect("The point is uncompressed; qed")
+                .to_vec(),
+        );
+        let credential_public_key =
+            CoseKeyBuilder::new_ec2_pub_key(iana::EllipticCurve::P_256, x.clone(), y.clone())
+                .algorithm(iana::Algorithm::ES256)
+                .build()
+                .to_vec()
+                .expect("Encoding a COSE key doesn't fail; qed");
+
+        let generated = verifier::parse_authenticator_data(&credential.response.authenticator_data)
+            .expect("The authenticator returns valid authenticator data; qed");
+        let aaguid = generated
+            .attested_credential_data
+            .expect("Registrations carry attested credential data; qed")
+            .aaguid;
+        let authenticator_data = [
+            &credential.response.authenticator_data[..37],
+            &aaguid,
+            &(credential_id.len() as u16).to_be_bytes(),
+            &credential_id,
+            &credential_public_key,
+            generated.extensions.unwrap_or_default(),
+        ]
+        .concat();
+
+        credential.id = base64::encode_engine(&credential_id, &BASE64_URL_SAFE_NO_PAD);
+        credential.raw_id = credential_id.clone().into();
+        credential.response.authenticator_data = authenticator_data.into();
+        credential.response.public_key = Some(
+            verifier::sec1_public_key_to_der(public_key.as_bytes())
+                .expect("The key is a valid P-256 point; qed")
+                .into(),
+        );
+
+        let passkey = self
+            .client
+            .authenticator_mut()
+            .store_mut()
+            .as_mut()
+            .expect("A credential was just created; qed");
+        passkey.credential_id = credential_id.into();
+        passkey.key = CoseKeyBuilder::new_ec2_priv_key(
+            iana::EllipticCurve::P_256,
+            x,
+            y,
+            signing_key.to_bytes().to_vec(),
+        )
+        .algorithm(iana::Algorithm::ES256)
+        .build();
+    }
+
+    /// Signs `authData || clientDataHash` with the private key of the credential the
+    /// authenticator holds, as it signs its responses, returning the DER-encoded signature.
+    fn sign(&self, authenticator_data: &[u8], client_data_json: &[u8]) -> Vec<u8> {
+        let passkey = self
+            .client
+            .authenticator()
+            .store()
+            .as_ref()
+            .expect("The authenticator holds a credential; qed");
+        let private_key = passkey
+            .key
+            .params
+            .iter()
+            .find_map(|(label, value)| {
+                (*label == Label::Int(iana::Ec2KeyParameter::D as i64))
+                    .then(|| value.as_bytes())
+                    .flatten()
+            })
+            .expect("The passkey holds its EC2 private key; qed");
+        let signing_key =
+            SigningKey::from_slice(private_key).expect("The private key is a P-256 scalar; qed");
+
+        let signature: Signature = signing_key.sign(
+            &[
+                authenticator_data,
+                &verifier::client_data_hash(client_data_json),
+            ]
+            .concat(),
+        );
+        signature.to_der().as_bytes().to_vec()
+    }
+
+    /// Signs `tampered` again with the private key of the credential the authenticator holds,
+    /// so only what was tampered with makes it invalid, not a broken signature.
+    pub fn resign<T: Tamper>(&self, tampered: &T) -> T {
+        let mut resigned = tampered.clone();
+        let authenticator_data = sealed::Parts::authenticator_data(&mut resigned).clone();
+        let client_data = sealed::Parts::client_data(&mut resigned).clone();
+        *sealed::Parts::signature(&mut resigned) = self.sign(&authenticator_data, &client_data);
+        resigned
+    }
+
+    /// Re-encodes the attestation object of a freshly created credential as `packed`
+    /// self-attestation, signing `authData || clientDataHash` with the credential private key,
+    /// as an authenticator without an attestation certificate would.
+    fn packed_self_attestation(&self, credential: &CreatedPublicKeyCredential) -> Vec<u8> {
+        let alg = credential.response.public_key_algorithm;
+        assert_eq!(
+            alg,
+            iana::Algorithm::ES256 as i64,
+            "Only P-256 credentials can be re-attested"
+        );
+        let authenticator_data = &credential.response.authenticator_data;
+        let signature = self.sign(authenticator_data, &credential.response.client_data_json);
+
+        let attestation_object = Value::Map(vec![
+            (Value::Text("fmt".into()), Value::Text("packed".into())),
+            (
+                Value::Text("attStmt".into()),
+                Value::Map(vec![
+                    (Value::Text("alg".into()), Value::Integer(alg.into())),
+                    (Value::Text("sig".into()), Value::Bytes(signature)),
+                ]),
+            ),
+            (
+                Value::Text("authData".into()),
+                Value::Bytes(authenticator_data.to_vec()),
+            ),
+        ]);
+
+        let mut encoded = Vec::new();
+        into_writer(&attestation_object, &mut encoded)
+            .expect("Writing to a vector doesn't fail; qed");
+        encoded
+    }
+
+    /// Signs `challenge` with the credential `credential_id`, returning the raw response of the
+    /// authenticator.
+    pub fn authenticate_credential_sync(
+        &mut self,
+        credential_id: impl Into<Bytes>,
+        challenge: impl Into<Bytes>,
+    ) -> Result<AuthenticatedPublicKeyCredential, ClientError> {
+        let request_options = CredentialRequestOptions {
+            public_key: PublicKeyCredentialRequestOptions {
+                challenge: challenge.into(), // Provided as input
+                rp_id: Some(self.origin.domain().unwrap().into()),
+                allow_credentials: Some(vec![PublicKeyCredentialDescriptor {
+                    ty: PublicKeyCredentialType::PublicKey,
+                    id: credential_id.into(),
+                    transports: None,
+                }]),
+                timeout: None,
+                user_verification: self.user_verification,
+                hints: None,
+                attestation: AttestationConveyancePreference::None,
+                attestation_formats: None,
+                extensions: None,
+            },
+        };
+
+        let mut credential = block_on(self.client.authenticate(
+            &self.origin,
+            request_options,
+            DefaultClientData,
+        ))?;
+        if self.omit_user_handle {
+            credential.response.user_handle = None;
+        }
+        Ok(credential)
+    }
+
+    /// Registers a new credential for `user_id` with `authority_id`, signing the challenge of
+    /// `context`. Returns the credential id along with the attestation, whose device id is the
+    /// BLAKE2-256 hash of the credential id, and whose user id is `user_id`.
+    pub fn attestation(
+        &mut self,
+        user_id: HashedUserId,
+        context: CxOf<Ch>,
+        authority_id: AuthorityId,
+    ) -> (Vec<u8>, crate::Attestation<CxOf<Ch>>) {
+        let challenge = Ch::generate(&context);
+        self.attestation_with_challenge(user_id, challenge, context, authority_id)
+    }
+
+    /// Like [`attestation`](Self::attestation), but signing `challenge` instead.
+    pub fn attestation_with_challenge(
+        &mut self,
+        user_id: HashedUserId,
+        challenge: Challenge,
+        context: CxOf<Ch>,
+        authority_id: AuthorityId,
+    ) -> (Vec<u8>, crate::Attestation<CxOf<Ch>>) {
+        let credential = self
+            .create_credential_sync(user_id, challenge.as_slice(), &[])
+            .expect("Failed creating credential");
+        let credential_id: Vec<u8> = credential.raw_id.clone().into();
+        // Creation responses carry no user handle: the user is the one of the creation options.
+        let meta = AttestationMeta::new(
+            authority_id,
+            ids::DeviceId::from_credential_id(&credential_id).into(),
+            context,
+        )
+        .with_user_id(user_id);
+
+        (
+            credential_id,
+            (credential, meta)
+                .try_into()
+                .expect("The authenticator returns a valid P-256 public key; qed"),
+        )
+    }
+
+    /// Creates a credential on each client for the same user, each signing the challenge for its
+    /// index in the batch.
+    pub fn batch_attestation(
+        clients: &mut [Self],
+        user_id: HashedUserId,
+        context: CxOf<Ch>,
+        authority_id: AuthorityId,
+    ) -> (Vec<Vec<u8>>, crate::BatchAttestation<CxOf<Ch>>) {
+        let challenge = Ch::generate(&context);
+        let (credential_ids, attestations) = (0..)
+            .zip(clients)
+            .map(|(index, client)| {
+                client.attestation_with_challenge(
+                    user_id,
+                    batch_challenge(&challenge, index),
+                    context,
+                    authority_id,
+                )
+            })
+            .unzip();
+
+        (
+            credential_ids,
+            crate::BatchAttestation::new(context, attestations)
+                .expect("Tests don't exceed the batch size; qed"),
+        )
+    }
+
+    /// Authenticates with the credential `credential_id` for `authority_id`, signing the
+    /// challenge of `context`. The user is the one the assertion's user handle names, see
+    /// [`assertion_for_user`](Self::assertion_for_user) for assertions without one.
+    pub fn assertion(
+        &mut self,
+        credential_id: impl Into<Bytes>,
+        context: CxOf<Ch>,
+        authority_id: AuthorityId,
+    ) -> crate::Assertion<CxOf<Ch>> {
+        self.assertion_with(credential_id, None, None, context, authority_id)
+    }
+
+    /// Like [`assertion`](Self::assertion), for the credential of `user_id`, as a relying party
+    /// that asked for a credential that isn't discoverable knows whose it is. When the assertion
+    /// has a user handle, it must be `user_id`.
+    pub fn assertion_for_user(
+        &mut self,
+        credential_id: impl Into<Bytes>,
+        user_id: HashedUserId,
+        context: CxOf<Ch>,
+        authority_id: AuthorityId,
+    ) -> crate::Assertion<CxOf<Ch>> {
+        self.assertion_with(credential_id, Some(user_id), None, context, authority_id)
+    }
+
+    /// Like [`assertion`](Self::assertion), but with the signature counter of the authenticator
+    /// data set to `counter`, and signed again, e.g. to make assertions whose counter doesn't
+    /// advance, as those of a cloned authenticator.
+    pub fn assertion_with_counter(
+        &mut self,
+        credential_id: impl Into<Bytes>,
+        context: CxOf<Ch>,
+        authority_id: AuthorityId,
+        counter: u32,
+    ) -> crate::Assertion<CxOf<Ch>> {
+        self.assertion_with(credential_id, None, Some(counter), context, authority_id)
+    }
+
+    fn assertion_with(
+        &mut self,
+        credential_id: impl Into<Bytes>,
+        user_id: Option<HashedUserId>,
+        counter: Option<u32>,
+        context: CxOf<Ch>,
+        authority_id: AuthorityId,
+    ) -> crate::Assertion<CxOf<Ch>> {
+        let challenge = Ch::generate(&context);
+
+        let mut credential = self
+            .authenticate_credential_sync(credential_id, challenge.as_slice())
+            .expect("Failed retrieving credential");
+        if let Some(counter) = counter {
+            let mut authenticator_data = credential.response.authenticator_data.to_vec();
+            authenticator_data[33..37].copy_from_slice(&counter.to_be_bytes());
+            credential.response.signature = self
+                .sign(&authenticator_data, &credential.response.client_data_json)
+                .into();
+            credential.response.authenticator_data = authenticator_data.into();
+        }
+        let user_id = user_id
+            .or_else(|| user_id_from_user_handle(&credential))
+            .expect("Assertions without a user handle are made with `assertion_for_user`");
+        let meta = AssertionMeta::new(authority_id, user_id, context);
+
+        (credential, meta)
+            .try_into()
+            .expect("`user_handle` matches the `user_id` in meta; qed")
+    }
+
+    /// Registers a new credential for `user_id` and authenticates with it, returning the
+    /// assertion as a fixture expecting it to verify, e.g. to [save](fixture::Fixture::save)
+    /// along with captured ones.
+    pub fn assertion_fixture(
+        &mut self,
+        description: impl Into<String>,
+        user_id: HashedUserId,
+        context: CxOf<Ch>,
+        authority_id: AuthorityId,
+    ) -> fixture::Fixture {
+        let (credential_id, attestation) = self.attestation(user_id, context, authority_id);
+        let assertion = self.assertion(credential_id, context, authority_id);
+        fixture::Fixture::from_responses(description, &attestation, &assertion)
+    }
+}
+
+mod sealed {
+    /// The parts of a response tampering applies to.
+    pub trait Parts {
+        /// A ceremony type the response isn't for.
+        const OTHER_TYPE: &'static str;
+
+        fn client_data(&mut self) -> &mut Vec<u8>;
+        fn authenticator_data(&mut self) -> &mut Vec<u8>;
+        fn signature(&mut self) -> &mut Vec<u8>;
+    }
+}
+
+impl<Cx, P> sealed::Parts for crate::Attestation<Cx, P> {
+    const OTHER_TYPE: &'static str = "webauthn.get";
+
+    fn client_data(&mut self) -> &mut Vec<u8> {
+        &mut self.client_data
+    }
+
+    fn authenticator_data(&mut self) -> &mut Vec<u8> {
+        &mut self.authenticator_data
+    }
+
+    fn signature(&mut self) -> &mut Vec<u8> {
+        match &mut self.attestation_statement {
+            crate::AttestationStatement::Packed { sig, .. }
+            | crate::AttestationStatement::PackedX5c { sig, .. } => sig,
+            crate::AttestationStatement::None => {
+                panic!("`none` attestation carries no signature to tamper with")
+            }
+        }
+    }
+}
+
+impl<Cx> sealed::Parts for crate::Assertion<Cx> {
+    const OTHER_TYPE: &'static str = "webauthn.create";
+
+    fn client_data(&mut self) -> &mut Vec<u8> {
+        &mut self.client_data
+    }
+
+    fn authenticator_data(&mut self) -> &mut Vec<u8> {
+        &mut self.authenticator_data
+    }
+
+    fn signature(&mut self) -> &mut Vec<u8> {
+        &mut self.signature
+    }
+}
+
+/// Replaces the value of the string `member` of the client data JSON with `value`.
+fn with_member(client_data: &[u8], member: &str, value: &str) -> Vec<u8> {
+    let client_data =
+        core::str::from_utf8(client_data).expect("The client data is UTF-8 encoded JSON; qed");
+    let key = format!("\"{member}\":\"");
+    let start = client_data
+        .find(&key)
+        .unwrap_or_else(|| panic!("The client data has no `{member}`"))
+        + key.len();
+    let end = start
+        + client_data[start..]
+            .find('"')
+            .expect("String members are terminated; qed");
+    [&client_data[..start], value, &client_data[end..]]
+        .concat()
+        .into_bytes()
+}
+
+/// Corrupted copies of attestations and assertions, one per way a response can be wrong, for
+/// negative tests. The original is left untouched, so it remains usable.
+pub trait Tamper: sealed::Parts + Clone {
+    /// Signs (as far as the client data goes) a challenge that wasn't issued.
+    fn with_wrong_challenge(&self) -> Self {
+        let mut tampered = self.clone();
+        let challenge = base64::encode_engine([0xff; 32], &BASE64_URL_SAFE_NO_PAD);
+        *tampered.client_data() = with_member(tampered.client_data(), "challenge", &challenge);
+        tampered
+    }
+
+    /// Claims the response was made at another origin.
+    fn with_wrong_origin(&self) -> Self {
+        let mut tampered = self.clone();
+        *tampered.client_data() =
+            with_member(tampered.client_data(), "origin", "https://evil.example");
+        tampered
+    }
+
+    /// Claims the response is for the other ceremony.
+    fn with_wrong_type(&self) -> Self {
+        let mut tampered = self.clone();
+        *tampered.client_data() = with_member(tampered.client_data(), "type", Self::OTHER_TYPE);
+        tampered
+    }
+
+    fn with_flipped_signature_bit(&self) -> Self {
+        let mut tampered = self.clone();
+        *tampered
+            .signature()
+            .last_mut()
+            .expect("Signatures are not empty; qed") ^= 0x01;
+        tampered
+    }
+
+    /// Cuts the authenticator data one byte short of its RP ID hash, flags and counter.
+    fn with_truncated_authenticator_data(&self) -> Self {
+        let mut tampered = self.clone();
+        tampered.authenticator_data().truncate(36);
+        tampered
+    }
+
+    fn with_user_presence_cleared(&self) -> Self {
+        let mut tampered = self.clone();
+        tampered.authenticator_data()[32] &= !AuthenticatorDataFlags::USER_PRESENT.bits();
+        tampered
+    }
+
+    /// Moves the signature counter one step back (wrapping around at zero).
+    fn with_decremented_counter(&self) -> Self {
+        let mut tampered = self.clone();
+        let authenticator_data = tampered.authenticator_data();
+        let decremented = verifier::sign_count_from_authenticator_data(authenticator_data)
+            .expect("The authenticator returns valid authenticator data; qed")
+            .wrapping_sub(1);
+        authenticator_data[33..37].copy_from_slice(&decremented.to_be_bytes());
+        tampered
+    }
+}
+
+impl<Cx: Clone, P: Clone> Tamper for crate::Attestation<Cx, P> {}
+
+impl<Cx: Clone> Tamper for crate::Assertion<Cx> {}
+
+impl<Cx, P> crate::Attestation<Cx, P> {
+    /// The flags of the authenticator data, e.g. to check whether the user was verified.
+    pub fn flags(&self) -> AuthenticatorDataFlags {
+        verifier::parse_authenticator_data(&self.authenticator_data)
+            .expect("The authenticator returns valid authenticator data; qed")
+            .flags
+    }
+}
+
+impl<Cx> crate::Assertion<Cx> {
+    /// The flags of the authenticator data, e.g. to check whether the user was verified.
+    pub fn flags(&self) -> AuthenticatorDataFlags {
+        verifier::parse_authenticator_data(&self.authenticator_data)
+            .expect("The authenticator returns valid authenticator data; qed")
+            .flags
+    }
+}
+
+impl<Cx: Clone, P: Clone> crate::Attestation<Cx, P> {
+    /// A copy of the attestation for a public key other than the one its statement signs.
+    pub fn with_swapped_public_key(&self) -> Self {
+        let other_key = SigningKey::from_slice(&[0x42; 32])
+            .expect("The scalar is in range; qed")
+            .verifying_key()
+            .to_encoded_point(false);
+        Self {
+            public_key: verifier::sec1_public_key_to_der(other_key.as_bytes())
+                .ok()
+                .and_then(|der| crate::DEREncodedPublicKey::try_from(&der[..]).ok())
+                .expect("The key is a valid P-256 point; qed"),
+            ..self.clone()
+        }
+    }
+}
+
+/// DER-encodes an element.
+fn der(tag: u8, contents: &[u8]) -> Vec<u8> {
+    let len = match contents.len() {
+        len @ 0..=0x7f => vec![len as u8],
+        len @ 0x80..=0xff => vec![0x81, len as u8],
+        len => [&[0x82][..], &(len as u16).to_be_bytes()].concat(),
+    };
+    [&[tag][..], &len, contents].concat()
+}
+
+/// A minimal X.509 v3 attestation certificate, without extensions, for `public_key_der`. Its
+/// signature is made up, as the verifier doesn't chain certificates up to a root.
+fn attestation_certificate(public_key_der: &[u8]) -> Vec<u8> {
+    let ecdsa_with_sha256 = der(
+        0x30,
+        &der(0x06, &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02]),
+    );
+    let name = der(
+        0x30,
+        &der(
+            0x31,
+            &der(
+                0x30,
+                &[der(0x06, &[0x55, 0x04, 0x03]), der(0x0c, b"test")].concat(),
+            ),
+        ),
+    );
+    let validity = der(
+        0x30,
+        &[der(0x17, b"250101000000Z"), der(0x17, b"350101000000Z")].concat(),
+    );
+    let tbs = der(
+        0x30,
+        &[
+            der(0xa0, &der(0x02, &[0x02])),
+            der(0x02, &[0x01]),
+            ecdsa_with_sha256.clone(),
+            name.clone(),
+            validity,
+            name,
+            public_key_der.to_vec(),
+        ]
+        .concat(),
+    );
+    der(
+        0x30,
+        &[tbs, ecdsa_with_sha256, der(0x03, &[0x00, 0x30, 0x00])].concat(),
+    )
+}
+
+impl<Cx: Clone, P: Clone> crate::Attestation<Cx, P> {
+    /// A copy of the attestation with its `packed` self-attestation presented as basic
+    /// attestation (`x5c`), under a certificate for the credential key that made the signature.
+    pub fn with_x5c_statement(&self) -> Self {
+        let crate::AttestationStatement::Packed { alg, sig } = &self.attestation_statement else {
+            panic!("The test client returns `packed` self-attestation");
+        };
+        Self {
+            attestation_statement: crate::AttestationStatement::PackedX5c {
+                alg: *alg,
+                sig: sig.clone(),
+                x5c: vec![attestation_certificate(self.public_key.as_der())],
+            },
+            ..self.clone()
+        }
+    }
+}
@@ -0,0 +1,367 @@
+//! Captured WebAuthn responses stored as JSON, replayed against both the verifier and
+//! [`Credential::verify_assertion`](crate::Credential::verify_assertion).
+//!
+//! A fixture is an assertion along with the public key it's checked against, and what verifying
+//! it should yield. Binary fields are base64url-encoded, without padding:
+//!
+//! ```json
+//! {
+//!   "description": "YubiKey 5 NFC, ES256, user verified",
+//!   "authenticator_data": "SZYN5YgOjGh0NBcPZHZgW4_krrmihjLHmVzzuoMdl2MFAAAAAQ",
+//!   "client_data_json": "eyJ0eXBlIjoid2ViYXV0aG4uZ2V0Ii...",
+//!   "signature": "MEUCIQDq...",
+//!   "public_key": "MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAE...",
+//!   "parsed": { "flags": 5, "sign_count": 1 },
+//!   "policy": { "expected_challenge": "dGVzdA", "stored_sign_count": 0, "uv_required": true },
+//!   "expected": "ok"
+//! }
+//! ```
+//!
+//! `public_key` is a DER-encoded `SubjectPublicKeyInfo`. `parsed` lists what the authenticator
+//! data decodes to, its `aaguid` in the usual UUID form. `parsed`, `policy` and each of their
+//! members are optional, and `expected` is either `"ok"` or the name of the error, as in
+//! `{ "error": "VerifySignature" }`. Errors are named after their
+//! [`VerifyError`](verifier::VerifyError) variant, or their [`WebAuthnError`] one for those
+//! outside of [`WebAuthnError::Verify`].
+//!
+//! Checking in a new capture takes no code: [`replay_dir`] picks up every `.json` file of the
+//! directory it's given.
+
+use base64::prelude::BASE64_URL_SAFE_NO_PAD;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+use verifier::{
+    parse_authenticator_data, verify_challenge, webauthn_verify, webauthn_verify_with_challenge,
+    AuthenticatorDataFlags, CoseAlgorithm,
+};
+
+use crate::{
+    runtime_helpers::find_challenge_from_client_data, Assertion, AssertionMeta, Attestation,
+    Credential, CredentialPublicKey, DEREncodedPublicKey, WebAuthnError,
+};
+
+/// Errors only [`Credential::verify_assertion`] checks for, from the client data type, the user
+/// presence flag or the stored state `policy` describes. The verifier alone accepts assertions
+/// failing with them.
+const CREDENTIAL_ONLY_ERRORS: [&str; 5] = [
+    "BackupStateInconsistency",
+    "InvalidClientData(TypeMismatch)",
+    "Replayed",
+    "UserNotPresent",
+    "UserVerificationRequired",
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Fixture {
+    /// What made the response, e.g. the authenticator and browser it was captured from.
+    pub description: String,
+    #[serde(with = "base64url")]
+    pub authenticator_data: Vec<u8>,
+    #[serde(with = "base64url")]
+    pub client_data_json: Vec<u8>,
+    #[serde(with = "base64url")]
+    pub signature: Vec<u8>,
+    /// The DER-encoded `SubjectPublicKeyInfo` of the credential.
+    #[serde(with = "base64url")]
+    pub public_key: Vec<u8>,
+    #[serde(default, skip_serializing_if = "Parsed::is_empty")]
+    pub parsed: Parsed,
+    #[serde(default)]
+    pub policy: Policy,
+    pub expected: Outcome,
+}
+
+/// The fields the authenticator data must decode to, each left unchecked when absent.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct Parsed {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flags: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sign_count: Option<u32>,
+    /// Only found in the authenticator data of registrations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aaguid: Option<String>,
+}
+
+impl Parsed {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Formats an AAGUID as a lowercase UUID, e.g. `cb69481e-8ff7-4039-93ec-0a2729a154a8`.
+fn format_aaguid(aaguid: &[u8; 16]) -> String {
+    aaguid
+        .iter()
+        .enumerate()
+        .map(|(i, byte)| match i {
+            4 | 6 | 8 | 10 => format!("-{byte:02x}"),
+            _ => format!("{byte:02x}"),
+        })
+        .collect()
+}
+
+/// What the relying party knows about the ceremony and the stored credential.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct Policy {
+    /// The challenge issued for the ceremony, left unchecked when absent.
+    #[serde(with = "base64url::option", skip_serializing_if = "Option::is_none")]
+    pub expected_challenge: Option<Vec<u8>>,
+    /// The signature counter stored along with the credential.
+    pub stored_sign_count: u32,
+    /// Whether the credential requires user verification.
+    pub uv_required: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Ok,
+    /// The name of the error, see the [module documentation](self).
+    Error(String),
+}
+
+impl Outcome {
+    fn of<E: core::fmt::Debug>(result: Result<(), E>) -> Self {
+        match result {
+            Ok(()) => Self::Ok,
+            Err(e) => Self::Error(format!("{e:?}")),
+        }
+    }
+}
+
+impl Fixture {
+    /// A fixture expecting `assertion` to verify against the public key of `attestation`, with
+    /// the challenge it signed.
+    pub fn from_responses<Cx, P>(
+        description: impl Into<String>,
+        attestation: &Attestation<Cx, P>,
+        assertion: &Assertion<Cx>,
+    ) -> Self {
+        Self {
+            description: description.into(),
+            authenticator_data: assertion.authenticator_data.clone(),
+            client_data_json: assertion.client_data.clone(),
+            signature: assertion.signature.clone(),
+            public_key: attestation.public_key.as_der().to_vec(),
+            parsed: Parsed::default(),
+            policy: Policy {
+                expected_challenge: find_challenge_from_client_data(&assertion.client_data)
+                    .map(|challenge| challenge.to_vec()),
+                ..Default::default()
+            },
+            expected: Outcome::Ok,
+        }
+    }
+
+    /// Reads a fixture from a JSON file.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        serde_json::from_slice(&fs::read(path)?).map_err(io::Error::other)
+    }
+
+    /// Writes the fixture to a JSON file, pretty-printed so captures diff well.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        json.push('\n');
+        fs::write(path, json)
+    }
+
+    /// Verifies the response with [`webauthn_verify`], checking the challenge too when the
+    /// policy has one.
+    pub fn verify_low_level(&self) -> Outcome {
+        Outcome::of(match &self.policy.expected_challenge {
+            Some(challenge) => webauthn_verify_with_challenge(
+                &self.authenticator_data,
+                &self.client_data_json,
+                &self.signature,
+                &self.public_key,
+                challenge,
+            ),
+            None => webauthn_verify(
+                &self.authenticator_data,
+                &self.client_data_json,
+                &self.signature,
+                &self.public_key,
+            ),
+        })
+    }
+
+    /// Verifies the response as an [`Assertion`] against a [`Credential`] holding the public key
+    /// and stored state of the policy, checking the challenge too when the policy has one.
+    pub fn verify_high_level(&self) -> Outcome {
+        let credential = match DEREncodedPublicKey::try_from(&self.public_key[..])
+            .and_then(|der| CredentialPublicKey::try_from(&der))
+        {
+            Ok(public_key) => Credential {
+                device_id: [0; 32],
+                credential_id_hash: [0; 32],
+                public_key,
+                authenticator_attachment: Default::default(),
+                sign_count: self.policy.stored_sign_count.into(),
+                algorithm: CoseAlgorithm::ES256.cose_id(),
+                registration_flags: AuthenticatorDataFlags::default(),
+                uv_required: self.policy.uv_required,
+                transports: Default::default(),
+            },
+            Err(e) => return Outcome::of(Err(e)),
+        };
+        let assertion = Assertion {
+            meta: AssertionMeta::new([0; 32], [0; 32], ()),
+            authenticator_data: self.authenticator_data.clone(),
+            client_data: self.client_data_json.clone(),
+            signature: self.signature.clone(),
+            credential_id: Vec::new(),
+        };
+
+        let result = credential.verify_assertion(&assertion).and_then(|_| {
+            self.policy
+                .expected_challenge
+                .as_ref()
+                .map_or(Ok(()), |challenge| {
+                    verify_challenge(&self.client_data_json, challenge).map_err(Into::into)
+                })
+        });
+        match result {
+            Err(WebAuthnError::Verify(e)) => Outcome::of(Err(e)),
+            result => Outcome::of(result),
+        }
+    }
+
+    /// Checks the authenticator data decodes to the [`Parsed`] fields.
+    pub fn check_parsed(&self) -> Result<(), String> {
+        if self.parsed.is_empty() {
+            return Ok(());
+        }
+        let data = parse_authenticator_data(&self.authenticator_data)
+            .map_err(|e| format!("{}: {e:?}", self.description))?;
+        let actual = Parsed {
+            flags: self.parsed.flags.and(Some(data.flags.bits())),
+            sign_count: self.parsed.sign_count.and(Some(data.sign_count)),
+            aaguid: self.parsed.aaguid.as_ref().and_then(|_| {
+                data.attested_credential_data
+                    .map(|data| format_aaguid(&data.aaguid))
+            }),
+        };
+
+        (actual == self.parsed).then_some(()).ok_or_else(|| {
+            format!(
+                "{}: the authenticator data decodes to {actual:?}, expected {:?}",
+                self.description, self.parsed
+            )
+        })
+    }
+
+    /// Checks the authenticator data decodes to the [`Parsed`] fields, and both verifiers
+    /// yield the expected outcome, except for the errors only the credential checks for, where
+    /// the verifier must accept the response instead.
+    pub fn check(&self) -> Result<(), String> {
+        self.check_parsed()?;
+        let expected_low_level = match &self.expected {
+            Outcome::Error(e) if CREDENTIAL_ONLY_ERRORS.contains(&e.as_str()) => Outcome::Ok,
+            expected => expected.clone(),
+        };
+
+        [
+            ("verifier", expected_low_level, self.verify_low_level()),
+            (
+                "credential",
+                self.expected.clone(),
+                self.verify_high_level(),
+            ),
+        ]
+        .into_iter()
+        .try_for_each(|(level, expected, actual)| {
+            (expected == actual).then_some(()).ok_or_else(|| {
+                format!(
+                    "{}: the {level} returned {actual:?}, expected {expected:?}",
+                    self.description
+                )
+            })
+        })
+    }
+}
+
+/// Loads every `.json` file of `dir` as a fixture, in file name order.
+pub fn load_dir(dir: impl AsRef<Path>) -> io::Result<Vec<(PathBuf, Fixture)>> {
+    let mut paths = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .filter(|path| {
+            path.as_ref().map_or(true, |path| {
+                path.extension().is_some_and(|ext| ext == "json")
+            })
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let fixture = Fixture::load(&path)
+                .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))?;
+            Ok((path, fixture))
+        })
+        .collect()
+}
+
+/// Loads and [checks](Fixture::check) every fixture of `dir`, panicking with the failures of all
+/// those that don't check out. Returns how many were replayed.
+pub fn replay_dir(dir: impl AsRef<Path>) -> usize {
+    let fixtures = load_dir(dir).expect("Fixtures are readable and well-formed");
+    let failures: Vec<_> = fixtures
+        .iter()
+        .filter_map(|(path, fixture)| {
+            fixture
+                .check()
+                .err()
+                .map(|e| format!("{}: {e}", path.display()))
+        })
+        .collect();
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+    fixtures.len()
+}
+
+/// (De)serializes bytes as base64url, without padding.
+mod base64url {
+    use super::*;
+    use serde::{de::Error, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64::encode_engine(bytes, &BASE64_URL_SAFE_NO_PAD))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        base64::decode_engine(encoded.as_bytes(), &BASE64_URL_SAFE_NO_PAD).map_err(D::Error::custom)
+    }
+
+    pub mod option {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(
+            bytes: &Option<Vec<u8>>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match bytes {
+                Some(bytes) => super::serialize(bytes, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<Vec<u8>>, D::Error> {
+            Option::<String>::deserialize(deserializer)?
+                .map(|encoded| {
+                    base64::decode_engine(encoded.as_bytes(), &BASE64_URL_SAFE_NO_PAD)
+                        .map_err(D::Error::custom)
+                })
+                .transpose()
+        }
+    }
+}
@@ -9,11 +9,9 @@ use frame_support::{
 use frame_system::{pallet_prelude::BlockNumberFor, Config, EnsureRootWithSuccess};
 use traits_authn::{util::AuthorityFromPalletId, Challenger, HashedUserId};
 
-use crate::Authenticator;
+use crate::{AllowNoneAttestation, Authenticator, AuthenticatorOf, SubstrateWebAuthnRuntime};
 
-mod authenticator_client;
-
-use authenticator_client::*;
+type WebAuthnClient = crate::testing::WebAuthnClient<BlockChallenger>;
 
 #[frame_support::runtime]
 pub mod runtime {
@@ -71,11 +69,23 @@ impl Challenger for BlockChallenger {
     }
 }
 
+impl SubstrateWebAuthnRuntime for Test {
+    type Challenger = BlockChallenger;
+    type Authority = AuthorityId;
+    type AttestationPolicy = AllowNoneAttestation;
+}
+
+// The bundled types are the ones spelled out.
+static_assertions::assert_type_eq_all!(
+    AuthenticatorOf<Test>,
+    Authenticator<BlockChallenger, AuthorityId>
+);
+
 impl pallet_pass::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type RuntimeCall = RuntimeCall;
     type Currency = Balances;
-    type Authenticator = Authenticator<BlockChallenger, AuthorityId>;
+    type Authenticator = AuthenticatorOf<Test>;
     type PalletsOrigin = OriginCaller;
     type PalletId = PassPalletId;
     type MaxSessionDuration = ConstU64<10>;
@@ -96,7 +106,7 @@ impl pallet_pass::BenchmarkHelper<Test> for Helper {
     }
 
     fn device_attestation(_: traits_authn::DeviceId) -> pallet_pass::DeviceAttestationOf<Test, ()> {
-        WebAuthnClient::new("https://pass_web.pass.int", 1)
+        WebAuthnClient::new("https://pass-web.pass.int", 1)
             .attestation(
                 blake2_256(b"USER_ID"),
                 System::block_number(),
@@ -129,32 +139,126 @@ fn new_test_ext(times: usize) -> TestExt {
     t.execute_with(|| {
         System::set_block_number(1);
     });
-    TestExt(t, WebAuthnClient::new("https://pass_web.pass.int", times))
+    TestExt(t, WebAuthnClient::new("https://pass-web.pass.int", times))
 }
 
 const USER: HashedUserId = s("the_user");
 
+/// The credential an attestation of the test client registers.
+fn credential_of<Cx, P>(attestation: crate::Attestation<Cx, P>) -> crate::Credential {
+    crate::Credential::try_from(attestation).expect("The test client makes valid attestations; qed")
+}
+
+/// The authority id of a `PalletId`, its bytes padded with zeros.
+fn pallet_authority(pallet_id: &[u8; 8]) -> traits_authn::AuthorityId {
+    let mut authority = [0; 32];
+    authority[..8].copy_from_slice(pallet_id);
+    authority
+}
+
 use traits_authn::composite_prelude::Get;
 
+/// Registers a new credential for `user_id` and authenticates with it, panicking with the
+/// failing step if either doesn't succeed.
+///
+/// With `tamper = |assertion| ...`, the assertion is altered before authenticating, and the
+/// result of `Pass::authenticate` is returned instead, to check failure paths.
+macro_rules! assert_webauthn_roundtrip {
+    ($client:expr, $user_id:expr, $block_number:expr, $authority_id:expr $(,)?) => {
+        if let Err(e) = assert_webauthn_roundtrip!(
+            $client,
+            $user_id,
+            $block_number,
+            $authority_id,
+            tamper = |_| {}
+        ) {
+            panic!("authentication with the registered credential failed: {e:?}");
+        }
+    };
+    ($client:expr, $user_id:expr, $block_number:expr, $authority_id:expr, tamper = $tamper:expr $(,)?) => {{
+        fn tamper_with<Cx>(
+            assertion: &mut crate::Assertion<Cx>,
+            mut tamper: impl FnMut(&mut crate::Assertion<Cx>),
+        ) {
+            tamper(assertion)
+        }
+
+        let client: &mut WebAuthnClient = $client;
+        let (user_id, block_number, authority_id) = ($user_id, $block_number, $authority_id);
+
+        let (credential_id, attestation) = client.attestation(user_id, block_number, authority_id);
+        let device_id = *traits_authn::DeviceChallengeResponse::device_id(&attestation);
+        if let Err(e) = Pass::register(RuntimeOrigin::root(), user_id, attestation) {
+            panic!("registration of a new credential failed: {e:?}");
+        }
+
+        let mut assertion = client.assertion(credential_id, block_number, authority_id);
+        tamper_with(&mut assertion, $tamper);
+        Pass::authenticate(RuntimeOrigin::signed(1), device_id, assertion, None)
+    }};
+}
+
 mod attestation {
+    use codec::{Decode, Encode};
+    use traits_authn::{DeviceChallengeResponse, DeviceId};
+    use verifier::VerifyError;
+
     use super::*;
+    use crate::{
+        testing::{ClientError, Tamper},
+        Attestation, WebAuthnError,
+    };
+
+    #[test]
+    fn attestations_record_the_user_they_were_created_for() {
+        new_test_ext(2).execute_with(|client| {
+            let (credential_id, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            assert_eq!(attestation.meta.user_id(), Some(USER));
+            assert_eq!(
+                Attestation::<_>::decode(&mut &attestation.encode()[..]),
+                Ok(attestation.clone())
+            );
+
+            let assertion =
+                client.assertion(credential_id, System::block_number(), AuthorityId::get());
+            assert_eq!(attestation.meta.user_id(), Some(assertion.meta.user_id));
+        })
+    }
 
     #[test]
     fn registration_fails_if_attestation_is_invalid() {
+        new_test_ext(1).execute_with(|client| {
+            let (_, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+
+            assert_noop!(
+                Pass::register(
+                    RuntimeOrigin::root(),
+                    USER,
+                    attestation.with_wrong_challenge()
+                ),
+                pallet_pass::Error::<Test>::DeviceAttestationInvalid,
+            );
+        })
+    }
+
+    #[test]
+    fn registration_fails_if_challenge_is_missing_instead_of_defaulting() {
         new_test_ext(1).execute_with(|client| {
             let (_, mut attestation) =
                 client.attestation(USER, System::block_number(), AuthorityId::get());
 
-            // Alters "challenge", so this will fail
             attestation.client_data = String::from_utf8(attestation.client_data)
                 .map(|client_data| {
                     client_data
-                        .replace("challenge", "chellang")
-                        .as_bytes()
-                        .to_vec()
+                        .replace("\"challenge\"", "\"nonce\"")
+                        .into_bytes()
                 })
                 .expect("`client_data` is a buffer representation of a utf-8 encoded json");
 
+            assert!(!attestation.is_valid());
+            assert_ne!(attestation.used_challenge().1, [0u8; 32]);
             assert_noop!(
                 Pass::register(RuntimeOrigin::root(), USER, attestation),
                 pallet_pass::Error::<Test>::DeviceAttestationInvalid,
@@ -162,6 +266,105 @@ mod attestation {
         })
     }
 
+    #[test]
+    fn registering_an_existing_credential_again_is_rejected() {
+        new_test_ext(2).execute_with(|client| {
+            let (_, registered) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let (_, new) = client.attestation(USER, System::block_number(), AuthorityId::get());
+            let is_credential_registered =
+                |device_id: &DeviceId| device_id == registered.device_id();
+
+            assert_eq!(
+                registered.validate_new_credential(is_credential_registered),
+                Err(VerifyError::CredentialAlreadyRegistered.into())
+            );
+            assert_eq!(
+                new.validate_new_credential(is_credential_registered),
+                Ok(())
+            );
+        })
+    }
+
+    #[test]
+    fn registered_credentials_cant_come_back_under_another_device_id() {
+        new_test_ext(2).execute_with(|client| {
+            let (_, registered) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let is_credential_registered =
+                |device_id: &DeviceId| device_id == registered.device_id();
+
+            let mut reused = registered.clone();
+            reused.meta.device_id = [0xaa; 32];
+            assert!(!is_credential_registered(reused.device_id()));
+            assert_eq!(
+                reused.validate_new_credential(is_credential_registered),
+                Err(WebAuthnError::DeviceMismatch)
+            );
+        })
+    }
+
+    #[test]
+    fn excluded_credentials_are_not_created_again() {
+        new_test_ext(2).execute_with(|client| {
+            let context = System::block_number();
+            let (credential_id, _) = client.attestation(USER, context, AuthorityId::get());
+
+            let challenge = BlockChallenger::generate(&context);
+            assert!(matches!(
+                client.create_credential_sync(USER, challenge.as_slice(), &[credential_id]),
+                Err(ClientError::CredentialExcluded)
+            ));
+            // Excluding credentials the authenticator doesn't hold stands in nobody's way.
+            assert!(client
+                .create_credential_sync(USER, challenge.as_slice(), &[vec![0; 16]])
+                .is_ok());
+        })
+    }
+
+    #[test]
+    fn registering_the_same_device_twice_fails() {
+        new_test_ext(1).execute_with(|client| {
+            let (_, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            assert_ok!(Pass::register(
+                RuntimeOrigin::root(),
+                USER,
+                attestation.clone()
+            ));
+
+            assert!(Pass::register(RuntimeOrigin::root(), USER, attestation.clone()).is_err());
+            // What a relying party checking its registered credentials fails the call with.
+            assert_eq!(
+                attestation
+                    .validate_new_credential(|device_id| device_id == attestation.device_id())
+                    .map_err(WebAuthnError::into_pass_error::<Test, ()>),
+                Err(pallet_pass::Error::<Test>::DeviceAttestationInvalid)
+            );
+        })
+    }
+
+    #[test]
+    fn attestations_expire_after_the_session_duration() {
+        new_test_ext(1).execute_with(|client| {
+            let context = System::block_number();
+            let (_, attestation) = client.attestation(USER, context, AuthorityId::get());
+            let max_age = <Test as pallet_pass::Config>::MaxSessionDuration::get();
+            assert_eq!(attestation.expires_at(max_age), context + max_age);
+
+            System::set_block_number(context + max_age);
+            assert_eq!(
+                attestation.check_not_expired(System::block_number(), max_age),
+                Ok(())
+            );
+            System::set_block_number(context + max_age + 1);
+            assert_eq!(
+                attestation.check_not_expired(System::block_number(), max_age),
+                Err(WebAuthnError::ChallengeExpired)
+            );
+        })
+    }
+
     #[test]
     fn registration_works_if_attestation_is_valid() {
         new_test_ext(1).execute_with(|client| {
@@ -177,9 +380,10 @@ mod attestation {
 }
 
 mod assertion {
-    use traits_authn::DeviceChallengeResponse;
+    use traits_authn::{DeviceChallengeResponse, UserChallengeResponse};
 
     use super::*;
+    use crate::testing::Tamper;
 
     #[test]
     fn authentication_fails_if_credentials_are_invalid() {
@@ -193,15 +397,14 @@ mod assertion {
                 attestation.clone()
             ));
 
-            let mut assertion =
+            let assertion =
                 client.assertion(credential_id, System::block_number(), AuthorityId::get());
-            assertion.signature = [assertion.signature, b"Whoops".to_vec()].concat();
 
             assert_noop!(
                 Pass::authenticate(
                     RuntimeOrigin::signed(1),
                     *(attestation.device_id()),
-                    assertion,
+                    assertion.with_flipped_signature_bit(),
                     None
                 ),
                 pallet_pass::Error::<Test>::CredentialInvalid
@@ -209,6 +412,27 @@ mod assertion {
         })
     }
 
+    #[test]
+    fn assertion_is_invalid_if_challenge_is_missing() {
+        new_test_ext(2).execute_with(|client| {
+            let (credential_id, _) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+
+            let mut assertion =
+                client.assertion(credential_id, System::block_number(), AuthorityId::get());
+            assertion.client_data = String::from_utf8(assertion.client_data)
+                .map(|client_data| {
+                    client_data
+                        .replace("\"challenge\"", "\"nonce\"")
+                        .into_bytes()
+                })
+                .expect("`client_data` is a buffer representation of a utf-8 encoded json");
+
+            assert!(!assertion.is_valid());
+            assert_ne!(assertion.used_challenge().1, [0u8; 32]);
+        })
+    }
+
     #[test]
     fn authentication_works_if_credentials_are_valid() {
         new_test_ext(2).execute_with(|client| {
@@ -229,4 +453,3140 @@ mod assertion {
             ));
         })
     }
+
+    #[test]
+    fn authentication_works_without_a_user_handle() {
+        new_test_ext(0).execute_with(|_| {
+            let mut client =
+                WebAuthnClient::new("https://pass-web.pass.int", 2).without_user_handle();
+            let (credential_id, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            assert_ok!(Pass::register(
+                RuntimeOrigin::root(),
+                USER,
+                attestation.clone()
+            ));
+
+            let challenge = BlockChallenger::generate(&System::block_number());
+            let authenticated = client
+                .authenticate_credential_sync(credential_id.clone(), challenge.as_slice())
+                .expect("Failed retrieving credential");
+            assert_eq!(authenticated.response.user_handle, None);
+            assert_eq!(
+                crate::passkey_compat::user_id_from_user_handle(&authenticated),
+                None
+            );
+
+            let assertion = client.assertion_for_user(
+                credential_id,
+                USER,
+                System::block_number(),
+                AuthorityId::get(),
+            );
+            assert_eq!(assertion.user_id(), USER);
+            assert_ok!(Pass::authenticate(
+                RuntimeOrigin::signed(1),
+                *(attestation.device_id()),
+                assertion,
+                None
+            ));
+        })
+    }
+}
+
+mod attestation_statement {
+    use sp_io::hashing::blake2_256;
+    use traits_authn::DeviceChallengeResponse;
+
+    use super::*;
+    use crate::{
+        testing::Tamper, AllowNoneAttestation, Attestation, AttestationMeta, AttestationPolicy,
+        AttestationStatement, AuthenticatorStatus, DevicePolicyEngine, RequireSignedAttestation,
+        RequireX5cAttestation, StatusReport, WebAuthnError, WithDevicePolicy,
+        WithRevocationChecker,
+    };
+    use verifier::{RevocationChecker, RevocationError, VerifyError};
+
+    parameter_types! {
+        // The test authenticator doesn't disclose its model, so it has the all-zero AAGUID.
+        pub CompromisedTestAuthenticator: DevicePolicyEngine =
+            DevicePolicyEngine::from_mds_status_reports(&[StatusReport {
+                aaguid: [0; 16],
+                status: AuthenticatorStatus::AttestationKeyCompromise,
+            }]);
+        pub OtherCompromisedModel: DevicePolicyEngine = DevicePolicyEngine::default().block([1; 16]);
+    }
+
+    fn attestation<P: AttestationPolicy>(
+        client: &mut WebAuthnClient,
+    ) -> Attestation<BlockNumberFor<Test>, P> {
+        let context = System::block_number();
+        let created = client
+            .create_credential_sync(USER, BlockChallenger::generate(&context).as_slice(), &[])
+            .expect("Failed creating credential");
+        let meta = AttestationMeta::new(AuthorityId::get(), blake2_256(&created.raw_id), context);
+
+        Attestation::try_from((created, meta))
+            .expect("The authenticator returns a valid P-256 public key; qed")
+    }
+
+    #[test]
+    fn packed_self_attestation_is_accepted() {
+        new_test_ext(2).execute_with(|client| {
+            let attestation = attestation::<RequireSignedAttestation>(client);
+            assert!(matches!(
+                attestation.attestation_statement,
+                AttestationStatement::Packed { .. }
+            ));
+            assert!(attestation.is_valid());
+        })
+    }
+
+    #[test]
+    fn tampered_attestation_statement_is_rejected() {
+        new_test_ext(1).execute_with(|client| {
+            let (_, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let attestation = attestation.with_flipped_signature_bit();
+
+            assert!(!attestation.is_valid());
+            assert_noop!(
+                Pass::register(RuntimeOrigin::root(), USER, attestation),
+                pallet_pass::Error::<Test>::DeviceAttestationInvalid,
+            );
+        })
+    }
+
+    #[test]
+    fn none_attestation_is_accepted_only_when_policy_allows() {
+        new_test_ext(2).execute_with(|client| {
+            let mut allowed = attestation::<AllowNoneAttestation>(client);
+            allowed.attestation_statement = AttestationStatement::None;
+            assert!(allowed.is_valid());
+
+            let mut required = attestation::<RequireSignedAttestation>(client);
+            required.attestation_statement = AttestationStatement::None;
+            assert!(!required.is_valid());
+        })
+    }
+
+    #[test]
+    fn public_keys_other_than_the_attested_one_are_rejected() {
+        new_test_ext(2).execute_with(|client| {
+            // A `none` statement vouches for nothing, so only the authenticator data binds the
+            // key there.
+            let mut unsigned = attestation::<AllowNoneAttestation>(client);
+            unsigned.attestation_statement = AttestationStatement::None;
+            assert_eq!(
+                unsigned.with_swapped_public_key().validate(),
+                Err(VerifyError::PublicKeyMismatch.into())
+            );
+
+            let signed = attestation::<RequireSignedAttestation>(client);
+            assert_eq!(
+                signed.with_swapped_public_key().validate(),
+                Err(VerifyError::PublicKeyMismatch.into())
+            );
+        })
+    }
+
+    #[test]
+    fn self_attestation_is_rejected_when_trusted_attestation_is_required() {
+        new_test_ext(1).execute_with(|client| {
+            let attestation = attestation::<RequireX5cAttestation>(client);
+            assert!(attestation.attestation_statement.is_self_attested());
+            assert_eq!(
+                attestation.validate(),
+                Err(VerifyError::SelfAttestationNotAllowed.into())
+            );
+        })
+    }
+
+    #[test]
+    fn blocked_authenticator_models_are_rejected() {
+        new_test_ext(2).execute_with(|client| {
+            let blocked = attestation::<
+                WithDevicePolicy<AllowNoneAttestation, CompromisedTestAuthenticator>,
+            >(client);
+            assert!(!blocked.is_valid());
+            assert_eq!(
+                blocked.validate(),
+                Err(VerifyError::AuthenticatorBlocked.into())
+            );
+
+            let allowed = attestation::<
+                WithDevicePolicy<AllowNoneAttestation, OtherCompromisedModel>,
+            >(client);
+            assert!(allowed.is_valid());
+        })
+    }
+
+    #[test]
+    fn credentials_of_algorithms_not_offered_are_rejected() {
+        use coset::iana::Algorithm;
+
+        new_test_ext(1).execute_with(|client| {
+            let attestation = attestation::<RequireSignedAttestation>(client);
+            assert_eq!(attestation.check_algorithm(&[Algorithm::ES256]), Ok(()));
+            assert_eq!(
+                attestation.check_algorithm(&[Algorithm::EdDSA, Algorithm::ES256]),
+                Ok(())
+            );
+
+            // The authenticator registered an ES256 credential, which the relying party didn't
+            // offer.
+            assert_eq!(
+                attestation.check_algorithm(&[Algorithm::EdDSA, Algorithm::ES384]),
+                Err(VerifyError::AlgorithmNotOffered.into())
+            );
+        })
+    }
+
+    #[test]
+    fn x5c_statements_are_not_self_attested() {
+        new_test_ext(1).execute_with(|client| {
+            let mut attestation = attestation::<RequireX5cAttestation>(client);
+            let AttestationStatement::Packed { alg, sig } = attestation.attestation_statement
+            else {
+                panic!("The test client returns `packed` self-attestation");
+            };
+            attestation.attestation_statement = AttestationStatement::PackedX5c {
+                alg,
+                sig,
+                x5c: vec![b"not a certificate".to_vec()],
+            };
+
+            assert!(!attestation.attestation_statement.is_self_attested());
+            // Past the policy, down to the (bogus) certificate.
+            assert_eq!(
+                attestation.validate(),
+                Err(VerifyError::ParseAttestationCertificate.into())
+            );
+        })
+    }
+
+    #[test]
+    fn x5c_statements_are_only_accepted_for_the_attested_public_key() {
+        new_test_ext(1).execute_with(|client| {
+            let attestation = attestation::<RequireX5cAttestation>(client).with_x5c_statement();
+            assert_eq!(attestation.validate(), Ok(()));
+
+            // The certificate vouches for the authenticator data, not for the key stored apart
+            // from it.
+            assert_eq!(
+                attestation.with_swapped_public_key().validate(),
+                Err(VerifyError::PublicKeyMismatch.into())
+            );
+        })
+    }
+
+    /// Reports every certificate revoked.
+    #[derive(Default)]
+    struct RevokingEverything;
+
+    impl RevocationChecker for RevokingEverything {
+        fn check_ocsp(&self, _: &[u8]) -> Result<(), RevocationError> {
+            Err(RevocationError::Revoked)
+        }
+    }
+
+    #[test]
+    fn attestations_with_revoked_certificates_are_rejected() {
+        new_test_ext(1).execute_with(|client| {
+            let revoked = attestation::<
+                WithRevocationChecker<RequireX5cAttestation, RevokingEverything>,
+            >(client)
+            .with_x5c_statement();
+            assert!(!revoked.is_valid());
+            assert_eq!(
+                revoked.validate(),
+                Err(VerifyError::AttestationCertificateRevoked.into())
+            );
+            // What registering it fails with.
+            assert_eq!(
+                revoked
+                    .validate()
+                    .map_err(WebAuthnError::into_pass_error::<Test, ()>),
+                Err(pallet_pass::Error::<Test>::DeviceAttestationInvalid)
+            );
+
+            // Self-attestation has no certificate to revoke.
+            let self_attested = attestation::<
+                WithRevocationChecker<RequireSignedAttestation, RevokingEverything>,
+            >(client);
+            assert!(self_attested.is_valid());
+        })
+    }
+}
+
+mod device_policy {
+    use crate::{AuthenticatorStatus, DevicePolicyEngine, StatusReport};
+
+    const YUBIKEY_5_NFC: [u8; 16] = [
+        0x2f, 0xc0, 0x57, 0x9f, 0x81, 0x13, 0x47, 0xea, 0xb1, 0x16, 0xbb, 0x5a, 0x8d, 0xb9, 0x20,
+        0x2a,
+    ];
+
+    #[test]
+    fn only_compromised_keys_block_a_model() {
+        let report = |aaguid, status| StatusReport { aaguid, status };
+        let engine = DevicePolicyEngine::from_mds_status_reports(&[
+            report([1; 16], AuthenticatorStatus::AttestationKeyCompromise),
+            report([2; 16], AuthenticatorStatus::UserKeyPhysicalCompromise),
+            report([3; 16], AuthenticatorStatus::UserKeyRemoteCompromise),
+            report([4; 16], AuthenticatorStatus::Revoked),
+            report(YUBIKEY_5_NFC, AuthenticatorStatus::FidoCertifiedL1),
+        ]);
+
+        assert!(!engine.is_allowed([1; 16]));
+        assert!(!engine.is_allowed([2; 16]));
+        for aaguid in [[3; 16], [4; 16], YUBIKEY_5_NFC, [0; 16]] {
+            assert!(engine.is_allowed(aaguid));
+        }
+    }
+
+    #[test]
+    fn models_can_be_blocked_by_hand() {
+        let engine = DevicePolicyEngine::default();
+        assert!(engine.is_allowed(YUBIKEY_5_NFC));
+        assert!(!engine.block(YUBIKEY_5_NFC).is_allowed(YUBIKEY_5_NFC));
+    }
+}
+
+mod cbor {
+    use ciborium::{de::from_reader, value::Value};
+
+    use super::*;
+    use crate::{cbor::CborError, Assertion, Attestation, AttestationStatement, Transports};
+
+    #[test]
+    fn attestation_and_assertion_round_trip() {
+        new_test_ext(2).execute_with(|client| {
+            let (credential_id, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let assertion =
+                client.assertion(credential_id, System::block_number(), AuthorityId::get());
+
+            assert_eq!(
+                Attestation::<_>::from_cbor(&attestation.to_cbor()),
+                Ok(attestation.clone())
+            );
+            assert_eq!(Assertion::from_cbor(&assertion.to_cbor()), Ok(assertion));
+        })
+    }
+
+    #[test]
+    fn basic_attestation_statements_round_trip() {
+        new_test_ext(1).execute_with(|client| {
+            let (_, mut attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            attestation.attestation_statement = AttestationStatement::PackedX5c {
+                alg: -7,
+                sig: vec![0x30; 8],
+                x5c: vec![vec![0x30, 0x00], vec![0x30, 0x01, 0x00]],
+            };
+
+            assert_eq!(
+                Attestation::<_>::from_cbor(&attestation.to_cbor()),
+                Ok(attestation)
+            );
+        })
+    }
+
+    #[test]
+    fn byte_fields_are_encoded_as_byte_strings() {
+        new_test_ext(1).execute_with(|client| {
+            let (_, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+
+            let map = from_reader::<Value, _>(attestation.to_cbor().as_slice())
+                .expect("`to_cbor` produces valid CBOR")
+                .into_map()
+                .expect("`to_cbor` produces a map");
+            assert_eq!(
+                map[3],
+                (
+                    Value::from(3u8),
+                    Value::Bytes(attestation.authenticator_data.clone())
+                )
+            );
+            assert_eq!(
+                map[5],
+                (
+                    Value::from(5u8),
+                    Value::Bytes(attestation.public_key.as_der().to_vec())
+                )
+            );
+        })
+    }
+
+    #[test]
+    fn assertions_without_credential_id_decode() {
+        new_test_ext(2).execute_with(|client| {
+            let (credential_id, _) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let mut assertion =
+                client.assertion(credential_id, System::block_number(), AuthorityId::get());
+
+            let mut map = from_reader::<Value, _>(assertion.to_cbor().as_slice())
+                .expect("`to_cbor` produces valid CBOR")
+                .into_map()
+                .expect("`to_cbor` produces a map");
+            map.pop();
+            let mut legacy = Vec::new();
+            ciborium::ser::into_writer(&Value::Map(map), &mut legacy)
+                .expect("Writing to a vector doesn't fail; qed");
+
+            assertion.credential_id = Vec::new();
+            assert_eq!(Assertion::from_cbor(&legacy), Ok(assertion));
+        })
+    }
+
+    #[test]
+    fn attestations_without_transports_decode() {
+        new_test_ext(1).execute_with(|client| {
+            let (_, mut attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            attestation.transports = Transports::USB | Transports::NFC;
+
+            let mut map = from_reader::<Value, _>(attestation.to_cbor().as_slice())
+                .expect("`to_cbor` produces valid CBOR")
+                .into_map()
+                .expect("`to_cbor` produces a map");
+            assert_eq!(map[8], (Value::from(8u8), Value::from(0b0110u8)));
+            // Older encodings had neither the user id nor the transports.
+            map.truncate(8);
+            let mut legacy = Vec::new();
+            ciborium::ser::into_writer(&Value::Map(map), &mut legacy)
+                .expect("Writing to a vector doesn't fail; qed");
+
+            attestation.transports = Transports::default();
+            attestation.meta.user_id = None;
+            assert_eq!(Attestation::<_>::from_cbor(&legacy), Ok(attestation));
+        })
+    }
+
+    #[test]
+    fn attestations_without_user_id_decode() {
+        new_test_ext(1).execute_with(|client| {
+            let (_, mut attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+
+            let mut map = from_reader::<Value, _>(attestation.to_cbor().as_slice())
+                .expect("`to_cbor` produces valid CBOR")
+                .into_map()
+                .expect("`to_cbor` produces a map");
+            assert_eq!(map[9], (Value::from(9u8), Value::Bytes(USER.to_vec())));
+            map.pop();
+            let mut legacy = Vec::new();
+            ciborium::ser::into_writer(&Value::Map(map), &mut legacy)
+                .expect("Writing to a vector doesn't fail; qed");
+
+            attestation.meta.user_id = None;
+            assert_eq!(Attestation::<_>::from_cbor(&legacy), Ok(attestation));
+        })
+    }
+
+    #[test]
+    fn trailing_data_is_rejected() {
+        new_test_ext(1).execute_with(|client| {
+            let (_, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+
+            let mut cbor = attestation.to_cbor();
+            cbor.push(0x00);
+            assert_eq!(
+                Attestation::<BlockNumberFor<Test>>::from_cbor(&cbor),
+                Err(CborError::Malformed)
+            );
+        })
+    }
+}
+
+mod attestation_object {
+    use ciborium::value::Value;
+    use passkey_types::webauthn::CreatedPublicKeyCredential;
+    use sp_io::hashing::blake2_256;
+    use verifier::VerifyError;
+
+    use super::*;
+    use crate::{
+        attestation_object::DeviceAttestationFromCbor, AllowNoneAttestation, Attestation,
+        AttestationMeta, RequireSignedAttestation, ResponseError,
+    };
+
+    fn create(client: &mut WebAuthnClient) -> (CreatedPublicKeyCredential, AttestationMeta<u64>) {
+        let context = System::block_number();
+        let created = client
+            .create_credential_sync(USER, BlockChallenger::generate(&context).as_slice(), &[])
+            .expect("Failed creating credential");
+        let meta = AttestationMeta::new(AuthorityId::get(), blake2_256(&created.raw_id), context);
+        (created, meta)
+    }
+
+    fn none_attestation_object(authenticator_data: &[u8]) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        ciborium::ser::into_writer(
+            &Value::Map(vec![
+                (Value::Text("fmt".into()), Value::Text("none".into())),
+                (Value::Text("attStmt".into()), Value::Map(vec![])),
+                (
+                    Value::Text("authData".into()),
+                    Value::Bytes(authenticator_data.to_vec()),
+                ),
+            ]),
+            &mut encoded,
+        )
+        .expect("Writing to a vector doesn't fail; qed");
+        encoded
+    }
+
+    #[test]
+    fn packed_attestation_objects_are_verified() {
+        new_test_ext(1).execute_with(|client| {
+            let (created, meta) = create(client);
+
+            let attestation = Attestation::<_>::try_from_cbor(
+                &created.response.attestation_object,
+                &created.response.client_data_json,
+                meta,
+            )
+            .expect("The authenticator attests with a valid packed statement");
+            let expected = Attestation::<_>::try_from((created, meta))
+                .expect("The authenticator returns a valid P-256 public key; qed");
+            assert_eq!(attestation.authenticator_data, expected.authenticator_data);
+            assert_eq!(attestation.client_data, expected.client_data);
+            assert_eq!(attestation.public_key, expected.public_key);
+            assert_eq!(
+                attestation.attestation_statement,
+                expected.attestation_statement
+            );
+            assert_ok!(attestation.validate());
+        })
+    }
+
+    #[test]
+    fn statements_over_other_client_data_are_rejected() {
+        new_test_ext(1).execute_with(|client| {
+            let (created, meta) = create(client);
+
+            assert_eq!(
+                Attestation::<_>::try_from_cbor(
+                    &created.response.attestation_object,
+                    br#"{"type":"webauthn.create","challenge":"","origin":"https://pass-web.pass.int"}"#,
+                    meta,
+                ),
+                Err(ResponseError::InvalidStatement(VerifyError::VerifySignature))
+            );
+        })
+    }
+
+    #[test]
+    fn none_attestation_follows_the_policy() {
+        new_test_ext(1).execute_with(|client| {
+            let (created, meta) = create(client);
+            let none = none_attestation_object(&created.response.authenticator_data);
+
+            assert!(Attestation::<_, AllowNoneAttestation>::try_from_cbor(
+                &none,
+                &created.response.client_data_json,
+                meta
+            )
+            .is_ok());
+            assert_eq!(
+                Attestation::<_, RequireSignedAttestation>::try_from_cbor(
+                    &none,
+                    &created.response.client_data_json,
+                    meta
+                ),
+                Err(ResponseError::InvalidStatement(
+                    VerifyError::UnsupportedAttestationFormat
+                ))
+            );
+        })
+    }
+
+    #[test]
+    fn malformed_attestation_objects_are_rejected() {
+        new_test_ext(1).execute_with(|client| {
+            let (created, meta) = create(client);
+            let client_data_json = &created.response.client_data_json;
+
+            assert_eq!(
+                Attestation::<_>::try_from_cbor(b"not cbor", client_data_json, meta),
+                Err(ResponseError::InvalidAttestationObject)
+            );
+            assert_eq!(
+                Attestation::<_>::try_from_cbor(
+                    &none_attestation_object(&[0; 37]),
+                    client_data_json,
+                    meta
+                ),
+                Err(ResponseError::InvalidAuthenticatorData)
+            );
+        })
+    }
+}
+
+mod credential_id {
+    use super::*;
+
+    #[test]
+    fn credential_id_falls_back_to_the_authenticator_data() {
+        new_test_ext(2).execute_with(|client| {
+            let (credential_id, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let mut assertion = client.assertion(
+                credential_id.clone(),
+                System::block_number(),
+                AuthorityId::get(),
+            );
+            assert_eq!(assertion.try_credential_id(), Some(&credential_id[..]));
+
+            // Assertions don't carry attested credential data.
+            assertion.credential_id = Vec::new();
+            assert_eq!(assertion.try_credential_id(), None);
+
+            assertion.authenticator_data = attestation.authenticator_data;
+            assert_eq!(assertion.try_credential_id(), Some(&credential_id[..]));
+        })
+    }
+
+    #[test]
+    fn assertions_of_the_stored_credential_verify() {
+        new_test_ext(2).execute_with(|client| {
+            let (credential_id, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let credential = credential_of(attestation);
+            let assertion =
+                client.assertion(credential_id, System::block_number(), AuthorityId::get());
+
+            assert!(credential.verify_assertion(&assertion).is_ok());
+        })
+    }
+
+    #[test]
+    fn assertions_of_another_credential_are_rejected() {
+        new_test_ext(2).execute_with(|client| {
+            let (_, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let (other_credential_id, _) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let credential = credential_of(attestation);
+            let assertion = client.assertion(
+                other_credential_id,
+                System::block_number(),
+                AuthorityId::get(),
+            );
+
+            assert_eq!(
+                credential.verify_assertion(&assertion),
+                Err(WebAuthnError::CredentialIdMismatch)
+            );
+        })
+    }
+}
+
+mod error {
+    use verifier::VerifyError;
+
+    use super::*;
+    use crate::{ClientDataError, WebAuthnError};
+
+    const VERIFY_ERRORS: [VerifyError; 25] = [
+        VerifyError::AaguidMismatch,
+        VerifyError::AlgorithmMismatch,
+        VerifyError::AlgorithmNotOffered,
+        VerifyError::AttestationCertificateRevoked,
+        VerifyError::AuthenticatorBlocked,
+        VerifyError::BackupStateInconsistency,
+        VerifyError::ChallengeMismatch,
+        VerifyError::ClientDataMalformed,
+        VerifyError::ClientDataModified,
+        VerifyError::CredentialAlreadyRegistered,
+        VerifyError::ExtractPublicKey,
+        VerifyError::InputTooLarge,
+        VerifyError::InvalidAttestationCertificate,
+        VerifyError::MissingAlgorithm,
+        VerifyError::ParseAttestationCertificate,
+        VerifyError::ParseAttestationObject,
+        VerifyError::ParseAuthenticatorData,
+        VerifyError::ParseClientData,
+        VerifyError::ParseCtap2Response,
+        VerifyError::ParseSignature,
+        VerifyError::PublicKeyMismatch,
+        VerifyError::SelfAttestationNotAllowed,
+        VerifyError::UnsupportedAlgorithm,
+        VerifyError::UnsupportedAttestationFormat,
+        VerifyError::VerifySignature,
+    ];
+
+    // Stops compiling when a variant is added, so `VERIFY_ERRORS` is kept complete.
+    #[allow(dead_code)]
+    fn listed(e: VerifyError) {
+        match e {
+            VerifyError::AaguidMismatch
+            | VerifyError::AlgorithmMismatch
+            | VerifyError::AlgorithmNotOffered
+            | VerifyError::AttestationCertificateRevoked
+            | VerifyError::AuthenticatorBlocked
+            | VerifyError::BackupStateInconsistency
+            | VerifyError::ChallengeMismatch
+            | VerifyError::ClientDataMalformed
+            | VerifyError::ClientDataModified
+            | VerifyError::CredentialAlreadyRegistered
+            | VerifyError::ExtractPublicKey
+            | VerifyError::InputTooLarge
+            | VerifyError::InvalidAttestationCertificate
+            | VerifyError::MissingAlgorithm
+            | VerifyError::ParseAttestationCertificate
+            | VerifyError::ParseAttestationObject
+            | VerifyError::ParseAuthenticatorData
+            | VerifyError::ParseClientData
+            | VerifyError::ParseCtap2Response
+            | VerifyError::ParseSignature
+            | VerifyError::PublicKeyMismatch
+            | VerifyError::SelfAttestationNotAllowed
+            | VerifyError::UnsupportedAlgorithm
+            | VerifyError::UnsupportedAttestationFormat
+            | VerifyError::VerifySignature => {}
+        }
+    }
+
+    #[test]
+    fn every_verify_error_maps_to_verify() {
+        for e in VERIFY_ERRORS {
+            assert_eq!(WebAuthnError::from(e), WebAuthnError::Verify(e));
+        }
+    }
+
+    #[test]
+    fn webauthn_error_is_a_std_error() {
+        fn source_of(e: &dyn std::error::Error) -> Option<&dyn std::error::Error> {
+            e.source()
+        }
+        assert!(source_of(&WebAuthnError::from(ClientDataError::MissingChallenge)).is_none());
+    }
+
+    #[test]
+    fn error_encodings_are_stable() {
+        use crate::StoreError;
+        use codec::{Decode, Encode};
+
+        // Part of the ABI events and RPC responses expose: never change an encoding here.
+        let encodings: [(WebAuthnError, &[u8]); 14] = [
+            (VerifyError::ParseSignature.into(), &[0, 11]),
+            (ClientDataError::MissingChallenge.into(), &[1, 0]),
+            (ClientDataError::TypeMismatch.into(), &[1, 1]),
+            (WebAuthnError::InvalidChallenge, &[2]),
+            (WebAuthnError::ChallengeExpired, &[3]),
+            (WebAuthnError::RpIdMismatch, &[4]),
+            (
+                WebAuthnError::AuthorityMismatch {
+                    expected: [0; 32],
+                    actual_origin: None,
+                },
+                &[5],
+            ),
+            (WebAuthnError::DeviceMismatch, &[6]),
+            (WebAuthnError::Replayed, &[7]),
+            (StoreError::NotFound.into(), &[8, 0]),
+            (WebAuthnError::UserVerificationRequired, &[9]),
+            (WebAuthnError::UserNotPresent, &[10]),
+            (WebAuthnError::UserMismatch, &[11]),
+            (WebAuthnError::CredentialIdMismatch, &[12]),
+        ];
+        for (e, encoding) in encodings {
+            assert_eq!(e.encode(), encoding);
+            assert_eq!(WebAuthnError::decode(&mut &encoding[..]), Ok(e));
+        }
+    }
+
+    #[test]
+    fn errors_map_to_pass_errors() {
+        use crate::pass_error;
+        type PassError = pallet_pass::Error<Test>;
+
+        assert_eq!(
+            pass_error::<Test, ()>(VerifyError::ParseAuthenticatorData),
+            PassError::DeviceAttestationInvalid
+        );
+        assert_eq!(
+            pass_error::<Test, ()>(VerifyError::VerifySignature),
+            PassError::CredentialInvalid
+        );
+        assert_eq!(
+            WebAuthnError::from(ClientDataError::MissingChallenge).into_pass_error::<Test, ()>(),
+            PassError::DeviceAttestationInvalid
+        );
+        assert_eq!(
+            WebAuthnError::InvalidChallenge.into_pass_error::<Test, ()>(),
+            PassError::CredentialInvalid
+        );
+    }
+
+    #[test]
+    fn failures_are_reported_with_their_cause() {
+        new_test_ext(2).execute_with(|client| {
+            let (credential_id, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let mut assertion =
+                client.assertion(credential_id, System::block_number(), AuthorityId::get());
+            let credential = credential_of(attestation);
+
+            assert!(credential.verify_assertion(&assertion).is_ok());
+
+            assertion.signature = [assertion.signature, b"Whoops".to_vec()].concat();
+            assert_eq!(
+                credential.verify_assertion(&assertion),
+                Err(WebAuthnError::Verify(VerifyError::ParseSignature))
+            );
+
+            assertion.client_data = String::from_utf8(assertion.client_data)
+                .map(|client_data| {
+                    client_data
+                        .replace("\"challenge\"", "\"nonce\"")
+                        .into_bytes()
+                })
+                .expect("`client_data` is a buffer representation of a utf-8 encoded json");
+            assert_eq!(
+                assertion.validate(),
+                Err(WebAuthnError::InvalidClientData(
+                    ClientDataError::MissingChallenge
+                ))
+            );
+        })
+    }
+}
+
+mod rotation {
+    use sp_io::hashing::blake2_256;
+    use traits_authn::DeviceChallengeResponse;
+
+    use super::*;
+    use crate::{
+        Attestation, AttestationMeta, AuthenticatorAttachment, Credential, MergeError, Transports,
+        WebAuthnError,
+    };
+
+    fn rotation_attestation(
+        client: &mut WebAuthnClient,
+        user_id: HashedUserId,
+        device_id: traits_authn::DeviceId,
+    ) -> (Vec<u8>, Attestation<BlockNumberFor<Test>>) {
+        let context = System::block_number();
+        let created = client
+            .create_credential_sync(user_id, BlockChallenger::generate(&context).as_slice(), &[])
+            .expect("Failed creating credential");
+        let credential_id = created.raw_id.to_vec();
+        let meta =
+            AttestationMeta::new(AuthorityId::get(), device_id, context).with_user_id(user_id);
+
+        (
+            credential_id,
+            Attestation::try_from((created, meta))
+                .expect("The authenticator returns a valid P-256 public key; qed"),
+        )
+    }
+
+    #[test]
+    fn rotated_credential_verifies_only_the_new_key() {
+        new_test_ext(4).execute_with(|client| {
+            let (old_credential_id, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let device_id = *attestation.device_id();
+            let credential = credential_of(attestation);
+            let old_assertion = client.assertion(
+                old_credential_id,
+                System::block_number(),
+                AuthorityId::get(),
+            );
+            assert!(credential.verify_assertion(&old_assertion).is_ok());
+
+            let (new_credential_id, rotation) = rotation_attestation(client, USER, device_id);
+            let rotated = credential
+                .rotate::<BlockChallenger, _>(rotation, USER, AuthorityId::get())
+                .expect("The rotation attestation is valid and for the same device");
+            assert_eq!(rotated.as_ref(), &device_id);
+
+            let new_assertion = client.assertion(
+                new_credential_id,
+                System::block_number(),
+                AuthorityId::get(),
+            );
+            assert!(rotated.verify_assertion(&new_assertion).is_ok());
+            assert_eq!(
+                rotated.verify_assertion(&old_assertion),
+                Err(WebAuthnError::CredentialIdMismatch)
+            );
+        })
+    }
+
+    #[test]
+    fn rotation_requires_the_same_device_and_authority() {
+        new_test_ext(3).execute_with(|client| {
+            let (_, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let device_id = *attestation.device_id();
+            let credential = credential_of(attestation);
+
+            let (_, other_device) = rotation_attestation(client, USER, blake2_256(b"other"));
+            assert!(matches!(
+                credential.rotate::<BlockChallenger, _>(other_device, USER, AuthorityId::get()),
+                Err(WebAuthnError::DeviceMismatch)
+            ));
+
+            let (_, rotation) = rotation_attestation(client, USER, device_id);
+            assert!(matches!(
+                credential.rotate::<BlockChallenger, _>(rotation, USER, s("other_authority")),
+                Err(WebAuthnError::AuthorityMismatch { .. })
+            ));
+        })
+    }
+
+    #[test]
+    fn rotation_requires_the_user_of_the_device() {
+        new_test_ext(3).execute_with(|client| {
+            let (_, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let device_id = *attestation.device_id();
+            let credential = credential_of(attestation);
+
+            let (_, other_user) = rotation_attestation(client, s("other_user"), device_id);
+            assert_eq!(
+                credential
+                    .rotate::<BlockChallenger, _>(other_user, USER, AuthorityId::get())
+                    .err(),
+                Some(WebAuthnError::UserMismatch)
+            );
+
+            let (_, mut unknown_user) = rotation_attestation(client, USER, device_id);
+            unknown_user.meta.user_id = None;
+            assert_eq!(
+                credential
+                    .rotate::<BlockChallenger, _>(unknown_user, USER, AuthorityId::get())
+                    .err(),
+                Some(WebAuthnError::UserMismatch)
+            );
+        })
+    }
+
+    #[test]
+    fn authority_mismatches_name_the_origin() {
+        new_test_ext(2).execute_with(|client| {
+            let (_, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let device_id = *attestation.device_id();
+            let credential = credential_of(attestation);
+            let (_, rotation) = rotation_attestation(client, USER, device_id);
+
+            let Err(e) = credential.rotate::<BlockChallenger, _>(rotation, USER, [0xab; 32]) else {
+                panic!("The rotation is for another authority");
+            };
+            assert_eq!(
+                e,
+                WebAuthnError::AuthorityMismatch {
+                    expected: [0xab; 32],
+                    actual_origin: Some("https://pass-web.pass.int".into()),
+                }
+            );
+            let message = e.to_string();
+            assert!(message.contains(&"ab".repeat(32)));
+            assert!(message.contains("https://pass-web.pass.int"));
+        })
+    }
+
+    #[test]
+    fn merged_credentials_take_the_new_key() {
+        new_test_ext(3).execute_with(|client| {
+            let (_, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let device_id = *attestation.device_id();
+            let old = Credential {
+                sign_count: 7.into(),
+                authenticator_attachment: AuthenticatorAttachment::CrossPlatform,
+                transports: Transports::USB,
+                ..credential_of(attestation)
+            }
+            .with_uv_required(true);
+
+            let (new_credential_id, rotation) = rotation_attestation(client, USER, device_id);
+            let new = credential_of(rotation);
+            let new_key = *new.public_key();
+            assert_eq!(new.sign_count(), 0.into());
+
+            let merged = Credential::merge(old, new).expect("Both are for the same device");
+            assert_eq!(merged.public_key(), &new_key);
+            assert_eq!(merged.sign_count(), 7.into());
+            assert_eq!(
+                merged.authenticator_attachment(),
+                AuthenticatorAttachment::CrossPlatform
+            );
+            assert_eq!(merged.transports(), Transports::USB);
+            assert!(merged.uv_required());
+
+            let assertion = client.assertion(
+                new_credential_id,
+                System::block_number(),
+                AuthorityId::get(),
+            );
+            assert!(merged.verify_assertion(&assertion).is_ok());
+        })
+    }
+
+    #[test]
+    fn merged_credentials_keep_a_running_counter() {
+        new_test_ext(2).execute_with(|client| {
+            let (_, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let device_id = *attestation.device_id();
+            let old = Credential {
+                sign_count: 7.into(),
+                ..credential_of(attestation)
+            };
+            let (_, rotation) = rotation_attestation(client, USER, device_id);
+            let new = Credential {
+                sign_count: 3.into(),
+                ..credential_of(rotation)
+            };
+
+            assert_eq!(
+                Credential::merge(old, new).map(|c| c.sign_count()),
+                Ok(3.into())
+            );
+        })
+    }
+
+    #[test]
+    fn credentials_are_the_same_whatever_their_counter() {
+        new_test_ext(2).execute_with(|client| {
+            let (_, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let device_id = *attestation.device_id();
+            let credential = credential_of(attestation.clone());
+            let used = Credential {
+                sign_count: 42.into(),
+                ..credential_of(attestation.clone())
+            }
+            .with_uv_required(true);
+            assert!(credential.same_credential(&used));
+            assert!(used.same_credential(&credential));
+
+            let other_device = Credential {
+                device_id: blake2_256(b"other"),
+                ..credential_of(attestation)
+            };
+            assert!(!credential.same_credential(&other_device));
+
+            let (_, rotation) = rotation_attestation(client, USER, device_id);
+            let rotated = credential_of(rotation);
+            assert!(!credential.same_credential(&rotated));
+        })
+    }
+
+    #[test]
+    fn merging_credentials_of_other_devices_fails() {
+        new_test_ext(2).execute_with(|client| {
+            let (_, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let (_, other_device) = rotation_attestation(client, USER, blake2_256(b"other"));
+
+            assert_eq!(
+                Credential::merge(credential_of(attestation), credential_of(other_device)).err(),
+                Some(MergeError::DeviceIdMismatch)
+            );
+        })
+    }
+
+    #[test]
+    fn authority_mismatches_without_an_origin_say_so() {
+        let e = WebAuthnError::AuthorityMismatch {
+            expected: [0; 32],
+            actual_origin: None,
+        };
+        assert!(e.to_string().ends_with("an unknown origin"));
+    }
+}
+
+mod roundtrip {
+    use frame_support::assert_err;
+
+    use super::*;
+
+    #[test]
+    fn roundtrip_works() {
+        new_test_ext(2).execute_with(|client| {
+            assert_webauthn_roundtrip!(client, USER, System::block_number(), AuthorityId::get());
+        })
+    }
+
+    #[test]
+    fn roundtrip_works_for_several_users() {
+        new_test_ext(4).execute_with(|client| {
+            assert_webauthn_roundtrip!(client, USER, System::block_number(), AuthorityId::get());
+            assert_webauthn_roundtrip!(
+                client,
+                s("another_user"),
+                System::block_number(),
+                AuthorityId::get()
+            );
+        })
+    }
+
+    #[test]
+    fn tampered_signature_fails() {
+        new_test_ext(2).execute_with(|client| {
+            assert_err!(
+                assert_webauthn_roundtrip!(
+                    client,
+                    USER,
+                    System::block_number(),
+                    AuthorityId::get(),
+                    tamper = |assertion| assertion.signature.push(0)
+                ),
+                pallet_pass::Error::<Test>::CredentialInvalid
+            );
+        })
+    }
+
+    #[test]
+    fn tampered_authenticator_data_fails() {
+        new_test_ext(2).execute_with(|client| {
+            assert_err!(
+                assert_webauthn_roundtrip!(
+                    client,
+                    USER,
+                    System::block_number(),
+                    AuthorityId::get(),
+                    tamper = |assertion| assertion.authenticator_data[0] ^= 0x01
+                ),
+                pallet_pass::Error::<Test>::CredentialInvalid
+            );
+        })
+    }
+}
+
+mod replay {
+    use frame_support::{assert_err, storage_alias, Identity, Twox64Concat};
+    use traits_authn::DeviceChallengeResponse;
+
+    use super::*;
+    use crate::{
+        replay::{ReplayGuard, ReplayNonce},
+        WebAuthnError,
+    };
+
+    #[storage_alias]
+    type UsedNonces =
+        StorageDoubleMap<PassWebAuthnReplay, Twox64Concat, u64, Identity, ReplayNonce, ()>;
+
+    struct StorageReplayGuard;
+
+    impl ReplayGuard<BlockNumberFor<Test>> for StorageReplayGuard {
+        fn was_used(context: &BlockNumberFor<Test>, nonce: &ReplayNonce) -> bool {
+            UsedNonces::contains_key(context, nonce)
+        }
+
+        fn mark_used(context: &BlockNumberFor<Test>, nonce: ReplayNonce) {
+            UsedNonces::insert(context, nonce, ());
+        }
+
+        fn prune(context: &BlockNumberFor<Test>) {
+            let _ = UsedNonces::clear_prefix(context, u32::MAX, None);
+        }
+    }
+
+    const WINDOW: BlockNumberFor<Test> = 10;
+
+    #[test]
+    fn replayed_assertion_is_rejected_until_pruned() {
+        new_test_ext(2).execute_with(|client| {
+            let context = System::block_number();
+            let (credential_id, attestation) =
+                client.attestation(USER, context, AuthorityId::get());
+            let device_id = *attestation.device_id();
+            assert_ok!(Pass::register(RuntimeOrigin::root(), USER, attestation));
+
+            let assertion = client.assertion(credential_id, context, AuthorityId::get());
+            assert_ok!(Pass::authenticate(
+                RuntimeOrigin::signed(1),
+                device_id,
+                assertion.clone(),
+                None
+            ));
+            assert_ok!(assertion.mark_used::<StorageReplayGuard>(&device_id));
+
+            // The very same assertion, submitted again in the next block.
+            System::set_block_number(context + 1);
+            assert!(assertion.was_used::<StorageReplayGuard>(&device_id));
+            assert_err!(
+                assertion.mark_used::<StorageReplayGuard>(&device_id),
+                WebAuthnError::Replayed
+            );
+
+            // Once the window for `context` passes, its nonces can be forgotten.
+            System::set_block_number(context + WINDOW);
+            StorageReplayGuard::prune(&context);
+            assert!(!assertion.was_used::<StorageReplayGuard>(&device_id));
+        })
+    }
+
+    #[test]
+    fn nonces_differ_per_device() {
+        new_test_ext(2).execute_with(|client| {
+            let (credential_id, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let device_id = *attestation.device_id();
+            let assertion =
+                client.assertion(credential_id, System::block_number(), AuthorityId::get());
+
+            assert_ne!(
+                assertion.replay_nonce(&device_id),
+                assertion.replay_nonce(&[0; 32])
+            );
+        })
+    }
+}
+
+mod sign_count {
+    use codec::{Decode, Encode, MaxEncodedLen};
+
+    use super::*;
+    use crate::{ReplayError, SignCount, WebAuthnError};
+
+    #[test]
+    fn advance_requires_a_strictly_greater_count() {
+        let stored = SignCount::from(5);
+
+        assert_eq!(stored.advance(6.into()), Ok(6.into()));
+        assert_eq!(stored.advance(u32::MAX.into()), Ok(u32::MAX.into()));
+        assert_eq!(stored.advance(5.into()), Err(ReplayError));
+        assert_eq!(stored.advance(4.into()), Err(ReplayError));
+    }
+
+    #[test]
+    fn advance_allows_authenticators_without_a_counter() {
+        assert_eq!(SignCount::default().advance(0.into()), Ok(0.into()));
+        assert_eq!(SignCount::from(5).advance(0.into()), Ok(5.into()));
+    }
+
+    #[test]
+    fn sign_count_encodes_as_u32() {
+        assert_eq!(SignCount::from(7).encode(), 7u32.encode());
+        assert_eq!(SignCount::max_encoded_len(), u32::max_encoded_len());
+        assert_eq!(u32::from(SignCount::from(7)), 7);
+    }
+
+    #[test]
+    fn recorded_credentials_reject_counters_that_dont_advance() {
+        new_test_ext(3).execute_with(|client| {
+            let context = System::block_number();
+            let (credential_id, attestation) =
+                client.attestation(USER, context, AuthorityId::get());
+            let stored = credential_of(attestation).encode();
+
+            let mut credential =
+                crate::Credential::decode(&mut stored.as_slice()).expect("Encoded above; qed");
+            let first = client.assertion_with_counter(
+                credential_id.clone(),
+                context,
+                AuthorityId::get(),
+                5,
+            );
+            let outcome = credential
+                .verify_assertion(&first)
+                .expect("The counter advances over the registration one");
+            credential.record(outcome);
+            assert_eq!(credential.sign_count(), 5.into());
+            let stored = credential.encode();
+
+            let credential =
+                crate::Credential::decode(&mut stored.as_slice()).expect("Encoded above; qed");
+            let cloned =
+                client.assertion_with_counter(credential_id, context, AuthorityId::get(), 5);
+            assert_eq!(
+                credential.verify_assertion(&cloned),
+                Err(WebAuthnError::from(ReplayError))
+            );
+        })
+    }
+
+    #[test]
+    fn assertions_carry_the_chosen_counter() {
+        new_test_ext(2).execute_with(|client| {
+            let (credential_id, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let assertion = client.assertion_with_counter(
+                credential_id,
+                System::block_number(),
+                AuthorityId::get(),
+                42,
+            );
+
+            let authenticator_data =
+                verifier::parse_authenticator_data(&assertion.authenticator_data)
+                    .expect("The authenticator data is well-formed");
+            assert_eq!(authenticator_data.sign_count, 42);
+            // Signed again over the new counter.
+            let credential = credential_of(attestation);
+            assert!(credential.verify_assertion(&assertion).is_ok());
+        })
+    }
+
+    #[test]
+    fn only_increasing_counters_are_accepted() {
+        new_test_ext(5).execute_with(|client| {
+            let context = System::block_number();
+            let (credential_id, attestation) =
+                client.attestation(USER, context, AuthorityId::get());
+            let credential = credential_of(attestation);
+
+            let assertion = client.assertion_with_counter(
+                credential_id.clone(),
+                context,
+                AuthorityId::get(),
+                5,
+            );
+            let outcome = credential
+                .verify_assertion(&assertion)
+                .expect("The counter increased");
+            assert_eq!(outcome.new_sign_count, 5.into());
+            let mut credential = credential;
+            credential.record(outcome);
+
+            for counter in [5, 4] {
+                let assertion = client.assertion_with_counter(
+                    credential_id.clone(),
+                    context,
+                    AuthorityId::get(),
+                    counter,
+                );
+                assert_eq!(
+                    credential.verify_assertion(&assertion).map(|_| ()),
+                    Err(WebAuthnError::Replayed)
+                );
+            }
+
+            let assertion =
+                client.assertion_with_counter(credential_id, context, AuthorityId::get(), 6);
+            assert!(credential.verify_assertion(&assertion).is_ok());
+        })
+    }
+}
+
+mod algorithm {
+    use coset::iana::Algorithm;
+    use verifier::VerifyError;
+
+    use super::*;
+    use crate::{
+        testing::Tamper, Attestation, AttestationMeta, AttestationStatement, Credential,
+        DEREncodedPublicKey, WebAuthnError,
+    };
+
+    #[test]
+    fn credentials_of_each_supported_algorithm_verify() {
+        new_test_ext(0).execute_with(|_| {
+            let context = System::block_number();
+            for alg in [Algorithm::ES256] {
+                let mut client = WebAuthnClient::new("https://pass-web.pass.int", 2);
+                let created = client
+                    .create_credential_with_algorithms(
+                        USER,
+                        BlockChallenger::generate(&context).as_slice(),
+                        &[alg],
+                    )
+                    .expect("The algorithm is supported");
+                assert_eq!(client.algorithm(), Some(alg as i64));
+
+                let credential_id = created.raw_id.to_vec();
+                let meta =
+                    AttestationMeta::new(AuthorityId::get(), blake2_256(&credential_id), context);
+                let attestation: Attestation<_> = (created, meta)
+                    .try_into()
+                    .expect("The public key is of the picked algorithm");
+                assert!(attestation.is_valid());
+
+                let credential = credential_of(attestation);
+                assert_eq!(credential.algorithm(), alg as i64);
+                let assertion = client.assertion(credential_id, context, AuthorityId::get());
+                assert!(credential.verify_assertion(&assertion).is_ok());
+            }
+        })
+    }
+
+    #[test]
+    fn authenticator_picks_an_offered_algorithm_it_supports() {
+        new_test_ext(1).execute_with(|client| {
+            let challenge = BlockChallenger::generate(&System::block_number());
+            client
+                .create_credential_with_algorithms(
+                    USER,
+                    challenge.as_slice(),
+                    &[Algorithm::EdDSA, Algorithm::ES256],
+                )
+                .expect("ES256 is supported");
+            assert_eq!(client.algorithm(), Some(Algorithm::ES256 as i64));
+        })
+    }
+
+    #[test]
+    fn credential_stores_the_attested_algorithm() {
+        new_test_ext(1).execute_with(|client| {
+            let (_, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let credential = credential_of(attestation);
+
+            assert_eq!(credential.algorithm(), coset::iana::Algorithm::ES256 as i64);
+        })
+    }
+
+    #[test]
+    fn attestations_that_cant_be_read_make_no_credential() {
+        new_test_ext(1).execute_with(|client| {
+            let (_, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+
+            assert_eq!(
+                Credential::try_from(attestation.with_truncated_authenticator_data()).err(),
+                Some(VerifyError::ParseAuthenticatorData.into())
+            );
+            // A valid header, but a point off the curve.
+            let off_curve = [&attestation.public_key.as_der()[..27], &[0xff; 64]].concat();
+            let off_curve = Attestation {
+                public_key: DEREncodedPublicKey::try_from(&off_curve[..])
+                    .expect("The header is the one of a P-256 key"),
+                ..attestation.clone()
+            };
+            assert_eq!(
+                Credential::try_from(off_curve).err(),
+                Some(VerifyError::ExtractPublicKey.into())
+            );
+            let eddsa = Attestation {
+                attestation_statement: AttestationStatement::Packed {
+                    alg: Algorithm::EdDSA as i64,
+                    sig: Vec::new(),
+                },
+                ..attestation
+            };
+            assert_eq!(
+                Credential::try_from(eddsa).err(),
+                Some(VerifyError::UnsupportedAlgorithm.into())
+            );
+        })
+    }
+
+    #[test]
+    fn devices_of_attestations_that_make_no_credential_verify_nothing() {
+        new_test_ext(2).execute_with(|client| {
+            let (credential_id, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let assertion =
+                client.assertion(credential_id, System::block_number(), AuthorityId::get());
+            let unreadable = attestation.with_truncated_authenticator_data();
+
+            let credential = Credential::unpacked_from(unreadable.clone());
+            assert_eq!(credential.as_ref(), &attestation.meta.device_id);
+            assert_eq!(
+                credential.verify_assertion(&assertion),
+                Err(WebAuthnError::Verify(VerifyError::UnsupportedAlgorithm))
+            );
+            // Rather than panicking.
+            let _: crate::Device<BlockChallenger, AuthorityId> = unreadable.into();
+        })
+    }
+
+    #[test]
+    fn assertion_signed_with_another_algorithm_is_rejected() {
+        new_test_ext(2).execute_with(|client| {
+            let (credential_id, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let credential = credential_of(attestation);
+            let mut assertion =
+                client.assertion(credential_id, System::block_number(), AuthorityId::get());
+
+            // A raw, 64 byte signature, as EdDSA produces them.
+            assertion.signature = [0x42; 64].to_vec();
+            assert_eq!(
+                credential.verify_assertion(&assertion),
+                Err(WebAuthnError::Verify(VerifyError::AlgorithmMismatch))
+            );
+        })
+    }
+
+    #[test]
+    fn credential_with_an_unsupported_algorithm_rejects_every_assertion() {
+        new_test_ext(2).execute_with(|client| {
+            let (credential_id, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let credential = Credential {
+                algorithm: coset::iana::Algorithm::EdDSA as i64,
+                ..credential_of(attestation)
+            };
+            let assertion =
+                client.assertion(credential_id, System::block_number(), AuthorityId::get());
+
+            assert_eq!(
+                credential.verify_assertion(&assertion),
+                Err(WebAuthnError::Verify(VerifyError::UnsupportedAlgorithm))
+            );
+        })
+    }
+}
+
+mod allocations {
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        cell::Cell,
+    };
+    use traits_authn::{DeviceChallengeResponse, UserChallengeResponse};
+
+    use super::*;
+
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    fn allocations_in<R>(f: impl FnOnce() -> R) -> usize {
+        let before = ALLOCATIONS.with(Cell::get);
+        core::hint::black_box(f());
+        ALLOCATIONS.with(Cell::get) - before
+    }
+
+    #[test]
+    fn extracting_the_challenge_does_not_allocate() {
+        new_test_ext(2).execute_with(|client| {
+            let (credential_id, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let assertion =
+                client.assertion(credential_id, System::block_number(), AuthorityId::get());
+
+            assert_eq!(allocations_in(|| attestation.used_challenge()), 0);
+            assert_eq!(allocations_in(|| assertion.used_challenge()), 0);
+            assert_eq!(allocations_in(|| assertion.is_valid()), 0);
+        })
+    }
+}
+
+mod backup_state {
+    use verifier::{parse_authenticator_data, AuthenticatorDataFlags, VerifyError};
+
+    use super::*;
+    use crate::{Credential, WebAuthnError};
+
+    #[test]
+    fn credential_stores_the_registration_flags() {
+        new_test_ext(1).execute_with(|client| {
+            let (_, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let flags = parse_authenticator_data(&attestation.authenticator_data)
+                .expect("The authenticator returns valid authenticator data")
+                .flags;
+
+            let credential = credential_of(attestation);
+            assert_eq!(credential.registration_flags(), flags);
+        })
+    }
+
+    #[test]
+    fn inconsistent_backup_state_is_rejected() {
+        new_test_ext(2).execute_with(|client| {
+            let (credential_id, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let assertion =
+                client.assertion(credential_id, System::block_number(), AuthorityId::get());
+
+            // Backed up, yet not backup eligible.
+            let credential = Credential {
+                registration_flags: AuthenticatorDataFlags::BACKED_UP,
+                ..credential_of(attestation)
+            };
+            assert_eq!(
+                credential.verify_assertion(&assertion),
+                Err(WebAuthnError::Verify(VerifyError::BackupStateInconsistency))
+            );
+        })
+    }
+}
+
+mod user_verification {
+    use passkey_types::webauthn::UserVerificationRequirement;
+    use verifier::AuthenticatorDataFlags;
+
+    use super::*;
+    use crate::{testing::UserVerification, WebAuthnError};
+
+    fn presence_only_client(times: usize) -> WebAuthnClient {
+        WebAuthnClient::new_with_user_verification(
+            "https://pass-web.pass.int",
+            times,
+            UserVerification::PresenceOnly,
+        )
+    }
+
+    #[test]
+    fn clients_verify_the_user_as_configured() {
+        new_test_ext(2).execute_with(|client| {
+            let context = System::block_number();
+            let (credential_id, attestation) =
+                client.attestation(USER, context, AuthorityId::get());
+            let assertion = client.assertion(credential_id, context, AuthorityId::get());
+            assert!(attestation
+                .flags()
+                .contains(AuthenticatorDataFlags::USER_VERIFIED));
+            assert!(assertion
+                .flags()
+                .contains(AuthenticatorDataFlags::USER_VERIFIED));
+
+            let mut client = presence_only_client(2);
+            let (credential_id, attestation) =
+                client.attestation(USER, context, AuthorityId::get());
+            let assertion = client.assertion(credential_id, context, AuthorityId::get());
+            for flags in [attestation.flags(), assertion.flags()] {
+                assert!(flags.contains(AuthenticatorDataFlags::USER_PRESENT));
+                assert!(!flags.contains(AuthenticatorDataFlags::USER_VERIFIED));
+            }
+        })
+    }
+
+    #[test]
+    fn uv_required_credential_rejects_up_only_assertions() {
+        new_test_ext(2).execute_with(|client| {
+            let context = System::block_number();
+            let (credential_id, attestation) =
+                client.attestation(USER, context, AuthorityId::get());
+            let assertion = client.assertion(credential_id, context, AuthorityId::get());
+            let credential = credential_of(attestation).with_uv_required(true);
+            assert!(credential.uv_required());
+            assert!(credential.verify_assertion(&assertion).is_ok());
+
+            let mut client = presence_only_client(2);
+            let (credential_id, attestation) =
+                client.attestation(USER, context, AuthorityId::get());
+            let assertion = client.assertion(credential_id, context, AuthorityId::get());
+            let credential = credential_of(attestation).with_uv_required(true);
+
+            assert_eq!(
+                credential.verify_assertion(&assertion),
+                Err(WebAuthnError::UserVerificationRequired)
+            );
+            assert!(credential
+                .with_uv_required(false)
+                .verify_assertion(&assertion)
+                .is_ok());
+        })
+    }
+
+    #[test]
+    fn presence_only_authenticators_authenticate() {
+        new_test_ext(0).execute_with(|_| {
+            let mut client = presence_only_client(2);
+            let (credential_id, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let device_id = *attestation.device_id();
+            assert_ok!(Pass::register(RuntimeOrigin::root(), USER, attestation));
+
+            let assertion =
+                client.assertion(credential_id, System::block_number(), AuthorityId::get());
+            assert_ok!(Pass::authenticate(
+                RuntimeOrigin::signed(1),
+                device_id,
+                assertion,
+                None
+            ));
+        })
+    }
+
+    #[test]
+    fn verified_users_show_through_discouraged_requests() {
+        new_test_ext(0).execute_with(|_| {
+            let mut client = WebAuthnClient::new("https://pass-web.pass.int", 2)
+                .requesting_user_verification(UserVerificationRequirement::Discouraged);
+            let context = System::block_number();
+            let (credential_id, attestation) =
+                client.attestation(USER, context, AuthorityId::get());
+            let assertion = client.assertion(credential_id, context, AuthorityId::get());
+
+            // The relying party didn't ask for it, yet the authenticator reports it verified the
+            // user, which the credential accepts.
+            assert!(assertion
+                .flags()
+                .contains(AuthenticatorDataFlags::USER_VERIFIED));
+            let credential = credential_of(attestation).with_uv_required(true);
+            assert!(credential.verify_assertion(&assertion).is_ok());
+        })
+    }
+}
+
+mod credential_response {
+    use base64::prelude::BASE64_URL_SAFE_NO_PAD;
+    use serde_json::json;
+    use sp_io::hashing::blake2_256;
+    use traits_authn::DeviceChallengeResponse;
+
+    use super::*;
+    use crate::{
+        credential_response::{CredentialCreationResponse, CredentialRequestResponse},
+        Assertion, AssertionMeta, Attestation, AttestationMeta,
+    };
+
+    fn encode(bytes: &[u8]) -> String {
+        base64::encode_engine(bytes, &BASE64_URL_SAFE_NO_PAD)
+    }
+
+    #[test]
+    fn registration_and_authentication_work_with_browser_json_bodies() {
+        new_test_ext(2).execute_with(|client| {
+            let context = System::block_number();
+            let challenge = BlockChallenger::generate(&context);
+
+            let created = client
+                .create_credential_sync(USER, challenge.as_slice(), &[])
+                .expect("Failed creating credential");
+            let credential_id: Vec<u8> = created.raw_id.clone().into();
+
+            let creation_response: CredentialCreationResponse = serde_json::from_value(json!({
+                "id": encode(&credential_id),
+                "rawId": encode(&credential_id),
+                "type": "public-key",
+                "response": {
+                    "clientDataJSON": encode(&created.response.client_data_json),
+                    "attestationObject": encode(&created.response.attestation_object),
+                },
+            }))
+            .expect("the body matches the shape sent by browsers");
+            let attestation: Attestation<_> = (
+                creation_response,
+                AttestationMeta::new(AuthorityId::get(), blake2_256(&credential_id), context),
+            )
+                .try_into()
+                .expect("the attestation object carries a valid credential public key");
+
+            assert_ok!(Pass::register(
+                RuntimeOrigin::root(),
+                USER,
+                attestation.clone()
+            ));
+
+            let authenticated = client
+                .authenticate_credential_sync(credential_id.clone(), challenge.as_slice())
+                .expect("Failed retrieving credential");
+
+            let request_response: CredentialRequestResponse = serde_json::from_value(json!({
+                "id": encode(&credential_id),
+                "rawId": encode(&credential_id),
+                "type": "public-key",
+                "response": {
+                    "clientDataJSON": encode(&authenticated.response.client_data_json),
+                    "authenticatorData": encode(&authenticated.response.authenticator_data),
+                    "signature": encode(&authenticated.response.signature),
+                    "userHandle": authenticated.response.user_handle.as_deref().map(|h| encode(h)),
+                },
+            }))
+            .expect("the body matches the shape sent by browsers");
+            let assertion: Assertion<_> = (
+                request_response,
+                AssertionMeta::new(AuthorityId::get(), USER, context),
+            )
+                .try_into()
+                .expect("the user handle matches the user id");
+
+            assert_ok!(Pass::authenticate(
+                RuntimeOrigin::signed(1),
+                *(attestation.device_id()),
+                assertion,
+                None
+            ));
+        })
+    }
+
+    #[test]
+    fn a_missing_user_handle_yields_no_user_id() {
+        let response = |user_handle: Option<String>| -> CredentialRequestResponse {
+            serde_json::from_value(json!({
+                "id": encode(b"credential"),
+                "rawId": encode(b"credential"),
+                "type": "public-key",
+                "response": {
+                    "clientDataJSON": encode(b"{}"),
+                    "authenticatorData": encode(&[0; 37]),
+                    "signature": encode(&[0; 64]),
+                    "userHandle": user_handle,
+                },
+            }))
+            .expect("the body matches the shape sent by browsers")
+        };
+
+        assert_eq!(response(None).user_id(), Ok(None));
+        assert_eq!(response(Some(encode(&USER))).user_id(), Ok(Some(USER)));
+        assert!(response(Some("not base64!".into())).user_id().is_err());
+    }
+}
+
+mod passkey_compat {
+    use sp_io::hashing::blake2_256;
+
+    use super::*;
+    use crate::{Assertion, AssertionMeta, Attestation, AttestationMeta, ResponseError};
+
+    #[test]
+    fn creations_without_a_public_key_are_rejected() {
+        new_test_ext(1).execute_with(|client| {
+            let context = System::block_number();
+            let mut created = client
+                .create_credential_sync(USER, BlockChallenger::generate(&context).as_slice(), &[])
+                .expect("Failed creating credential");
+            let meta =
+                AttestationMeta::new(AuthorityId::get(), blake2_256(&created.raw_id), context);
+            created.response.public_key = None;
+
+            assert_eq!(
+                Attestation::<_>::try_from((created, meta)).err(),
+                Some(ResponseError::MissingPublicKey)
+            );
+        })
+    }
+
+    #[test]
+    fn creations_with_an_invalid_public_key_are_rejected() {
+        new_test_ext(1).execute_with(|client| {
+            let context = System::block_number();
+            let mut created = client
+                .create_credential_sync(USER, BlockChallenger::generate(&context).as_slice(), &[])
+                .expect("Failed creating credential");
+            let meta =
+                AttestationMeta::new(AuthorityId::get(), blake2_256(&created.raw_id), context);
+            created.response.public_key = Some(vec![0; 91].into());
+
+            assert_eq!(
+                Attestation::<_>::try_from((created, meta)).err(),
+                Some(ResponseError::InvalidPublicKey)
+            );
+        })
+    }
+
+    #[test]
+    fn assertions_for_another_user_are_rejected() {
+        new_test_ext(2).execute_with(|client| {
+            let context = System::block_number();
+            let challenge = BlockChallenger::generate(&context);
+            let (credential_id, _) = client.attestation(USER, context, AuthorityId::get());
+            let authenticated = client
+                .authenticate_credential_sync(credential_id, challenge.as_slice())
+                .expect("Failed retrieving credential");
+
+            assert_eq!(
+                Assertion::try_from((
+                    authenticated,
+                    AssertionMeta::new(AuthorityId::get(), s("other_user"), context),
+                ))
+                .err(),
+                Some(ResponseError::UserHandleMismatch)
+            );
+        })
+    }
+}
+
+mod authenticator_attachment {
+    use codec::MaxEncodedLen;
+    use passkey_types::webauthn::{self, AuthenticatorTransport};
+    use sp_io::hashing::blake2_256;
+
+    use super::*;
+    use crate::{Attestation, AttestationMeta, AuthenticatorAttachment, Credential, Transports};
+
+    fn register(
+        client: &mut WebAuthnClient,
+        attachment: Option<webauthn::AuthenticatorAttachment>,
+        transports: Option<Vec<AuthenticatorTransport>>,
+    ) -> Credential {
+        let context = System::block_number();
+        let mut created = client
+            .create_credential_sync(USER, BlockChallenger::generate(&context).as_slice(), &[])
+            .expect("Failed creating credential");
+        created.authenticator_attachment = attachment;
+        created.response.transports = transports;
+
+        let meta = AttestationMeta::new(AuthorityId::get(), blake2_256(&created.raw_id), context);
+        Attestation::<_>::try_from((created, meta))
+            .expect("The authenticator returns a valid P-256 public key; qed")
+            .into()
+    }
+
+    #[test]
+    fn explicit_attachment_is_stored() {
+        new_test_ext(2).execute_with(|client| {
+            assert_eq!(
+                register(
+                    client,
+                    Some(webauthn::AuthenticatorAttachment::Platform),
+                    None
+                )
+                .authenticator_attachment(),
+                AuthenticatorAttachment::Platform
+            );
+            assert_eq!(
+                register(
+                    client,
+                    Some(webauthn::AuthenticatorAttachment::CrossPlatform),
+                    Some(vec![AuthenticatorTransport::Internal])
+                )
+                .authenticator_attachment(),
+                AuthenticatorAttachment::CrossPlatform
+            );
+        })
+    }
+
+    #[test]
+    fn attachment_falls_back_to_transports() {
+        new_test_ext(3).execute_with(|client| {
+            assert_eq!(
+                register(
+                    client,
+                    None,
+                    Some(vec![
+                        AuthenticatorTransport::Usb,
+                        AuthenticatorTransport::Nfc
+                    ])
+                )
+                .authenticator_attachment(),
+                AuthenticatorAttachment::CrossPlatform
+            );
+            assert_eq!(
+                register(
+                    client,
+                    None,
+                    Some(vec![
+                        AuthenticatorTransport::Hybrid,
+                        AuthenticatorTransport::Internal
+                    ])
+                )
+                .authenticator_attachment(),
+                AuthenticatorAttachment::Platform
+            );
+            assert_eq!(
+                register(client, None, None).authenticator_attachment(),
+                AuthenticatorAttachment::Unknown
+            );
+        })
+    }
+
+    #[test]
+    fn attachment_is_stored_in_a_single_byte() {
+        assert_eq!(AuthenticatorAttachment::max_encoded_len(), 1);
+    }
+
+    #[test]
+    fn reported_transports_are_stored() {
+        new_test_ext(2).execute_with(|client| {
+            let (_, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let reported = client
+                .transports()
+                .iter()
+                .map(|transport| match transport {
+                    AuthenticatorTransport::Usb => Transports::USB,
+                    AuthenticatorTransport::Nfc => Transports::NFC,
+                    AuthenticatorTransport::Ble => Transports::BLE,
+                    AuthenticatorTransport::Hybrid => Transports::HYBRID,
+                    AuthenticatorTransport::Internal => Transports::INTERNAL,
+                })
+                .fold(Transports::default(), |all, transport| all | transport);
+            assert_eq!(credential_of(attestation).transports(), reported);
+
+            let credential = register(
+                client,
+                None,
+                Some(vec![
+                    AuthenticatorTransport::Usb,
+                    AuthenticatorTransport::Nfc,
+                ]),
+            );
+            assert_eq!(credential.transports(), Transports::USB | Transports::NFC);
+            assert!(!credential.transports().contains(Transports::INTERNAL));
+        })
+    }
+
+    #[test]
+    fn unknown_transports_are_flagged_as_other() {
+        assert_eq!(
+            Transports::from_names(["smart-card", "internal", "carrier-pigeon"]),
+            Transports::SMART_CARD | Transports::INTERNAL | Transports::OTHER
+        );
+        assert!(Transports::from_names([]).is_empty());
+    }
+
+    #[test]
+    fn transports_are_stored_in_a_single_byte() {
+        assert_eq!(Transports::max_encoded_len(), 1);
+    }
+}
+
+mod compressed_public_key {
+    use codec::{Decode, MaxEncodedLen};
+    use verifier::VerifyError;
+
+    use super::*;
+    use crate::{
+        migrate_credential, Credential, CredentialPublicKey, DEREncodedPublicKey, LegacyCredential,
+        SignCount, Transports,
+    };
+
+    #[test]
+    fn compressed_keys_shrink_credential_storage() {
+        assert_eq!(CredentialPublicKey::max_encoded_len(), 1 + 33);
+        // Compressing the key makes up for everything credentials track since.
+        assert!(Credential::max_encoded_len() < LegacyCredential::max_encoded_len());
+    }
+
+    #[test]
+    fn der_encoded_public_keys_are_validated() {
+        new_test_ext(1).execute_with(|client| {
+            let (_, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let der = attestation.public_key.as_der();
+
+            assert_eq!(
+                DEREncodedPublicKey::try_from(&der[..]),
+                Ok(attestation.public_key)
+            );
+            assert!(attestation.public_key.verifying_key().is_ok());
+            assert_eq!(
+                DEREncodedPublicKey::decode(&mut &der[..]),
+                Ok(attestation.public_key)
+            );
+
+            let random = [0x5a; 91];
+            assert_eq!(
+                DEREncodedPublicKey::try_from(&random[..]),
+                Err(VerifyError::ExtractPublicKey)
+            );
+            assert!(DEREncodedPublicKey::decode(&mut &random[..]).is_err());
+            assert_eq!(
+                DEREncodedPublicKey::try_from(&der[..70]),
+                Err(VerifyError::ExtractPublicKey)
+            );
+
+            let off_curve = [&der[..27], &[0xff; 64]].concat();
+            assert_eq!(
+                DEREncodedPublicKey::try_from(&off_curve[..]).and_then(|key| key.verifying_key()),
+                Err(VerifyError::ExtractPublicKey)
+            );
+        })
+    }
+
+    #[test]
+    fn compressed_key_converts_back_to_der() {
+        new_test_ext(1).execute_with(|client| {
+            let (_, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let credential = credential_of(attestation.clone());
+
+            assert_eq!(
+                DEREncodedPublicKey::try_from(credential.public_key()),
+                Ok(attestation.public_key)
+            );
+        })
+    }
+
+    #[test]
+    fn legacy_credentials_are_migrated() {
+        new_test_ext(2).execute_with(|client| {
+            let (credential_id, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            // As first stored: the device id, then the DER-encoded key.
+            let stored = [
+                &attestation.meta.device_id[..],
+                &attestation.public_key.as_der()[..],
+            ]
+            .concat();
+
+            let legacy = LegacyCredential::decode(&mut stored.as_slice())
+                .expect("The legacy layout is two arrays; qed");
+            assert_eq!(legacy.device_id, attestation.meta.device_id);
+            let credential =
+                migrate_credential(legacy).expect("The legacy key is a valid P-256 key");
+            assert_eq!(credential.algorithm(), -7);
+            assert_eq!(credential.sign_count(), SignCount::default());
+            assert!(!credential.uv_required());
+            assert_eq!(credential.transports(), Transports::default());
+            let assertion =
+                client.assertion(credential_id, System::block_number(), AuthorityId::get());
+            assert!(credential.verify_assertion(&assertion).is_ok());
+
+            let legacy = |public_key| LegacyCredential {
+                device_id: attestation.meta.device_id,
+                public_key,
+            };
+            assert!(migrate_credential(legacy([0x5a; 91])).is_none());
+            // A valid header, but a point off the curve.
+            let mut off_curve = *attestation.public_key.as_der();
+            off_curve[27..].fill(0xff);
+            assert!(migrate_credential(legacy(off_curve)).is_none());
+        })
+    }
+}
+
+mod batch {
+    use traits_authn::{util::VerifyCredential, DeviceChallengeResponse};
+
+    use super::*;
+    use crate::WebAuthnError;
+
+    fn clients(n: usize) -> Vec<WebAuthnClient> {
+        (0..n)
+            .map(|_| WebAuthnClient::new("https://pass-web.pass.int", 2))
+            .collect()
+    }
+
+    #[test]
+    fn batch_registers_every_device() {
+        new_test_ext(1).execute_with(|_| {
+            let mut clients = clients(3);
+            let (credential_ids, batch) = WebAuthnClient::batch_attestation(
+                &mut clients,
+                USER,
+                System::block_number(),
+                AuthorityId::get(),
+            );
+
+            assert_eq!(batch.validate::<BlockChallenger>(), Ok(()));
+            let credentials = batch
+                .into_credentials()
+                .expect("Validated batches make credentials; qed");
+            assert_eq!(credentials.len(), 3);
+
+            let assertion = clients[1].assertion(
+                credential_ids[1].clone(),
+                System::block_number(),
+                AuthorityId::get(),
+            );
+            assert!(credentials[1].verify(&assertion).is_some());
+            assert!(credentials[0].verify(&assertion).is_none());
+        })
+    }
+
+    #[test]
+    fn batch_is_rejected_if_any_item_is_invalid() {
+        new_test_ext(1).execute_with(|_| {
+            let mut clients = clients(3);
+            let (_, mut batch) = WebAuthnClient::batch_attestation(
+                &mut clients,
+                USER,
+                System::block_number(),
+                AuthorityId::get(),
+            );
+
+            // The second item signs the challenge of the first one.
+            let mut attestations = batch.attestations().to_vec();
+            attestations.swap(0, 1);
+            batch = crate::BatchAttestation::new(System::block_number(), attestations)
+                .expect("Same size as before; qed");
+            assert_eq!(
+                batch.validate::<BlockChallenger>(),
+                Err(WebAuthnError::InvalidChallenge)
+            );
+        })
+    }
+
+    #[test]
+    fn items_carry_their_indexed_challenge() {
+        new_test_ext(1).execute_with(|_| {
+            let mut clients = clients(2);
+            let (_, batch) = WebAuthnClient::batch_attestation(
+                &mut clients,
+                USER,
+                System::block_number(),
+                AuthorityId::get(),
+            );
+
+            let challenge = BlockChallenger::generate(&System::block_number());
+            for (index, attestation) in (0..).zip(batch.attestations()) {
+                assert_eq!(
+                    attestation.used_challenge().1,
+                    crate::batch_challenge(&challenge, index)
+                );
+            }
+        })
+    }
+}
+
+mod registration {
+    use sp_io::hashing::blake2_256;
+    use traits_authn::util::VerifyCredential;
+
+    use super::*;
+    use crate::{
+        register_none_attestation, register_none_attestation_with_transports, Credential,
+        Transports, WebAuthnError,
+    };
+
+    #[test]
+    fn none_attestation_registers_a_credential() {
+        new_test_ext(2).execute_with(|client| {
+            let context = System::block_number();
+            let created = client
+                .create_credential_sync(USER, BlockChallenger::generate(&context).as_slice(), &[])
+                .expect("Failed creating credential");
+            let credential_id: Vec<u8> = created.raw_id.clone().into();
+
+            let record = register_none_attestation::<BlockChallenger>(
+                &context,
+                &created.response.authenticator_data,
+                &created.response.client_data_json,
+                blake2_256,
+            )
+            .expect("The response is for the issued challenge");
+            assert_eq!(record.credential_id, credential_id);
+            assert_eq!(record.device_id, blake2_256(&credential_id));
+            assert_eq!(record.aaguid, [0; 16]);
+            assert_eq!(record.algorithm, -7);
+            assert_eq!(record.transports, None);
+
+            let credential: Credential = record.into();
+            let assertion = client.assertion(credential_id, context, AuthorityId::get());
+            assert!(credential.verify(&assertion).is_some());
+        })
+    }
+
+    #[test]
+    fn reported_transports_are_stored_with_the_credential() {
+        new_test_ext(2).execute_with(|client| {
+            let context = System::block_number();
+            let created = client
+                .create_credential_sync(USER, BlockChallenger::generate(&context).as_slice(), &[])
+                .expect("Failed creating credential");
+            let credential_id: Vec<u8> = created.raw_id.clone().into();
+
+            let record = register_none_attestation_with_transports::<BlockChallenger>(
+                &context,
+                &created.response.authenticator_data,
+                &created.response.client_data_json,
+                ["usb", "nfc"],
+                blake2_256,
+            )
+            .expect("The response is for the issued challenge");
+            assert_eq!(record.transports, Some(Transports::USB | Transports::NFC));
+
+            let credential: Credential = record.into();
+            assert_eq!(credential.transports(), Transports::USB | Transports::NFC);
+            let assertion = client.assertion(credential_id, context, AuthorityId::get());
+            assert!(credential.verify(&assertion).is_some());
+        })
+    }
+
+    #[test]
+    fn none_attestation_for_another_challenge_is_rejected() {
+        new_test_ext(1).execute_with(|client| {
+            let context = System::block_number();
+            let created = client
+                .create_credential_sync(USER, BlockChallenger::generate(&context).as_slice(), &[])
+                .expect("Failed creating credential");
+
+            assert_eq!(
+                register_none_attestation::<BlockChallenger>(
+                    &(context + 1),
+                    &created.response.authenticator_data,
+                    &created.response.client_data_json,
+                    blake2_256,
+                ),
+                Err(WebAuthnError::InvalidChallenge)
+            );
+        })
+    }
+}
+
+mod strict_decoding {
+    use base64::prelude::BASE64_URL_SAFE_NO_PAD;
+
+    use crate::runtime_helpers::{find_challenge_from_client_data, strict_decode_fixed};
+
+    fn client_data_with_challenge(challenge: &[u8]) -> Vec<u8> {
+        format!(
+            r#"{{"type":"webauthn.get","challenge":"{}","origin":"https://pass.int"}}"#,
+            base64::encode_engine(challenge, &BASE64_URL_SAFE_NO_PAD)
+        )
+        .into_bytes()
+    }
+
+    #[test]
+    fn fixed_arrays_need_the_exact_length() {
+        assert_eq!(strict_decode_fixed::<4>(b"abcd"), Some(*b"abcd"));
+        assert_eq!(strict_decode_fixed::<4>(b"ab"), None);
+        assert_eq!(strict_decode_fixed::<4>(b"abcde"), None);
+    }
+
+    #[test]
+    fn short_challenges_are_not_zero_extended() {
+        // Zero-extended, both would be `ab` followed by 30 zero bytes.
+        assert_eq!(
+            find_challenge_from_client_data(&client_data_with_challenge(b"ab")),
+            None
+        );
+        assert_eq!(
+            find_challenge_from_client_data(&client_data_with_challenge(b"ab\0")),
+            None
+        );
+
+        let mut challenge = [0u8; 32];
+        challenge[..2].copy_from_slice(b"ab");
+        assert_eq!(
+            find_challenge_from_client_data(&client_data_with_challenge(&challenge)),
+            Some(challenge)
+        );
+    }
+
+    #[test]
+    fn long_challenges_are_not_truncated() {
+        let challenge = [7u8; 32];
+        let longer = [&challenge[..], b"x"].concat();
+
+        assert_eq!(
+            find_challenge_from_client_data(&client_data_with_challenge(&challenge)),
+            Some(challenge)
+        );
+        assert_eq!(
+            find_challenge_from_client_data(&client_data_with_challenge(&longer)),
+            None
+        );
+    }
+}
+
+mod rp_id {
+    use sha2::{Digest, Sha256};
+
+    use super::*;
+    use crate::{expected_rp_id, expected_rp_id_hash, RpIdError, WebAuthnError};
+
+    #[test]
+    fn pallet_ids_become_subdomains() {
+        let authority = pallet_authority(&PassPalletId::get().0);
+        let rp_id = expected_rp_id(&authority, "pass.int").expect("`pass_web` is a valid label");
+
+        // `_` isn't valid in DNS labels.
+        assert_eq!(rp_id.as_str(), "pass-web.pass.int");
+        assert_eq!(rp_id.origin(), "https://pass-web.pass.int");
+        assert_eq!(
+            expected_rp_id_hash(&authority, "pass.int"),
+            Ok(Sha256::digest(b"pass-web.pass.int").into())
+        );
+        assert_eq!(
+            expected_rp_id(&pallet_authority(b"PassAuth"), "pass.int").map(|id| id.to_string()),
+            Ok("passauth.pass.int".into())
+        );
+    }
+
+    #[test]
+    fn authorities_that_are_not_dns_labels_are_rejected() {
+        for authority in [b"pass\xffweb", b"pass.web", b"pass\0web", b"-passweb"] {
+            assert_eq!(
+                expected_rp_id(&pallet_authority(authority), "pass.int"),
+                Err(RpIdError::InvalidAuthority)
+            );
+        }
+        assert_eq!(
+            expected_rp_id(&[0; 32], "pass.int"),
+            Err(RpIdError::EmptyAuthority)
+        );
+
+        let authority = pallet_authority(b"pass_web");
+        for base_domain in [
+            "",
+            "https://pass.int",
+            "pass.int:443",
+            "Pass.int",
+            "pass..int",
+        ] {
+            assert_eq!(
+                expected_rp_id(&authority, base_domain),
+                Err(RpIdError::InvalidBaseDomain)
+            );
+        }
+    }
+
+    #[test]
+    fn origins_must_use_https() {
+        let rp_id = expected_rp_id(&pallet_authority(b"pass_web"), "pass.int").unwrap();
+
+        assert_eq!(rp_id.check_origin("https://pass-web.pass.int"), Ok(()));
+        assert_eq!(
+            rp_id.check_origin("http://pass-web.pass.int"),
+            Err(RpIdError::InsecureOrigin)
+        );
+        assert_eq!(
+            rp_id.check_origin("https://pass_web.pass.int"),
+            Err(RpIdError::OriginMismatch)
+        );
+        assert_eq!(
+            rp_id.check_origin("https://pass-web.pass.int:8443"),
+            Err(RpIdError::OriginMismatch)
+        );
+    }
+
+    #[test]
+    fn responses_are_checked_against_the_authority_rp_id() {
+        new_test_ext(2).execute_with(|client| {
+            let authority = pallet_authority(&PassPalletId::get().0);
+            let (credential_id, attestation) =
+                client.attestation(USER, System::block_number(), authority);
+            let assertion = client.assertion(credential_id, System::block_number(), authority);
+
+            assert_eq!(attestation.check_rp_id("pass.int"), Ok(()));
+            assert_eq!(assertion.check_rp_id("pass.int"), Ok(()));
+            assert_eq!(
+                attestation.check_rp_id("other.int"),
+                Err(WebAuthnError::RpIdMismatch)
+            );
+            assert_eq!(
+                assertion.check_rp_id("other.int"),
+                Err(WebAuthnError::RpIdMismatch)
+            );
+
+            let (_, elsewhere) = WebAuthnClient::new("https://helper.pass.int", 1).attestation(
+                USER,
+                System::block_number(),
+                authority,
+            );
+            assert_eq!(
+                elsewhere.check_rp_id("pass.int"),
+                Err(WebAuthnError::RpIdMismatch)
+            );
+        })
+    }
+}
+
+mod ids {
+    use codec::{Decode, Encode};
+    use sp_io::hashing::blake2_256;
+
+    use crate::{
+        expected_rp_id,
+        ids::{AuthorityId, DeviceId, HashedUserId},
+        RpIdError,
+    };
+
+    #[test]
+    fn authorities_round_trip_through_their_rp_id() {
+        let authority = AuthorityId::from_rp_id("pass-web.pass.int", "pass.int").unwrap();
+        assert_eq!(&authority.as_bytes()[..9], b"pass-web\0");
+        assert_eq!(
+            expected_rp_id(&authority.into(), "pass.int").map(|id| id.to_string()),
+            Ok("pass-web.pass.int".into())
+        );
+
+        // `pass_web` has the same RP ID, so its authority id isn't the one found.
+        let mut pass_web = [0; 32];
+        pass_web[..8].copy_from_slice(b"pass_web");
+        let rp_id = expected_rp_id(&pass_web, "pass.int").unwrap();
+        assert_eq!(
+            AuthorityId::from_rp_id(rp_id.as_str(), "pass.int"),
+            Ok(authority)
+        );
+    }
+
+    #[test]
+    fn rp_ids_that_are_not_an_authority_under_the_base_domain_are_rejected() {
+        for rp_id in ["pass.int", "pass-web.other.int", "pass-webpass.int"] {
+            assert_eq!(
+                AuthorityId::from_rp_id(rp_id, "pass.int"),
+                Err(RpIdError::NotUnderBaseDomain)
+            );
+        }
+        let too_long = format!("{}.pass.int", "a".repeat(33));
+        for rp_id in [
+            "a.b.pass.int",
+            "Pass-web.pass.int",
+            "-pass.pass.int",
+            &too_long,
+        ] {
+            assert_eq!(
+                AuthorityId::from_rp_id(rp_id, "pass.int"),
+                Err(RpIdError::InvalidAuthority)
+            );
+        }
+        assert_eq!(
+            AuthorityId::from_rp_id("pass-web.pass.int", "https://pass.int"),
+            Err(RpIdError::InvalidBaseDomain)
+        );
+    }
+
+    #[test]
+    fn devices_are_the_hash_of_their_credential() {
+        let device_id = DeviceId::from_credential_id(b"credential");
+        assert_eq!(*device_id.as_bytes(), blake2_256(b"credential"));
+        assert_ne!(device_id, DeviceId::from_credential_id(b"other credential"));
+    }
+
+    #[test]
+    fn user_handles_must_be_hashed_user_ids() {
+        assert_eq!(
+            HashedUserId::from_user_handle(&[1; 32]),
+            Some(HashedUserId::from_bytes([1; 32]))
+        );
+        assert_eq!(HashedUserId::from_user_handle(&[1; 31]), None);
+        assert_eq!(HashedUserId::from_user_handle(&[1; 33]), None);
+    }
+
+    #[test]
+    fn ids_encode_like_the_arrays_they_wrap() {
+        let bytes = [7; 32];
+        let device_id = DeviceId::from(bytes);
+
+        assert_eq!(device_id.encode(), bytes.encode());
+        assert_eq!(
+            DeviceId::decode(&mut &bytes.encode()[..]).unwrap(),
+            device_id
+        );
+        assert_eq!(
+            serde_json::to_string(&device_id).unwrap(),
+            serde_json::to_string(&bytes).unwrap()
+        );
+        assert_eq!(
+            serde_json::from_str::<HashedUserId>(&serde_json::to_string(&bytes).unwrap()).unwrap(),
+            HashedUserId::from(bytes)
+        );
+        assert_eq!(<[u8; 32]>::from(AuthorityId::from(bytes)), bytes);
+    }
+}
+
+mod meta_builder {
+    use crate::{AssertionMeta, AssertionMetaBuilder, AttestationMeta, AttestationMetaBuilder};
+
+    #[test]
+    fn builds_when_every_field_is_set() {
+        assert_eq!(
+            AttestationMetaBuilder::new()
+                .authority_id([1; 32])
+                .device_id([2; 32])
+                .context(3u32)
+                .build(),
+            Ok(AttestationMeta::new([1; 32], [2; 32], 3))
+        );
+        assert_eq!(
+            AttestationMetaBuilder::new()
+                .authority_id([1; 32])
+                .device_id([2; 32])
+                .context(3u32)
+                .user_id([4; 32])
+                .build(),
+            Ok(AttestationMeta::new([1; 32], [2; 32], 3).with_user_id([4; 32]))
+        );
+        assert_eq!(
+            AssertionMetaBuilder::new()
+                .authority_id([1; 32])
+                .user_id([2; 32])
+                .context(3u32)
+                .build(),
+            Ok(AssertionMeta::new([1; 32], [2; 32], 3))
+        );
+    }
+
+    #[test]
+    fn zero_authority_id_is_rejected() {
+        let error = AttestationMetaBuilder::new()
+            .authority_id([0; 32])
+            .device_id([2; 32])
+            .context(3u32)
+            .build()
+            .unwrap_err();
+        assert_eq!(error.missing, ["authority_id"]);
+        assert_eq!(error.to_string(), "missing authority_id");
+    }
+
+    #[test]
+    fn missing_fields_are_listed() {
+        let error = AttestationMetaBuilder::<u32>::new()
+            .authority_id([1; 32])
+            .build()
+            .unwrap_err();
+        assert_eq!(error.missing, ["device_id", "context"]);
+
+        let error = AssertionMetaBuilder::<u32>::new().build().unwrap_err();
+        assert_eq!(error.missing, ["authority_id", "user_id", "context"]);
+    }
+}
+
+mod logging {
+    use core::cell::RefCell;
+    use log::{Level, LevelFilter, Log, Metadata, Record};
+    use traits_authn::util::VerifyCredential;
+
+    use super::*;
+
+    /// Records the target and level of what's logged on the current thread, as tests run in
+    /// parallel.
+    struct Capture;
+
+    std::thread_local! {
+        static CAPTURED: RefCell<Vec<(String, Level)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    impl Log for Capture {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            CAPTURED.with_borrow_mut(|captured| {
+                captured.push((record.target().into(), record.level()))
+            });
+        }
+
+        fn flush(&self) {}
+    }
+
+    fn capture(run: impl FnOnce()) -> Vec<(String, Level)> {
+        // Fails when another test installed it first, which is fine.
+        let _ = log::set_logger(&Capture);
+        log::set_max_level(LevelFilter::Trace);
+        CAPTURED.take();
+        run();
+        CAPTURED.take()
+    }
+
+    #[test]
+    fn failing_attestations_log_their_checks() {
+        new_test_ext(1).execute_with(|client| {
+            let (_, mut attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            // Alters the signature counter, so only the statement fails to verify.
+            attestation.authenticator_data[33] ^= 1;
+
+            let captured = capture(|| assert!(attestation.validate().is_err()));
+            let attestation_logs: Vec<_> = captured
+                .iter()
+                .filter(|(target, _)| target == "pass_webauthn::attestation")
+                .map(|(_, level)| *level)
+                .collect();
+
+            // The client data, the challenge, the key, the statement and the outcome.
+            assert!(attestation_logs.contains(&Level::Debug));
+            assert!(attestation_logs.contains(&Level::Trace));
+            assert!(
+                attestation_logs
+                    .iter()
+                    .filter(|level| **level == Level::Debug)
+                    .count()
+                    >= 5
+            );
+            assert!(!captured
+                .iter()
+                .any(|(target, _)| target == "pass_webauthn::assertion"));
+        })
+    }
+
+    #[test]
+    fn failed_verifications_log_why() {
+        new_test_ext(2).execute_with(|client| {
+            let (credential_id, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            let credential = credential_of(attestation);
+            let mut assertion =
+                client.assertion(credential_id, System::block_number(), AuthorityId::get());
+            assertion.authenticator_data[33] ^= 1;
+
+            let captured = capture(|| assert!(credential.verify(&assertion).is_none()));
+            assert!(captured.contains(&("pass_webauthn::assertion".into(), Level::Warn)));
+        })
+    }
+}
+
+mod timestamp_challenger {
+    use core::time::Duration;
+    use frame_support::traits::UnixTime;
+    use traits_authn::DeviceChallengeResponse;
+
+    use super::*;
+    use crate::{Device, TimestampChallenger};
+
+    parameter_types! {
+        pub static Now: u64 = 0;
+    }
+
+    struct Clock;
+
+    impl UnixTime for Clock {
+        fn now() -> Duration {
+            Duration::from_secs(Now::get())
+        }
+    }
+
+    const BUCKET: u64 = 30;
+    const WINDOW: u64 = 2;
+
+    type Ch = TimestampChallenger<Clock, ConstU64<WINDOW>, ConstU64<BUCKET>>;
+
+    fn accepted_at(now: u64, bucket: u64, challenge: &[u8]) -> bool {
+        Now::set(now);
+        Ch::check_challenge(&bucket, challenge).is_some()
+    }
+
+    #[test]
+    fn attestations_are_accepted_within_the_window() {
+        new_test_ext(1).execute_with(|client| {
+            Now::set(10 * BUCKET + 5);
+            let bucket = Ch::current_bucket();
+            assert_eq!(bucket, 10);
+
+            let (_, attestation) = client.attestation_with_challenge(
+                USER,
+                Ch::generate(&bucket),
+                bucket,
+                AuthorityId::get(),
+            );
+            assert_ok!(attestation.validate());
+            let (context, challenge) = attestation.used_challenge();
+            assert_eq!(context, bucket);
+
+            assert!(accepted_at(10 * BUCKET + 5, context, &challenge));
+            assert!(accepted_at(11 * BUCKET, context, &challenge));
+            assert!(accepted_at(
+                (10 + WINDOW) * BUCKET + BUCKET - 1,
+                context,
+                &challenge
+            ));
+            assert!(!accepted_at(
+                (10 + WINDOW + 1) * BUCKET,
+                context,
+                &challenge
+            ));
+
+            // Devices can be challenged with it, as with the block number challenger.
+            let _: Device<Ch, AuthorityId> = attestation.into();
+        })
+    }
+
+    #[test]
+    fn issuers_may_be_a_bucket_ahead() {
+        // The last second of bucket 10, and the first one of bucket 11.
+        let (end_of_10, start_of_11) = (11 * BUCKET - 1, 11 * BUCKET);
+
+        assert!(accepted_at(end_of_10, 11, &Ch::generate(&11)));
+        assert!(!accepted_at(end_of_10, 12, &Ch::generate(&12)));
+        assert!(accepted_at(start_of_11, 12, &Ch::generate(&12)));
+        assert!(accepted_at(start_of_11, 10, &Ch::generate(&10)));
+    }
+
+    #[test]
+    fn challenges_are_bound_to_their_bucket() {
+        assert_ne!(Ch::generate(&10), Ch::generate(&11));
+        assert!(!accepted_at(10 * BUCKET, 10, &Ch::generate(&11)));
+        assert_ne!(Ch::generate(&10), BlockChallenger::generate(&10));
+    }
+}
+
+mod seeded_client {
+    use codec::Encode;
+
+    use super::*;
+
+    const SEED: [u8; 32] = [0x5e; 32];
+
+    /// A credential id, attestation and assertion of a fresh client seeded with `seed`.
+    fn ceremonies(seed: [u8; 32]) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        let mut client = WebAuthnClient::new_seeded("https://pass-web.pass.int", 2, seed);
+        let (credential_id, attestation) =
+            client.attestation(USER, System::block_number(), AuthorityId::get());
+        let assertion = client.assertion(
+            credential_id.as_slice(),
+            System::block_number(),
+            AuthorityId::get(),
+        );
+        (credential_id, attestation.encode(), assertion.encode())
+    }
+
+    #[test]
+    fn same_seed_yields_identical_responses() {
+        new_test_ext(0).execute_with(|_| {
+            let first = ceremonies(SEED);
+            assert_eq!(first, ceremonies(SEED));
+
+            let other = ceremonies([0x5f; 32]);
+            assert_ne!(first.0, other.0);
+            assert_ne!(first.1, other.1);
+        })
+    }
+
+    #[test]
+    fn seeded_credentials_verify() {
+        new_test_ext(0).execute_with(|_| {
+            let mut client = WebAuthnClient::new_seeded("https://pass-web.pass.int", 3, SEED);
+            let (first_id, attestation) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            assert!(attestation.is_valid());
+
+            let credential = credential_of(attestation);
+            let assertion =
+                client.assertion(first_id.clone(), System::block_number(), AuthorityId::get());
+            assert!(credential.verify_assertion(&assertion).is_ok());
+
+            // Later credentials of the same client are derived apart.
+            let (second_id, _) =
+                client.attestation(USER, System::block_number(), AuthorityId::get());
+            assert_ne!(first_id, second_id);
+        })
+    }
+}
+
+mod tamper {
+    use traits_authn::{Challenger, DeviceChallengeResponse, UserChallengeResponse};
+    use verifier::VerifyError;
+
+    use super::*;
+    use crate::{testing::Tamper, ClientDataError, Credential, WebAuthnError};
+
+    #[test]
+    fn every_tampered_attestation_is_rejected() {
+        new_test_ext(1).execute_with(|client| {
+            let authority = pallet_authority(&PassPalletId::get().0);
+            let (_, attestation) = client.attestation(USER, System::block_number(), authority);
+            assert_eq!(attestation.validate(), Ok(()));
+            assert_eq!(attestation.check_rp_id("pass.int"), Ok(()));
+            let (context, challenge) = attestation.used_challenge();
+            assert_eq!(
+                BlockChallenger::check_challenge(&context, &challenge),
+                Some(())
+            );
+
+            // Signed again, so each is rejected by the check for what was tampered with rather
+            // than by a broken signature.
+            let (context, challenge) = client
+                .resign(&attestation.with_wrong_challenge())
+                .used_challenge();
+            assert_eq!(BlockChallenger::check_challenge(&context, &challenge), None);
+            assert_eq!(
+                client
+                    .resign(&attestation.with_wrong_origin())
+                    .check_rp_id("pass.int"),
+                Err(WebAuthnError::RpIdMismatch)
+            );
+            assert_eq!(
+                client.resign(&attestation.with_wrong_type()).validate(),
+                Err(ClientDataError::TypeMismatch.into())
+            );
+            assert_eq!(
+                client
+                    .resign(&attestation.with_user_presence_cleared())
+                    .validate(),
+                Err(WebAuthnError::UserNotPresent)
+            );
+            assert_eq!(
+                client
+                    .resign(&attestation.with_truncated_authenticator_data())
+                    .validate(),
+                Err(VerifyError::ParseAuthenticatorData.into())
+            );
+
+            // A fresh credential has no counter to go back on, so only the signature catches it.
+            assert_eq!(
+                attestation.with_decremented_counter().validate(),
+                Err(VerifyError::VerifySignature.into())
+            );
+            assert_eq!(
+                attestation.with_flipped_signature_bit().validate(),
+                Err(VerifyError::VerifySignature.into())
+            );
+            assert_eq!(
+                attestation.with_swapped_public_key().validate(),
+                Err(VerifyError::PublicKeyMismatch.into())
+            );
+        })
+    }
+
+    #[test]
+    fn every_tampered_assertion_is_rejected() {
+        new_test_ext(2).execute_with(|client| {
+            let context = System::block_number();
+            let authority = pallet_authority(&PassPalletId::get().0);
+            let (credential_id, attestation) = client.attestation(USER, context, authority);
+            // Ahead of the stored counter, so moving it back doesn't wrap around.
+            let assertion = client.assertion_with_counter(credential_id, context, authority, 5);
+            let credential = Credential {
+                sign_count: 4.into(),
+                ..credential_of(attestation)
+            };
+            assert!(credential.verify_assertion(&assertion).is_ok());
+            assert_eq!(assertion.check_rp_id("pass.int"), Ok(()));
+            let (context, challenge) = assertion.used_challenge();
+            assert_eq!(
+                BlockChallenger::check_challenge(&context, &challenge),
+                Some(())
+            );
+
+            // Signed again, so each is rejected by the check for what was tampered with rather
+            // than by a broken signature.
+            let tampered = client.resign(&assertion.with_wrong_challenge());
+            assert!(credential.verify_assertion(&tampered).is_ok());
+            let (context, challenge) = tampered.used_challenge();
+            assert_eq!(BlockChallenger::check_challenge(&context, &challenge), None);
+            assert_eq!(
+                client
+                    .resign(&assertion.with_wrong_origin())
+                    .check_rp_id("pass.int"),
+                Err(WebAuthnError::RpIdMismatch)
+            );
+            let verify = |tampered| credential.verify_assertion(&tampered).map(|_| ());
+            assert_eq!(
+                verify(client.resign(&assertion.with_wrong_type())),
+                Err(ClientDataError::TypeMismatch.into())
+            );
+            assert_eq!(
+                verify(client.resign(&assertion.with_user_presence_cleared())),
+                Err(WebAuthnError::UserNotPresent)
+            );
+            assert_eq!(
+                verify(client.resign(&assertion.with_truncated_authenticator_data())),
+                Err(VerifyError::ParseAuthenticatorData.into())
+            );
+            assert_eq!(
+                verify(client.resign(&assertion.with_decremented_counter())),
+                Err(WebAuthnError::Replayed)
+            );
+
+            assert_eq!(
+                verify(assertion.with_flipped_signature_bit()),
+                Err(VerifyError::VerifySignature.into())
+            );
+        })
+    }
+}
+
+mod fixtures {
+    use super::*;
+    use crate::testing::fixture::{replay_dir, Fixture, Outcome, Parsed, Policy};
+
+    const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+    #[test]
+    fn checked_in_fixtures_replay() {
+        assert!(replay_dir(FIXTURES_DIR) > 0);
+    }
+
+    /// The vectors of `verifier::test_vectors` replay as fixtures, against the credential too.
+    #[test]
+    fn shared_test_vectors_replay() {
+        use verifier::test_vectors::{self, ES256_ASSERTION, ES256_ASSERTION_USER_PRESENT};
+
+        let fixture = |vector: test_vectors::Assertion, uv_required, expected| Fixture {
+            description: "verifier test vector".into(),
+            authenticator_data: vector.authenticator_data.to_vec(),
+            client_data_json: vector.client_data_json.to_vec(),
+            signature: vector.signature.to_vec(),
+            public_key: vector.public_key.to_vec(),
+            parsed: Parsed::default(),
+            policy: Policy {
+                expected_challenge: Some(test_vectors::CHALLENGE.to_vec()),
+                stored_sign_count: 0,
+                uv_required,
+            },
+            expected,
+        };
+
+        assert_eq!(fixture(ES256_ASSERTION, true, Outcome::Ok).check(), Ok(()));
+        assert_eq!(
+            fixture(ES256_ASSERTION_USER_PRESENT, false, Outcome::Ok).check(),
+            Ok(())
+        );
+        assert_eq!(
+            fixture(
+                ES256_ASSERTION_USER_PRESENT,
+                true,
+                Outcome::Error("UserVerificationRequired".into())
+            )
+            .check(),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn exported_fixtures_round_trip() {
+        new_test_ext(1).execute_with(|client| {
+            let fixture = client.assertion_fixture(
+                "passkey-rs software authenticator",
+                USER,
+                System::block_number(),
+                AuthorityId::get(),
+            );
+            assert_eq!(fixture.expected, Outcome::Ok);
+            assert!(fixture.policy.expected_challenge.is_some());
+            assert_eq!(fixture.check(), Ok(()));
+
+            let path = std::env::temp_dir().join("pass-webauthn-exported-fixture.json");
+            fixture.save(&path).unwrap();
+            let loaded = Fixture::load(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(loaded, fixture);
+        })
+    }
+
+    #[test]
+    fn unexpected_outcomes_fail_the_check() {
+        new_test_ext(1).execute_with(|client| {
+            let fixture = client.assertion_fixture(
+                "passkey-rs software authenticator",
+                USER,
+                System::block_number(),
+                AuthorityId::get(),
+            );
+
+            let mut corrupted = fixture.clone();
+            let last = corrupted.signature.len() - 1;
+            corrupted.signature[last] ^= 1;
+            assert_eq!(
+                corrupted.verify_low_level(),
+                Outcome::Error("VerifySignature".into())
+            );
+            assert_eq!(
+                corrupted.verify_high_level(),
+                Outcome::Error("VerifySignature".into())
+            );
+            assert!(corrupted.check().is_err());
+
+            let mut other_challenge = fixture.clone();
+            other_challenge.policy.expected_challenge = Some(vec![0; 32]);
+            assert_eq!(
+                other_challenge.verify_high_level(),
+                Outcome::Error("ChallengeMismatch".into())
+            );
+            assert!(other_challenge.check().is_err());
+
+            let mut expecting_failure = fixture;
+            expecting_failure.expected = Outcome::Error("VerifySignature".into());
+            assert!(expecting_failure.check().is_err());
+        })
+    }
+
+    #[test]
+    fn parsed_fields_are_checked() {
+        new_test_ext(1).execute_with(|client| {
+            let mut fixture = client.assertion_fixture(
+                "passkey-rs software authenticator",
+                USER,
+                System::block_number(),
+                AuthorityId::get(),
+            );
+            let flags = fixture.authenticator_data[32];
+            fixture.parsed = Parsed {
+                flags: Some(flags),
+                ..Default::default()
+            };
+            assert_eq!(fixture.check_parsed(), Ok(()));
+
+            fixture.parsed.flags = Some(flags ^ 0x04);
+            assert!(fixture.check().is_err());
+
+            // Assertions carry no attested credential data, hence no AAGUID.
+            fixture.parsed = Parsed {
+                aaguid: Some("00000000-0000-0000-0000-000000000000".into()),
+                ..Default::default()
+            };
+            assert!(fixture.check_parsed().is_err());
+        })
+    }
+}
+
+mod options {
+    use codec::{Encode, MaxEncodedLen};
+    use frame_support::{assert_err, storage_alias, Blake2_128Concat};
+
+    use super::*;
+    use crate::{AuthenticationOptions, RegistrationOptions, WebAuthnError};
+
+    #[storage_alias]
+    type PendingRegistrations =
+        StorageMap<PassWebAuthnOptions, Blake2_128Concat, HashedUserId, RegistrationOptions>;
+
+    #[storage_alias]
+    type PendingAuthentications =
+        StorageMap<PassWebAuthnOptions, Blake2_128Concat, HashedUserId, AuthenticationOptions>;
+
+    const RP_ID: &str = "pass-web.pass.int";
+    const ORIGIN: &str = "https://pass-web.pass.int";
+
+    #[test]
+    fn stored_options_check_the_ceremonies() {
+        new_test_ext(2).execute_with(|client| {
+            let context = System::block_number();
+            let challenge = BlockChallenger::generate(&context);
+            PendingRegistrations::insert(
+                USER,
+                RegistrationOptions::new(RP_ID, ORIGIN, challenge).unwrap(),
+            );
+            PendingAuthentications::insert(
+                USER,
+                AuthenticationOptions::new(RP_ID, ORIGIN, challenge).unwrap(),
+            );
+
+            let (credential_id, attestation) =
+                client.attestation(USER, context, AuthorityId::get());
+            let registration = PendingRegistrations::take(USER).unwrap();
+            assert_eq!(registration.challenge, challenge);
+            assert_ok!(attestation.check_options(&registration));
+            assert_ok!(attestation.validate());
+
+            let assertion = client.assertion(credential_id, context, AuthorityId::get());
+            let authentication = PendingAuthentications::take(USER).unwrap();
+            assert_ok!(assertion.check_options(&authentication));
+            let credential = credential_of(attestation);
+            assert!(credential.verify_assertion(&assertion).is_ok());
+
+            assert!(!PendingRegistrations::contains_key(USER));
+            assert!(!PendingAuthentications::contains_key(USER));
+        })
+    }
+
+    #[test]
+    fn responses_for_other_options_are_rejected() {
+        new_test_ext(1).execute_with(|client| {
+            let context = System::block_number();
+            let challenge = BlockChallenger::generate(&context);
+            let (_, attestation) = client.attestation(USER, context, AuthorityId::get());
+
+            let other_challenge = RegistrationOptions::new(RP_ID, ORIGIN, [0; 32]).unwrap();
+            assert_err!(
+                attestation.check_options(&other_challenge),
+                WebAuthnError::InvalidChallenge
+            );
+            let other_rp_id = RegistrationOptions::new("pass.int", ORIGIN, challenge).unwrap();
+            assert_err!(
+                attestation.check_options(&other_rp_id),
+                WebAuthnError::RpIdMismatch
+            );
+            let other_origin =
+                RegistrationOptions::new(RP_ID, "https://evil.pass.int", challenge).unwrap();
+            assert_err!(
+                attestation.check_options(&other_origin),
+                WebAuthnError::RpIdMismatch
+            );
+        })
+    }
+
+    #[test]
+    fn options_are_bounded() {
+        let longest_rp_id = "a".repeat(253);
+        let longest_origin = format!("https://{longest_rp_id}:65535");
+        let options = RegistrationOptions::new(&longest_rp_id, &longest_origin, [0; 32]).unwrap();
+        assert!(options.encoded_size() <= RegistrationOptions::max_encoded_len());
+
+        assert!(RegistrationOptions::new(&"a".repeat(254), ORIGIN, [0; 32]).is_none());
+        assert!(
+            AuthenticationOptions::new(RP_ID, &format!("{longest_origin}0"), [0; 32]).is_none()
+        );
+    }
 }
@@ -0,0 +1,61 @@
+//! A [`Challenger`] over wall-clock time rather than block numbers, for chains with long block
+//! times, and for relying parties outside a chain that have no block number at all.
+
+use core::marker::PhantomData;
+use frame_support::traits::{ConstU64, Get, UnixTime};
+use sha2::{Digest, Sha256};
+use traits_authn::{Challenge, Challenger};
+
+/// Length of a bucket, in seconds, unless configured otherwise.
+pub const DEFAULT_BUCKET_SECS: u64 = 60;
+
+/// Hashed along the bucket, so these challenges never collide with those of other challengers.
+const DOMAIN: &[u8] = b"pass-webauthn/timestamp-challenge";
+
+/// Issues challenges for time buckets: the Unix time `T` tells, in seconds, divided by `Bucket`.
+///
+/// The context of a challenge is the bucket it was issued in, which [`Self::current_bucket`]
+/// gives. It's accepted until `Window` buckets later, and already in the bucket before it, so an
+/// issuer whose clock runs ahead of `T`'s by less than a bucket isn't turned down.
+pub struct TimestampChallenger<T, Window, Bucket = ConstU64<DEFAULT_BUCKET_SECS>>(
+    PhantomData<(T, Window, Bucket)>,
+);
+
+impl<T, Window, Bucket> TimestampChallenger<T, Window, Bucket>
+where
+    T: UnixTime,
+    Bucket: Get<u64>,
+{
+    /// The bucket of the current time, to issue challenges with.
+    pub fn current_bucket() -> u64 {
+        T::now().as_secs() / Bucket::get().max(1)
+    }
+}
+
+impl<T, Window, Bucket> Challenger for TimestampChallenger<T, Window, Bucket>
+where
+    T: UnixTime,
+    Window: Get<u64>,
+    Bucket: Get<u64>,
+{
+    type Context = u64;
+
+    fn generate(bucket: &u64) -> Challenge {
+        Sha256::new()
+            .chain_update(DOMAIN)
+            .chain_update(bucket.to_le_bytes())
+            .finalize()
+            .into()
+    }
+
+    fn check_challenge(bucket: &u64, challenge: &[u8]) -> Option<()> {
+        let now = Self::current_bucket();
+        let expired = bucket.saturating_add(Window::get()) < now;
+        let from_the_future = *bucket > now.saturating_add(1);
+        if expired || from_the_future {
+            log::warn!("Challenge of bucket {bucket} isn't valid in bucket {now}");
+            return None;
+        }
+        (Self::generate(bucket) == challenge).then_some(())
+    }
+}
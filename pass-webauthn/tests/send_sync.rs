@@ -0,0 +1,15 @@
+//! Runtimes share these types across threads, so they must stay `Send + Sync`.
+
+use pass_webauthn::{
+    Assertion, AssertionMeta, Attestation, AttestationMeta, Credential, WebAuthnError,
+};
+use static_assertions::assert_impl_all;
+use verifier::VerifyError;
+
+assert_impl_all!(Credential: Send, Sync);
+assert_impl_all!(Attestation<u64>: Send, Sync);
+assert_impl_all!(Assertion<u64>: Send, Sync);
+assert_impl_all!(AttestationMeta<u64>: Send, Sync);
+assert_impl_all!(AssertionMeta<u64>: Send, Sync);
+assert_impl_all!(VerifyError: Send, Sync);
+assert_impl_all!(WebAuthnError: Send, Sync);
@@ -0,0 +1,34 @@
+//! Decoding of ES256 COSE keys straight from their coordinates, against the DER round trip it
+//! replaced. Only the key decoding is measured, as the signature check both paths share dwarfs
+//! it.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use p256::{ecdsa::SigningKey, pkcs8::DecodePublicKey, PublicKey};
+use rand::rngs::OsRng;
+use verifier::{cose_key_from_public_key, cose_key_to_der, cose_key_to_public_key};
+
+fn decode_cose_keys(c: &mut Criterion) {
+    let corpus: Vec<_> = (0..8)
+        .map(|_| cose_key_from_public_key(&SigningKey::random(&mut OsRng).verifying_key().into()))
+        .collect();
+
+    let mut group = c.benchmark_group("cose_key");
+    group.bench_function("der_path", |b| {
+        b.iter(|| {
+            for key in &corpus {
+                black_box(PublicKey::from_public_key_der(&cose_key_to_der(key).unwrap()).unwrap());
+            }
+        })
+    });
+    group.bench_function("cose_path", |b| {
+        b.iter(|| {
+            for key in &corpus {
+                black_box(cose_key_to_public_key(key).unwrap());
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, decode_cose_keys);
+criterion_main!(benches);
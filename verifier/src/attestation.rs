@@ -0,0 +1,269 @@
+//! Parsing of attestation objects.
+//!
+//! See <https://www.w3.org/TR/webauthn/#sctn-attestation>.
+
+use alloc::vec::Vec;
+use core::fmt;
+use coset::{
+    cbor::{de::from_reader, value::Value},
+    iana, RegisteredLabelWithPrivate,
+};
+
+use crate::{
+    certificate::parse_attestation_certificate, parse_authenticator_data, webauthn_verify,
+    CoseAlgorithm, VerifyError,
+};
+
+/// An attestation format the verifier supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttestationFormat {
+    /// No attestation at all.
+    None,
+    /// `packed` attestation, either self or basic.
+    Packed,
+}
+
+impl AttestationFormat {
+    const ALL: [Self; 2] = [Self::None, Self::Packed];
+
+    /// The identifier the format is registered with, as the `fmt` of attestation objects
+    /// carries it.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Packed => "packed",
+        }
+    }
+}
+
+impl fmt::Display for AttestationFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Every attestation format [`parse_attestation_object`] accepts.
+pub fn all_supported_formats() -> impl Iterator<Item = AttestationFormat> {
+    AttestationFormat::ALL.into_iter()
+}
+
+/// A parsed attestation statement, per attestation format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttestationStatement {
+    /// `none`: the authenticator provides no attestation.
+    None,
+    /// `packed` self-attestation: `sig` is made with the credential private key over
+    /// `authData || clientDataHash`.
+    Packed { alg: CoseAlgorithm, sig: Vec<u8> },
+    /// `packed` basic attestation: `sig` is made over `authData || clientDataHash` with the
+    /// attestation key of the first of the DER-encoded `x5c` certificates, the others being the
+    /// chain up to (and excluding) the attestation root.
+    PackedX5c {
+        alg: CoseAlgorithm,
+        sig: Vec<u8>,
+        x5c: Vec<Vec<u8>>,
+    },
+}
+
+impl AttestationStatement {
+    /// The format the statement is made in.
+    pub fn format(&self) -> AttestationFormat {
+        match self {
+            Self::None => AttestationFormat::None,
+            Self::Packed { .. } | Self::PackedX5c { .. } => AttestationFormat::Packed,
+        }
+    }
+
+    /// Whether the statement is signed with the credential key itself rather than with an
+    /// attestation key: it then proves nothing about the authenticator model, which anyone can
+    /// claim to be.
+    pub fn is_self_attested(&self) -> bool {
+        matches!(self, Self::Packed { .. })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttestationObject {
+    pub auth_data: Vec<u8>,
+    pub statement: AttestationStatement,
+}
+
+const LOG_TARGET: &str = "verifier::attestation";
+
+fn map_entry<'a>(map: &'a [(Value, Value)], key: &str) -> Option<&'a Value> {
+    map.iter()
+        .find_map(|(k, v)| (k.as_text() == Some(key)).then_some(v))
+}
+
+/// Parses a CBOR-encoded attestation object into its authenticator data and statement.
+///
+/// Only the [supported formats](all_supported_formats) are; any other fails with
+/// [`VerifyError::UnsupportedAttestationFormat`].
+pub fn parse_attestation_object(
+    attestation_object: &[u8],
+) -> Result<AttestationObject, VerifyError> {
+    let attestation_object = from_reader::<Value, _>(attestation_object)
+        .ok()
+        .and_then(|value| value.into_map().ok())
+        .ok_or(VerifyError::ParseAttestationObject)?;
+
+    let fmt = map_entry(&attestation_object, "fmt")
+        .and_then(Value::as_text)
+        .ok_or(VerifyError::ParseAttestationObject)?;
+    let att_stmt = map_entry(&attestation_object, "attStmt")
+        .and_then(Value::as_map)
+        .ok_or(VerifyError::ParseAttestationObject)?;
+    let auth_data = map_entry(&attestation_object, "authData")
+        .and_then(Value::as_bytes)
+        .ok_or(VerifyError::ParseAttestationObject)?
+        .clone();
+
+    let format = all_supported_formats()
+        .find(|format| format.as_str() == fmt)
+        .ok_or(VerifyError::UnsupportedAttestationFormat)?;
+
+    let statement = match format {
+        AttestationFormat::None if att_stmt.is_empty() => AttestationStatement::None,
+        AttestationFormat::None => return Err(VerifyError::ParseAttestationObject),
+        AttestationFormat::Packed => {
+            let alg = map_entry(att_stmt, "alg")
+                .and_then(Value::as_integer)
+                .and_then(|alg| i64::try_from(alg).ok())
+                .ok_or(VerifyError::ParseAttestationObject)?;
+            let sig = map_entry(att_stmt, "sig")
+                .and_then(Value::as_bytes)
+                .ok_or(VerifyError::ParseAttestationObject)?
+                .clone();
+            let alg = CoseAlgorithm::try_from(alg)?;
+
+            match map_entry(att_stmt, "x5c") {
+                None => AttestationStatement::Packed { alg, sig },
+                Some(x5c) => AttestationStatement::PackedX5c {
+                    alg,
+                    sig,
+                    x5c: x5c
+                        .as_array()
+                        .filter(|x5c| !x5c.is_empty())
+                        .and_then(|x5c| {
+                            x5c.iter()
+                                .map(|certificate| certificate.as_bytes().cloned())
+                                .collect()
+                        })
+                        .ok_or(VerifyError::ParseAttestationObject)?,
+                },
+            }
+        }
+    };
+
+    Ok(AttestationObject {
+        auth_data,
+        statement,
+    })
+}
+
+/// Checks the credential public key of the attested credential data of `authenticator_data` is
+/// for one of the `allowed_algs` the relying party offered in the `pubKeyCredParams` of its
+/// creation options, failing with [`VerifyError::AlgorithmNotOffered`] otherwise.
+///
+/// Keys without an `alg` fail with [`VerifyError::MissingAlgorithm`], and authenticator data
+/// without attested credential data with [`VerifyError::ParseAuthenticatorData`].
+pub fn verify_algorithm_offered(
+    authenticator_data: &[u8],
+    allowed_algs: &[iana::Algorithm],
+) -> Result<(), VerifyError> {
+    let key = parse_authenticator_data(authenticator_data)?
+        .attested_credential_data
+        .ok_or(VerifyError::ParseAuthenticatorData)?
+        .credential_public_key;
+    let alg = key.alg.ok_or(VerifyError::MissingAlgorithm)?;
+    if !allowed_algs
+        .iter()
+        .any(|&allowed| alg == RegisteredLabelWithPrivate::Assigned(allowed))
+    {
+        log::error!(target: LOG_TARGET, "The credential algorithm {alg:?} wasn't offered, only {allowed_algs:?}");
+        return Err(VerifyError::AlgorithmNotOffered);
+    }
+    Ok(())
+}
+
+/// Verifies a `packed` basic attestation, as [`AttestationStatement::PackedX5c`] carries it.
+///
+/// The first of the `x5c` certificates must be an X.509 v3 certificate that isn't a CA, and
+/// whose AAGUID extension, if it has one, isn't critical and is the AAGUID of the attested
+/// credential data of `authenticator_data`. Each certificate must be signed by the CA
+/// certificate following it, with ECDSA P-256 and SHA-256, and the key of the first must then
+/// verify `sig` as [`webauthn_verify`] does.
+///
+/// Whether the last certificate chains up to a trusted attestation root is left to the caller,
+/// and so are the validity periods, as there is no clock to check them against here: see
+/// [`verify_packed_x5c_attestation_at`].
+pub fn verify_packed_x5c_attestation(
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    alg: CoseAlgorithm,
+    sig: &[u8],
+    x5c: &[Vec<u8>],
+) -> Result<(), VerifyError> {
+    let leaf = x5c.first().ok_or(VerifyError::ParseAttestationObject)?;
+    let certificate = parse_attestation_certificate(leaf)?;
+    if certificate.version != 3 || certificate.is_ca {
+        log::error!(target: LOG_TARGET, "Attestation certificate is version {} with CA={}", certificate.version, certificate.is_ca);
+        return Err(VerifyError::InvalidAttestationCertificate);
+    }
+
+    if let Some(extension) = certificate.aaguid {
+        if extension.critical {
+            log::error!(target: LOG_TARGET, "The AAGUID extension of the attestation certificate is critical");
+            return Err(VerifyError::InvalidAttestationCertificate);
+        }
+        let aaguid = parse_authenticator_data(authenticator_data)?
+            .attested_credential_data
+            .ok_or(VerifyError::ParseAuthenticatorData)?
+            .aaguid;
+        if extension.aaguid != aaguid {
+            log::error!(target: LOG_TARGET, "Attestation certificate is for AAGUID {:?}, not {:?}", extension.aaguid, aaguid);
+            return Err(VerifyError::AaguidMismatch);
+        }
+    }
+
+    let mut subject = certificate.clone();
+    for issuer in &x5c[1..] {
+        let issuer = parse_attestation_certificate(issuer)?;
+        subject.verify_issued_by(&issuer)?;
+        subject = issuer;
+    }
+
+    match alg {
+        CoseAlgorithm::ES256 => webauthn_verify(
+            authenticator_data,
+            client_data_json,
+            sig,
+            certificate.subject_public_key_info,
+        )
+        .map_err(|e| match e {
+            VerifyError::ExtractPublicKey => VerifyError::ParseAttestationCertificate,
+            e => e,
+        }),
+    }
+}
+
+/// Like [`verify_packed_x5c_attestation`], also checking every certificate of `x5c` is valid
+/// `at` the given time, in seconds since the Unix epoch.
+pub fn verify_packed_x5c_attestation_at(
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    alg: CoseAlgorithm,
+    sig: &[u8],
+    x5c: &[Vec<u8>],
+    at: u64,
+) -> Result<(), VerifyError> {
+    verify_packed_x5c_attestation(authenticator_data, client_data_json, alg, sig, x5c)?;
+
+    x5c.iter().enumerate().try_for_each(|(index, certificate)| {
+        if parse_attestation_certificate(certificate)?.is_valid_at(at) {
+            return Ok(());
+        }
+        log::error!(target: LOG_TARGET, "Certificate {index} of the attestation chain isn't valid at {at}");
+        Err(VerifyError::InvalidAttestationCertificate)
+    })
+}
@@ -0,0 +1,292 @@
+//! Parsing of the authenticator data structure.
+//!
+//! See <https://www.w3.org/TR/webauthn/#sctn-authenticator-data>.
+
+use alloc::vec::Vec;
+#[cfg(feature = "scale")]
+use codec::{Decode, Encode, MaxEncodedLen};
+use coset::{
+    cbor::{de::from_reader, value::Value},
+    AsCborValue, CborSerializable, CoseKey,
+};
+#[cfg(feature = "scale")]
+use scale_info::TypeInfo;
+
+use crate::VerifyError;
+
+/// The flags byte of the authenticator data.
+///
+/// See <https://www.w3.org/TR/webauthn/#authdata-flags>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "scale", derive(Encode, Decode, MaxEncodedLen, TypeInfo))]
+pub struct AuthenticatorDataFlags(u8);
+
+impl AuthenticatorDataFlags {
+    /// User present (`UP`).
+    pub const USER_PRESENT: Self = Self(0b0000_0001);
+    /// User verified (`UV`).
+    pub const USER_VERIFIED: Self = Self(0b0000_0100);
+    /// Backup eligibility (`BE`).
+    pub const BACKUP_ELIGIBLE: Self = Self(0b0000_1000);
+    /// Backup state (`BS`).
+    pub const BACKED_UP: Self = Self(0b0001_0000);
+    /// Attested credential data included (`AT`).
+    pub const ATTESTED_CREDENTIAL_DATA: Self = Self(0b0100_0000);
+    /// Extension data included (`ED`).
+    pub const EXTENSION_DATA: Self = Self(0b1000_0000);
+
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Whether all the flags set in `other` are set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl From<u8> for AuthenticatorDataFlags {
+    fn from(value: u8) -> Self {
+        Self(value)
+    }
+}
+
+impl From<AuthenticatorDataFlags> for u8 {
+    fn from(value: AuthenticatorDataFlags) -> Self {
+        value.0
+    }
+}
+
+/// Checks the backup flags of an assertion are consistent with those seen at registration.
+///
+/// A credential may become backup eligible (`BE`) after registration, but never stop being so,
+/// and a credential can only be backed up (`BS`) if it's backup eligible.
+#[must_use = "an inconsistent backup state is only rejected if the result is checked"]
+pub fn verify_backup_state_consistency(
+    registration_flags: AuthenticatorDataFlags,
+    assertion_flags: AuthenticatorDataFlags,
+) -> Result<(), VerifyError> {
+    use AuthenticatorDataFlags as F;
+
+    let lost_eligibility = registration_flags.contains(F::BACKUP_ELIGIBLE)
+        && !assertion_flags.contains(F::BACKUP_ELIGIBLE);
+    let backed_up_if_ineligible =
+        |flags: F| flags.contains(F::BACKED_UP) && !flags.contains(F::BACKUP_ELIGIBLE);
+
+    if lost_eligibility
+        || backed_up_if_ineligible(registration_flags)
+        || backed_up_if_ineligible(assertion_flags)
+    {
+        return Err(VerifyError::BackupStateInconsistency);
+    }
+    Ok(())
+}
+
+const RP_ID_HASH_LEN: usize = 32;
+const FLAGS_LEN: usize = 1;
+const SIGN_COUNT_LEN: usize = 4;
+const AAGUID_LEN: usize = 16;
+const CREDENTIAL_ID_LENGTH_LEN: usize = 2;
+/// The RP ID hash, flags and signature counter every authenticator data starts with.
+const MIN_LEN: usize = RP_ID_HASH_LEN + FLAGS_LEN + SIGN_COUNT_LEN;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthenticatorData<'a> {
+    pub rp_id_hash: [u8; 32],
+    pub flags: AuthenticatorDataFlags,
+    pub sign_count: u32,
+    pub attested_credential_data: Option<AttestedCredentialData<'a>>,
+    /// The CBOR-encoded extension outputs, if present.
+    pub extensions: Option<&'a [u8]>,
+}
+
+impl AuthenticatorData<'_> {
+    /// The signature counter, as [`sign_count_from_authenticator_data`] reads it.
+    pub fn sign_count(&self) -> u32 {
+        self.sign_count
+    }
+
+    /// Serializes the authenticator data back to the bytes [`parse_authenticator_data`] parses,
+    /// e.g. to build fixtures rather than laying out bytes by hand.
+    ///
+    /// The flags are written as they are: for the result to parse back to `self`, the attested
+    /// credential data (`AT`) and extension data (`ED`) flags must be set exactly when those
+    /// parts are present.
+    ///
+    /// # Panics
+    ///
+    /// If the credential ID is longer than the `u16::MAX` bytes its length prefix can tell.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut encoded = Vec::with_capacity(MIN_LEN);
+        encoded.extend_from_slice(&self.rp_id_hash);
+        encoded.push(self.flags.bits());
+        encoded.extend_from_slice(&self.sign_count.to_be_bytes());
+
+        if let Some(data) = &self.attested_credential_data {
+            let credential_id_len = u16::try_from(data.credential_id.len())
+                .expect("`credential_id` fits its length prefix");
+            encoded.extend_from_slice(&data.aaguid);
+            encoded.extend_from_slice(&credential_id_len.to_be_bytes());
+            encoded.extend_from_slice(data.credential_id);
+            encoded.extend(
+                data.credential_public_key
+                    .clone()
+                    .to_vec()
+                    .expect("Encoding CBOR into a `Vec` is infallible; qed"),
+            );
+        }
+        if let Some(extensions) = self.extensions {
+            encoded.extend_from_slice(extensions);
+        }
+        encoded
+    }
+}
+
+/// See <https://www.w3.org/TR/webauthn/#sctn-attested-credential-data>.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttestedCredentialData<'a> {
+    pub aaguid: [u8; 16],
+    pub credential_id: &'a [u8],
+    pub credential_public_key: CoseKey,
+}
+
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8], VerifyError> {
+    if bytes.len() < len {
+        return Err(VerifyError::ParseAuthenticatorData);
+    }
+    let (taken, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Ok(taken)
+}
+
+/// What [`parse_authenticator_data_partially`] got through before failing, for tools showing
+/// as much of a malformed authenticator data as they can.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PartialAuthenticatorData<'a> {
+    pub rp_id_hash: Option<[u8; 32]>,
+    pub flags: Option<AuthenticatorDataFlags>,
+    pub sign_count: Option<u32>,
+    pub aaguid: Option<[u8; 16]>,
+    pub credential_id: Option<&'a [u8]>,
+}
+
+/// Parses the raw `authenticator_data` bytes, including the attested credential data and the
+/// extensions when their respective flags are set.
+pub fn parse_authenticator_data(
+    authenticator_data: &[u8],
+) -> Result<AuthenticatorData<'_>, VerifyError> {
+    if authenticator_data.len() < MIN_LEN {
+        return Err(VerifyError::ParseAuthenticatorData);
+    }
+    parse(authenticator_data, &mut PartialAuthenticatorData::default())
+}
+
+/// Like [`parse_authenticator_data`], also returning the fields parsed before failing.
+pub fn parse_authenticator_data_partially(
+    authenticator_data: &[u8],
+) -> Result<AuthenticatorData<'_>, (PartialAuthenticatorData<'_>, VerifyError)> {
+    let mut partial = PartialAuthenticatorData::default();
+    parse(authenticator_data, &mut partial).map_err(|e| (partial, e))
+}
+
+/// Parses `authenticator_data`, recording each field in `partial` as it's parsed.
+fn parse<'a>(
+    authenticator_data: &'a [u8],
+    partial: &mut PartialAuthenticatorData<'a>,
+) -> Result<AuthenticatorData<'a>, VerifyError> {
+    let mut rest = authenticator_data;
+
+    let rp_id_hash = *partial.rp_id_hash.insert(
+        take(&mut rest, RP_ID_HASH_LEN)?
+            .try_into()
+            .map_err(|_| VerifyError::ParseAuthenticatorData)?,
+    );
+    let flags = *partial
+        .flags
+        .insert(AuthenticatorDataFlags(take(&mut rest, FLAGS_LEN)?[0]));
+    let sign_count = *partial.sign_count.insert(u32::from_be_bytes(
+        take(&mut rest, SIGN_COUNT_LEN)?
+            .try_into()
+            .map_err(|_| VerifyError::ParseAuthenticatorData)?,
+    ));
+
+    let attested_credential_data =
+        if flags.contains(AuthenticatorDataFlags::ATTESTED_CREDENTIAL_DATA) {
+            let aaguid = *partial.aaguid.insert(
+                take(&mut rest, AAGUID_LEN)?
+                    .try_into()
+                    .map_err(|_| VerifyError::ParseAuthenticatorData)?,
+            );
+            let credential_id_len = u16::from_be_bytes(
+                take(&mut rest, CREDENTIAL_ID_LENGTH_LEN)?
+                    .try_into()
+                    .map_err(|_| VerifyError::ParseAuthenticatorData)?,
+            );
+            let credential_id = *partial
+                .credential_id
+                .insert(take(&mut rest, credential_id_len.into())?);
+
+            // The public key is a CBOR item of unknown length, followed by the extensions (if any),
+            // so it's read off the stream rather than decoded from an exact slice.
+            let value: Value =
+                from_reader(&mut rest).map_err(|_| VerifyError::ParseAuthenticatorData)?;
+            let credential_public_key =
+                CoseKey::from_cbor_value(value).map_err(|_| VerifyError::ParseAuthenticatorData)?;
+
+            Some(AttestedCredentialData {
+                aaguid,
+                credential_id,
+                credential_public_key,
+            })
+        } else {
+            None
+        };
+
+    let extensions = if flags.contains(AuthenticatorDataFlags::EXTENSION_DATA) {
+        Some(core::mem::take(&mut rest))
+    } else {
+        None
+    };
+
+    if !rest.is_empty() {
+        return Err(VerifyError::ParseAuthenticatorData);
+    }
+
+    Ok(AuthenticatorData {
+        rp_id_hash,
+        flags,
+        sign_count,
+        attested_credential_data,
+        extensions,
+    })
+}
+
+/// Why a field couldn't be read from authenticator data without parsing all of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// The authenticator data ends before the field.
+    InsufficientData,
+}
+
+/// The signature counter of `authenticator_data` (big-endian, right after the flags), read
+/// without parsing the rest of it, failing with [`ParseError::InsufficientData`] if it's shorter
+/// than the 37 bytes needed to have one.
+pub fn sign_count_from_authenticator_data(authenticator_data: &[u8]) -> Result<u32, ParseError> {
+    let sign_count = authenticator_data
+        .get(RP_ID_HASH_LEN + FLAGS_LEN..MIN_LEN)
+        .ok_or(ParseError::InsufficientData)?;
+    Ok(u32::from_be_bytes(
+        sign_count
+            .try_into()
+            .expect("The range is `SIGN_COUNT_LEN` bytes; qed"),
+    ))
+}
+
+/// The credential ID in the attested credential data of `authenticator_data`, if it has any:
+/// registrations carry it, assertions usually don't.
+pub fn credential_id_from_authenticator_data(authenticator_data: &[u8]) -> Option<&[u8]> {
+    parse_authenticator_data(authenticator_data)
+        .ok()?
+        .attested_credential_data
+        .map(|data| data.credential_id)
+}
@@ -0,0 +1,86 @@
+//! Caching of verification results, for runtimes that may verify the same assertion again
+//! (e.g. when re-executing a block).
+
+use sha2::{Digest, Sha256};
+
+use crate::{webauthn_verify, VerifyError};
+
+/// Stores the outcome of verifications, keyed by a hash of their inputs.
+pub trait VerificationCache {
+    fn lookup(&self, key: [u8; 32]) -> Option<bool>;
+    fn insert(&mut self, key: [u8; 32], result: bool);
+}
+
+/// A cache that never hits.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopCache;
+
+impl VerificationCache for NoopCache {
+    fn lookup(&self, _: [u8; 32]) -> Option<bool> {
+        None
+    }
+
+    fn insert(&mut self, _: [u8; 32], _: bool) {}
+}
+
+/// An unbounded in-memory cache, mostly meant for tests.
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Clone)]
+pub struct InMemoryCache(std::collections::HashMap<[u8; 32], bool>);
+
+#[cfg(feature = "std")]
+impl VerificationCache for InMemoryCache {
+    fn lookup(&self, key: [u8; 32]) -> Option<bool> {
+        self.0.get(&key).copied()
+    }
+
+    fn insert(&mut self, key: [u8; 32], result: bool) {
+        self.0.insert(key, result);
+    }
+}
+
+/// Like [`webauthn_verify`], but looks the result up in `cache` first, only verifying the
+/// signature on a miss.
+///
+/// The cache key is the SHA-256 of every input, not just of the signature: a signature is only
+/// valid for one message and key, so a result cached for it must not be reused for others.
+/// Failures read from the cache are reported as [`VerifyError::VerifySignature`].
+#[must_use = "the signature is only verified if the result is checked"]
+pub fn webauthn_verify_cached<C: VerificationCache>(
+    cache: &mut C,
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    signature_der: &[u8],
+    credential_public_key_der: &[u8],
+) -> Result<(), VerifyError> {
+    let key = [
+        authenticator_data,
+        client_data_json,
+        signature_der,
+        credential_public_key_der,
+    ]
+    .iter()
+    .fold(Sha256::new(), |hasher, input| {
+        // Length-prefixed, so moving bytes from one input to the next changes the key.
+        hasher
+            .chain_update((input.len() as u64).to_le_bytes())
+            .chain_update(input)
+    })
+    .finalize()
+    .into();
+
+    match cache.lookup(key) {
+        Some(true) => Ok(()),
+        Some(false) => Err(VerifyError::VerifySignature),
+        None => {
+            let result = webauthn_verify(
+                authenticator_data,
+                client_data_json,
+                signature_der,
+                credential_public_key_der,
+            );
+            cache.insert(key, result.is_ok());
+            result
+        }
+    }
+}
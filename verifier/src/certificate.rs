@@ -0,0 +1,286 @@
+//! Just enough X.509 to check a `packed` attestation certificate: its version, basic constraints,
+//! AAGUID extension, public key and validity, and its ECDSA P-256 signature by the next
+//! certificate of the chain. Trusting the attestation root the chain ends at is up to the relying
+//! party.
+//!
+//! See <https://www.w3.org/TR/webauthn/#sctn-packed-attestation-cert-requirements>.
+
+use p256::{
+    ecdsa::{signature::hazmat::PrehashVerifier, DerSignature, VerifyingKey},
+    pkcs8::DecodePublicKey,
+};
+
+use crate::{sha256_fixed, VerifyError};
+
+const LOG_TARGET: &str = "verifier::certificate";
+
+const BOOLEAN: u8 = 0x01;
+const INTEGER: u8 = 0x02;
+const BIT_STRING: u8 = 0x03;
+const OCTET_STRING: u8 = 0x04;
+const OBJECT_IDENTIFIER: u8 = 0x06;
+const UTC_TIME: u8 = 0x17;
+const GENERALIZED_TIME: u8 = 0x18;
+const SEQUENCE: u8 = 0x30;
+/// `[0] EXPLICIT Version`.
+const VERSION: u8 = 0xa0;
+/// `[1] IMPLICIT UniqueIdentifier`.
+const ISSUER_UNIQUE_ID: u8 = 0x81;
+/// `[2] IMPLICIT UniqueIdentifier`.
+const SUBJECT_UNIQUE_ID: u8 = 0x82;
+/// `[3] EXPLICIT Extensions`.
+const EXTENSIONS: u8 = 0xa3;
+
+/// `id-ce-basicConstraints`, 2.5.29.19.
+const BASIC_CONSTRAINTS_OID: &[u8] = &[0x55, 0x1d, 0x13];
+/// `ecdsa-with-SHA256`, 1.2.840.10045.4.3.2.
+const ECDSA_WITH_SHA256_OID: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02];
+/// `id-fido-gen-ce-aaguid`, 1.3.6.1.4.1.45724.1.1.4.
+const AAGUID_OID: &[u8] = &[
+    0x2b, 0x06, 0x01, 0x04, 0x01, 0x82, 0xe5, 0x1c, 0x01, 0x01, 0x04,
+];
+
+/// The parts of an attestation certificate relying parties check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AttestationCertificate<'a> {
+    /// The X.509 version, 3 for certificates with extensions.
+    pub version: u8,
+    /// The DER-encoded `SubjectPublicKeyInfo`.
+    pub subject_public_key_info: &'a [u8],
+    /// Whether the basic constraints extension marks the certificate as a CA.
+    pub is_ca: bool,
+    pub aaguid: Option<AaguidExtension>,
+    /// The validity period, from `notBefore` to `notAfter` (inclusive), in seconds since the
+    /// Unix epoch.
+    valid_from: u64,
+    valid_until: u64,
+    /// The DER-encoded `TBSCertificate` the issuer signed.
+    tbs_certificate: &'a [u8],
+    /// The `signatureAlgorithm` object identifier.
+    signature_algorithm: &'a [u8],
+    /// The `signatureValue` bits, with their leading unused bits count.
+    signature: &'a [u8],
+}
+
+impl AttestationCertificate<'_> {
+    /// Whether the certificate is valid `at` the given time, in seconds since the Unix epoch.
+    pub fn is_valid_at(&self, at: u64) -> bool {
+        (self.valid_from..=self.valid_until).contains(&at)
+    }
+
+    /// Checks the certificate was signed by `issuer`, a CA certificate, with ECDSA P-256 and
+    /// SHA-256, the only signatures supported.
+    pub fn verify_issued_by(&self, issuer: &AttestationCertificate) -> Result<(), VerifyError> {
+        if !issuer.is_ca {
+            log::error!(target: LOG_TARGET, "The issuer of a certificate isn't a CA");
+            return Err(VerifyError::InvalidAttestationCertificate);
+        }
+        if self.signature_algorithm != ECDSA_WITH_SHA256_OID {
+            log::error!(target: LOG_TARGET, "Unsupported certificate signature algorithm {:?}", self.signature_algorithm);
+            return Err(VerifyError::UnsupportedAlgorithm);
+        }
+        let issuer_key = VerifyingKey::from_public_key_der(issuer.subject_public_key_info)
+            .map_err(|_| VerifyError::ParseAttestationCertificate)?;
+        let signature = match self.signature {
+            [0, signature @ ..] => DerSignature::try_from(signature)
+                .map_err(|_| VerifyError::ParseAttestationCertificate)?,
+            _ => return Err(VerifyError::ParseAttestationCertificate),
+        };
+        issuer_key
+            .verify_prehash(&sha256_fixed(self.tbs_certificate), &signature)
+            .map_err(|_| {
+                log::error!(target: LOG_TARGET, "A certificate signature doesn't verify with the key of its issuer");
+                VerifyError::InvalidAttestationCertificate
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct AaguidExtension {
+    pub aaguid: [u8; 16],
+    pub critical: bool,
+}
+
+/// A cursor over DER-encoded elements.
+struct Der<'a>(&'a [u8]);
+
+impl<'a> Der<'a> {
+    fn peek_tag(&self) -> Option<u8> {
+        self.0.first().copied()
+    }
+
+    /// Splits the next element off, returning its tag, its contents, and its whole encoding.
+    fn next(&mut self) -> Option<(u8, &'a [u8], &'a [u8])> {
+        let (&tag, rest) = self.0.split_first()?;
+        // Multi-byte tags aren't used by certificates.
+        if tag & 0x1f == 0x1f {
+            return None;
+        }
+        let (&first, mut rest) = rest.split_first()?;
+        let len = match first {
+            0..=0x7f => first.into(),
+            0x81..=0x84 => {
+                let (len_bytes, after) = rest.split_at_checked(usize::from(first & 0x7f))?;
+                rest = after;
+                len_bytes
+                    .iter()
+                    .fold(0usize, |len, &byte| len << 8 | usize::from(byte))
+            }
+            // Indefinite lengths aren't DER.
+            _ => return None,
+        };
+        let (contents, rest) = rest.split_at_checked(len)?;
+        let encoded_len = self.0.len() - rest.len();
+        let encoded = &self.0[..encoded_len];
+        self.0 = rest;
+        Some((tag, contents, encoded))
+    }
+
+    /// The contents of the next element, which must have the given tag.
+    fn expect(&mut self, tag: u8) -> Option<&'a [u8]> {
+        self.next()
+            .and_then(|(found, contents, _)| (found == tag).then_some(contents))
+    }
+
+    /// The contents of the next element if it has the given tag, leaving it otherwise.
+    fn optional(&mut self, tag: u8) -> Option<&'a [u8]> {
+        if self.peek_tag() == Some(tag) {
+            self.expect(tag)
+        } else {
+            None
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Parses a DER-encoded X.509 certificate.
+pub(crate) fn parse_attestation_certificate(
+    der: &[u8],
+) -> Result<AttestationCertificate<'_>, VerifyError> {
+    parse(der).ok_or(VerifyError::ParseAttestationCertificate)
+}
+
+fn parse(der: &[u8]) -> Option<AttestationCertificate<'_>> {
+    let mut outer = Der(der);
+    let mut certificate = Der(outer.expect(SEQUENCE)?);
+    if !outer.is_empty() {
+        return None;
+    }
+    let (SEQUENCE, tbs, tbs_certificate) = certificate.next()? else {
+        return None;
+    };
+    let mut tbs = Der(tbs);
+    let signature_algorithm = Der(certificate.expect(SEQUENCE)?).expect(OBJECT_IDENTIFIER)?;
+    let signature = certificate.expect(BIT_STRING)?;
+
+    let version = match tbs.optional(VERSION) {
+        Some(version) => match Der(version).expect(INTEGER)? {
+            &[version @ 0..=2] => version + 1,
+            _ => return None,
+        },
+        None => 1,
+    };
+    tbs.expect(INTEGER)?; // serialNumber
+    tbs.expect(SEQUENCE)?; // signature
+    tbs.expect(SEQUENCE)?; // issuer
+    let mut validity = Der(tbs.expect(SEQUENCE)?);
+    let valid_from = validity
+        .next()
+        .and_then(|(tag, time, _)| parse_time(tag, time))?;
+    let valid_until = validity
+        .next()
+        .and_then(|(tag, time, _)| parse_time(tag, time))?;
+    tbs.expect(SEQUENCE)?; // subject
+    let (SEQUENCE, _, subject_public_key_info) = tbs.next()? else {
+        return None;
+    };
+    tbs.optional(ISSUER_UNIQUE_ID);
+    tbs.optional(SUBJECT_UNIQUE_ID);
+
+    let mut parsed = AttestationCertificate {
+        version,
+        subject_public_key_info,
+        is_ca: false,
+        aaguid: None,
+        valid_from,
+        valid_until,
+        tbs_certificate,
+        signature_algorithm,
+        signature,
+    };
+    if let Some(extensions) = tbs.optional(EXTENSIONS) {
+        let mut extensions = Der(Der(extensions).expect(SEQUENCE)?);
+        while !extensions.is_empty() {
+            let mut extension = Der(extensions.expect(SEQUENCE)?);
+            let oid = extension.expect(OBJECT_IDENTIFIER)?;
+            let critical = extension
+                .optional(BOOLEAN)
+                .is_some_and(|critical| critical == [0xff]);
+            let value = extension.expect(OCTET_STRING)?;
+
+            match oid {
+                BASIC_CONSTRAINTS_OID => {
+                    let mut constraints = Der(Der(value).expect(SEQUENCE)?);
+                    parsed.is_ca = constraints.optional(BOOLEAN).is_some_and(|ca| ca == [0xff]);
+                }
+                AAGUID_OID => {
+                    parsed.aaguid = Some(AaguidExtension {
+                        aaguid: Der(value).expect(OCTET_STRING)?.try_into().ok()?,
+                        critical,
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+    tbs.is_empty().then_some(parsed)
+}
+
+/// Reads a `UTCTime` or `GeneralizedTime` as DER encodes them, `YYMMDDHHMMSSZ` and
+/// `YYYYMMDDHHMMSSZ` respectively, into seconds since the Unix epoch.
+fn parse_time(tag: u8, time: &[u8]) -> Option<u64> {
+    fn number(digits: &[u8]) -> Option<u64> {
+        digits.iter().try_fold(0, |n, &digit| {
+            digit
+                .is_ascii_digit()
+                .then(|| n * 10 + u64::from(digit - b'0'))
+        })
+    }
+
+    let (year, rest) = match tag {
+        // Two-digit years stand for 1950 to 2049.
+        UTC_TIME => match number(time.get(..2)?)? {
+            year @ 50.. => (1900 + year, &time[2..]),
+            year => (2000 + year, &time[2..]),
+        },
+        GENERALIZED_TIME => (number(time.get(..4)?)?, &time[4..]),
+        _ => return None,
+    };
+    // `MMDDHHMMSSZ`, DER times being in UTC and without fractional seconds.
+    let (fields, b"Z") = rest.split_at_checked(10)? else {
+        return None;
+    };
+    let field = |index: usize| number(&fields[2 * index..2 * index + 2]);
+    let (month, day, hours, minutes, seconds) =
+        (field(0)?, field(1)?, field(2)?, field(3)?, field(4)?);
+    if year < 1970
+        || !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hours > 23
+        || minutes > 59
+        || seconds > 59
+    {
+        return None;
+    }
+
+    // Days from the civil date, counting years from March so leap days come last.
+    let year = if month <= 2 { year - 1 } else { year };
+    let (era, year_of_era) = (year / 400, year % 400);
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    Some(days * 86_400 + hours * 3_600 + minutes * 60 + seconds)
+}
@@ -0,0 +1,151 @@
+//! Sanity checks on the `clientDataJSON` a response was signed over.
+//!
+//! See <https://www.w3.org/TR/webauthn/#dictdef-collectedclientdata>.
+
+use alloc::vec;
+use base64::prelude::BASE64_URL_SAFE_NO_PAD;
+use subtle::ConstantTimeEq;
+
+use crate::{ClientDataLite, VerifyError, VerifyLimits};
+
+const LOG_TARGET: &str = "verifier::client_data";
+
+/// Members every client data must carry, as JSON strings.
+const REQUIRED_MEMBERS: [&str; 3] = ["type", "challenge", "origin"];
+
+/// Splits a JSON string off `s`, which starts right after its opening quote, returning the
+/// string contents (still escaped) and what follows the closing quote.
+fn split_string(s: &str) -> Option<(&str, &str)> {
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some((&s[..i], &s[i + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Finds the (still escaped) string value of `key` in a JSON object.
+fn string_member<'a>(json: &'a str, key: &str) -> Option<&'a str> {
+    let mut rest = json;
+    while let Some(start) = rest.find('"') {
+        let (string, after) = split_string(&rest[start + 1..])?;
+        rest = after;
+
+        let Some(value) = after.trim_start().strip_prefix(':') else {
+            continue;
+        };
+        let value = value.trim_start();
+        if string == key {
+            return split_string(value.strip_prefix('"')?).map(|(value, _)| value);
+        }
+        rest = value;
+    }
+    None
+}
+
+/// Checks `client_data_json` still looks like what a client would have signed.
+///
+/// It must parse as a [`ClientDataLite`], otherwise this fails with
+/// [`VerifyError::ParseClientData`]. When `expected_client_data_json` is given (e.g. the
+/// bytes received directly from the browser, before any re-serialization), both must be equal
+/// byte-for-byte, otherwise this fails with [`VerifyError::ClientDataModified`]: a proxy
+/// reordering keys or changing whitespace changes the hash the signature covers.
+pub fn check_client_data(
+    client_data_json: &[u8],
+    expected_client_data_json: Option<&[u8]>,
+) -> Result<(), VerifyError> {
+    let mut unescape_buffer = vec![0; client_data_json.len()];
+    ClientDataLite::parse(
+        client_data_json,
+        &mut unescape_buffer,
+        &VerifyLimits::DEFAULT,
+    )?;
+
+    match expected_client_data_json {
+        Some(expected) if expected != client_data_json => {
+            log::error!(
+                target: LOG_TARGET,
+                "Client data differs from the expected bytes; was it re-serialized?"
+            );
+            Err(VerifyError::ClientDataModified)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks the `challenge` of `client_data_json` decodes to `expected_challenge`, failing with
+/// [`VerifyError::ChallengeMismatch`] otherwise.
+///
+/// The decoded challenge is compared in constant time, so a failed check doesn't tell how much
+/// of a guessed challenge was right.
+pub fn verify_challenge(
+    client_data_json: &[u8],
+    expected_challenge: &[u8],
+) -> Result<(), VerifyError> {
+    let mut unescape_buffer = vec![0; client_data_json.len()];
+    let client_data = ClientDataLite::parse(
+        client_data_json,
+        &mut unescape_buffer,
+        &VerifyLimits::DEFAULT,
+    )?;
+
+    let mut challenge = vec![0; client_data.challenge.len()];
+    let len = base64::decode_engine_slice(
+        client_data.challenge,
+        &mut challenge,
+        &BASE64_URL_SAFE_NO_PAD,
+    )
+    .map_err(|_| {
+        log::error!(target: LOG_TARGET, "Client data challenge isn't base64url");
+        VerifyError::ChallengeMismatch
+    })?;
+
+    if bool::from(challenge[..len].ct_eq(expected_challenge)) {
+        Ok(())
+    } else {
+        log::error!(target: LOG_TARGET, "Client data challenge isn't the expected one");
+        Err(VerifyError::ChallengeMismatch)
+    }
+}
+
+/// Checks `client_data_json` is serialized as clients do, a stricter check than
+/// [`check_client_data`] for relying parties that want to catch broken client data early.
+///
+/// The `type`, `challenge` and `origin` members must be present as strings, and come first, in
+/// this order and without whitespace, as the [serialization] of the client data mandates.
+/// Otherwise this fails with [`VerifyError::ClientDataMalformed`].
+///
+/// [serialization]: https://www.w3.org/TR/webauthn/#clientdatajson-serialization
+pub fn canonical_client_data(client_data_json: &[u8]) -> Result<(), VerifyError> {
+    let json =
+        core::str::from_utf8(client_data_json).map_err(|_| VerifyError::ClientDataMalformed)?;
+
+    let mut rest = json.strip_prefix('{');
+    for (i, member) in REQUIRED_MEMBERS.iter().enumerate() {
+        let Some(value) = string_member(json, member) else {
+            log::error!(target: LOG_TARGET, "Client data has no `{member}` string member");
+            return Err(VerifyError::ClientDataMalformed);
+        };
+        rest = rest
+            .and_then(|rest| {
+                if i == 0 {
+                    Some(rest)
+                } else {
+                    rest.strip_prefix(',')
+                }
+            })
+            .and_then(|rest| rest.strip_prefix('"')?.strip_prefix(member))
+            .and_then(|rest| rest.strip_prefix("\":\"")?.strip_prefix(value))
+            .and_then(|rest| rest.strip_prefix('"'));
+    }
+
+    if rest.is_none() {
+        log::error!(target: LOG_TARGET, "Client data doesn't start with the `type`, `challenge` and `origin` members, in order");
+        return Err(VerifyError::ClientDataMalformed);
+    }
+    Ok(())
+}
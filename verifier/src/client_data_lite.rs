@@ -0,0 +1,314 @@
+//! A borrowed view of the `clientDataJSON` members relying parties check, parsed without
+//! allocating, for `no_std` runtimes.
+//!
+//! See <https://www.w3.org/TR/webauthn/#dictdef-collectedclientdata>.
+
+use core::str;
+
+use crate::{VerifyError, VerifyLimits};
+
+const LOG_TARGET: &str = "verifier::client_data_lite";
+
+/// Deepest nesting of the unknown members skipped over, bounding the parser's recursion.
+const MAX_DEPTH: usize = 16;
+
+/// The `type`, `challenge`, `origin` and `crossOrigin` members of a client data.
+///
+/// Any other member (`topOrigin`, the `other_keys_can_be_added_here` Chrome sometimes adds, ...)
+/// is skipped, as the spec requires relying parties to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientDataLite<'a> {
+    pub r#type: &'a str,
+    /// Still base64url-encoded.
+    pub challenge: &'a str,
+    pub origin: &'a str,
+    /// `false` when absent, as clients predating the member send it.
+    pub cross_origin: bool,
+}
+
+impl<'a> ClientDataLite<'a> {
+    /// Parses `client_data_json`, a JSON object with `type`, `challenge` and `origin` string
+    /// members and an optional `crossOrigin` boolean one, each at most once.
+    ///
+    /// Strings without escape sequences are borrowed from `client_data_json`, the others are
+    /// unescaped into `unescape_buffer`; a buffer as long as `client_data_json` always suffices.
+    /// Fails with [`VerifyError::InputTooLarge`] when `client_data_json` exceeds `limits`, and
+    /// with [`VerifyError::ParseClientData`] when it can't be parsed.
+    pub fn parse(
+        client_data_json: &'a [u8],
+        unescape_buffer: &'a mut [u8],
+        limits: &VerifyLimits,
+    ) -> Result<Self, VerifyError> {
+        limits.check(&[], client_data_json)?;
+        if str::from_utf8(client_data_json).is_err() {
+            log::error!(target: LOG_TARGET, "Client data isn't UTF-8");
+            return Err(VerifyError::ParseClientData);
+        }
+
+        let mut parser = Parser {
+            json: client_data_json,
+            pos: 0,
+            unescape_buffer,
+        };
+        let Some(members) = parser.client_data() else {
+            log::error!(target: LOG_TARGET, "Client data isn't valid JSON, or has a duplicated or mistyped member, near byte {}", parser.pos);
+            return Err(VerifyError::ParseClientData);
+        };
+
+        match members {
+            Members {
+                r#type: Some(r#type),
+                challenge: Some(challenge),
+                origin: Some(origin),
+                cross_origin,
+            } => Ok(Self {
+                r#type,
+                challenge,
+                origin,
+                cross_origin: cross_origin.unwrap_or_default(),
+            }),
+            _ => {
+                log::error!(target: LOG_TARGET, "Client data is missing a `type`, `challenge` or `origin` member");
+                Err(VerifyError::ParseClientData)
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Members<'a> {
+    r#type: Option<&'a str>,
+    challenge: Option<&'a str>,
+    origin: Option<&'a str>,
+    cross_origin: Option<bool>,
+}
+
+/// Sets a member seen for the first time, failing on duplicates.
+fn set_once<T>(member: &mut Option<T>, value: T) -> Option<()> {
+    member.replace(value).is_none().then_some(())
+}
+
+struct Parser<'a> {
+    json: &'a [u8],
+    pos: usize,
+    unescape_buffer: &'a mut [u8],
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.json.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        let hit = self.peek() == Some(byte);
+        if hit {
+            self.pos += 1;
+        }
+        hit
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        self.skip_whitespace();
+        self.eat(byte).then_some(())
+    }
+
+    fn client_data(&mut self) -> Option<Members<'a>> {
+        let mut members = Members::default();
+        self.expect(b'{')?;
+        self.skip_whitespace();
+        if !self.eat(b'}') {
+            loop {
+                let key = self.string()?;
+                self.expect(b':')?;
+                self.skip_whitespace();
+                match key {
+                    "type" => set_once(&mut members.r#type, self.string()?)?,
+                    "challenge" => set_once(&mut members.challenge, self.string()?)?,
+                    "origin" => set_once(&mut members.origin, self.string()?)?,
+                    "crossOrigin" => set_once(&mut members.cross_origin, self.boolean()?)?,
+                    _ => self.skip_value(0)?,
+                }
+                self.skip_whitespace();
+                if self.eat(b'}') {
+                    break;
+                }
+                self.expect(b',')?;
+            }
+        }
+        self.skip_whitespace();
+        (self.pos == self.json.len()).then_some(members)
+    }
+
+    /// Splits the next string off, returning its contents, still escaped, and whether it has any
+    /// escape sequence.
+    fn raw_string(&mut self) -> Option<(&'a [u8], bool)> {
+        self.expect(b'"')?;
+        let start = self.pos;
+        let mut escaped = false;
+        loop {
+            match self.peek()? {
+                b'"' => break,
+                b'\\' => {
+                    escaped = true;
+                    self.pos += 1;
+                    match self.peek()? {
+                        b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't' => self.pos += 1,
+                        b'u' => {
+                            hex4(self.json.get(self.pos + 1..self.pos + 5)?)?;
+                            self.pos += 5;
+                        }
+                        _ => return None,
+                    }
+                }
+                // Control characters must be escaped.
+                0..=0x1f => return None,
+                _ => self.pos += 1,
+            }
+        }
+        let raw = &self.json[start..self.pos];
+        self.pos += 1;
+        Some((raw, escaped))
+    }
+
+    fn string(&mut self) -> Option<&'a str> {
+        let (raw, escaped) = self.raw_string()?;
+        if !escaped {
+            return str::from_utf8(raw).ok();
+        }
+
+        let len = unescape(raw, self.unescape_buffer)?;
+        let (unescaped, rest) = core::mem::take(&mut self.unescape_buffer).split_at_mut(len);
+        self.unescape_buffer = rest;
+        let unescaped: &'a [u8] = unescaped;
+        str::from_utf8(unescaped).ok()
+    }
+
+    fn boolean(&mut self) -> Option<bool> {
+        if self.literal(b"true").is_some() {
+            Some(true)
+        } else {
+            self.literal(b"false").map(|()| false)
+        }
+    }
+
+    fn literal(&mut self, literal: &[u8]) -> Option<()> {
+        self.json[self.pos..].starts_with(literal).then(|| {
+            self.pos += literal.len();
+        })
+    }
+
+    fn digits(&mut self) -> Option<()> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.pos += 1;
+        }
+        (self.pos > start).then_some(())
+    }
+
+    fn number(&mut self) -> Option<()> {
+        self.eat(b'-');
+        if !self.eat(b'0') {
+            self.digits()?;
+        }
+        if self.eat(b'.') {
+            self.digits()?;
+        }
+        if self.eat(b'e') || self.eat(b'E') {
+            if !self.eat(b'+') {
+                self.eat(b'-');
+            }
+            self.digits()?;
+        }
+        Some(())
+    }
+
+    fn skip_value(&mut self, depth: usize) -> Option<()> {
+        self.skip_whitespace();
+        match self.peek()? {
+            b'"' => self.raw_string().map(drop),
+            b'{' => self.skip_container(b'}', true, depth),
+            b'[' => self.skip_container(b']', false, depth),
+            b't' => self.literal(b"true"),
+            b'f' => self.literal(b"false"),
+            b'n' => self.literal(b"null"),
+            b'-' | b'0'..=b'9' => self.number(),
+            _ => None,
+        }
+    }
+
+    fn skip_container(&mut self, close: u8, is_object: bool, depth: usize) -> Option<()> {
+        if depth >= MAX_DEPTH {
+            return None;
+        }
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.eat(close) {
+            return Some(());
+        }
+        loop {
+            if is_object {
+                self.raw_string()?;
+                self.expect(b':')?;
+            }
+            self.skip_value(depth + 1)?;
+            self.skip_whitespace();
+            if self.eat(close) {
+                return Some(());
+            }
+            self.expect(b',')?;
+        }
+    }
+}
+
+/// Parses four hex digits.
+fn hex4(digits: &[u8]) -> Option<u32> {
+    digits.iter().try_fold(0, |code, &digit| {
+        Some(code << 4 | char::from(digit).to_digit(16)?)
+    })
+}
+
+/// Unescapes the contents of a JSON string into `out`, returning the length written.
+fn unescape(raw: &[u8], out: &mut [u8]) -> Option<usize> {
+    let mut len = 0;
+    let mut i = 0;
+    while i < raw.len() {
+        let mut utf8 = [0u8; 4];
+        let bytes: &[u8] = if raw[i] != b'\\' {
+            i += 1;
+            &raw[i - 1..i]
+        } else {
+            i += 2;
+            match *raw.get(i - 1)? {
+                b'b' => b"\x08",
+                b'f' => b"\x0c",
+                b'n' => b"\n",
+                b'r' => b"\r",
+                b't' => b"\t",
+                b'u' => {
+                    let mut code = hex4(raw.get(i..i + 4)?)?;
+                    i += 4;
+                    if (0xd800..0xdc00).contains(&code) {
+                        let low = hex4(raw.get(i..i + 6)?.strip_prefix(b"\\u")?)?;
+                        if !(0xdc00..0xe000).contains(&low) {
+                            return None;
+                        }
+                        i += 6;
+                        code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                    }
+                    char::from_u32(code)?.encode_utf8(&mut utf8).as_bytes()
+                }
+                // `"`, `\` and `/` stand for themselves.
+                _ => &raw[i - 1..i],
+            }
+        };
+        out.get_mut(len..len + bytes.len())?.copy_from_slice(bytes);
+        len += bytes.len();
+    }
+    Some(len)
+}
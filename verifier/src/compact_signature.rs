@@ -0,0 +1,41 @@
+//! Conversion of compact (`r || s`) ECDSA signatures to the DER encoding WebAuthn uses, for
+//! signatures made by hardware wallets or custom authenticators outside of WebAuthn.
+
+use alloc::vec::Vec;
+
+use p256::{ecdsa::Signature, FieldBytes, NonZeroScalar};
+
+const LOG_TARGET: &str = "verifier::compact_signature";
+
+/// Why a compact signature isn't a valid P-256 ECDSA signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureError {
+    /// `r` is zero, or not below the order of the curve.
+    InvalidR,
+    /// `s` is zero, or not below the order of the curve.
+    InvalidS,
+}
+
+fn scalar(bytes: &[u8]) -> Option<NonZeroScalar> {
+    NonZeroScalar::from_repr(FieldBytes::clone_from_slice(bytes)).into()
+}
+
+/// Encodes the compact P-256 signature `r || s` (32 big-endian bytes each) as DER, the encoding
+/// of the `signature` of an assertion.
+///
+/// `s` is kept as is, whether or not it's normalized to its low form.
+pub fn compact_signature_to_der(compact: &[u8; 64]) -> Result<Vec<u8>, SignatureError> {
+    let (r, s) = compact.split_at(32);
+    let r = scalar(r).ok_or_else(|| {
+        log::error!(target: LOG_TARGET, "The r of the compact signature is out of range");
+        SignatureError::InvalidR
+    })?;
+    let s = scalar(s).ok_or_else(|| {
+        log::error!(target: LOG_TARGET, "The s of the compact signature is out of range");
+        SignatureError::InvalidS
+    })?;
+
+    let signature =
+        Signature::from_scalars(r, s).expect("Non-zero scalars make a valid signature; qed");
+    Ok(signature.to_der().as_bytes().to_vec())
+}
@@ -0,0 +1,186 @@
+use alloc::vec::Vec;
+use coset::{iana, CoseKey, CoseKeyBuilder, KeyType, Label, RegisteredLabelWithPrivate};
+use p256::{
+    elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint},
+    EncodedPoint, PublicKey,
+};
+
+use crate::VerifyError;
+
+const LOG_TARGET: &str = "verifier::cose";
+
+/// `SubjectPublicKeyInfo` header of a DER-encoded P-256 public key, up to the point bytes.
+pub(crate) const P256_SPKI_HEADER: [u8; 26] = [
+    0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a,
+    0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00,
+];
+
+/// Signature algorithms the verifier knows how to dispatch on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoseAlgorithm {
+    /// ECDSA using the P-256 curve and SHA-256. COSE keys and ids only dispatch to it with the
+    /// `es256` feature.
+    ES256,
+}
+
+impl CoseAlgorithm {
+    /// The algorithm identifier, as registered in the IANA COSE Algorithms registry.
+    pub fn cose_id(&self) -> i64 {
+        match self {
+            CoseAlgorithm::ES256 => iana::Algorithm::ES256 as i64,
+        }
+    }
+}
+
+impl TryFrom<i64> for CoseAlgorithm {
+    type Error = VerifyError;
+
+    fn try_from(cose_id: i64) -> Result<Self, Self::Error> {
+        match cose_id {
+            #[cfg(feature = "es256")]
+            id if id == iana::Algorithm::ES256 as i64 => Ok(CoseAlgorithm::ES256),
+            _ => Err(VerifyError::UnsupportedAlgorithm),
+        }
+    }
+}
+
+/// What to do with a COSE key that doesn't carry an `alg` parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissingAlgorithmPolicy {
+    /// Fail with [`VerifyError::MissingAlgorithm`].
+    #[default]
+    Reject,
+    /// Infer the algorithm from the key type and curve, logging a warning.
+    InferFromKeyType,
+}
+
+/// Reads the `alg` parameter of a COSE key.
+///
+/// This is the single source of truth for algorithm dispatch: keys without an `alg` fail with
+/// [`VerifyError::MissingAlgorithm`], and algorithms the verifier doesn't support fail with
+/// [`VerifyError::UnsupportedAlgorithm`], as do those whose feature is disabled.
+pub fn cose_key_algorithm(key: &CoseKey) -> Result<CoseAlgorithm, VerifyError> {
+    match &key.alg {
+        #[cfg(feature = "es256")]
+        Some(RegisteredLabelWithPrivate::Assigned(iana::Algorithm::ES256)) => {
+            Ok(CoseAlgorithm::ES256)
+        }
+        Some(_) => Err(VerifyError::UnsupportedAlgorithm),
+        None => Err(VerifyError::MissingAlgorithm),
+    }
+}
+
+/// Like [`cose_key_algorithm`], but applies `policy` to keys without an `alg` parameter.
+pub fn cose_key_algorithm_with_policy(
+    key: &CoseKey,
+    policy: MissingAlgorithmPolicy,
+) -> Result<CoseAlgorithm, VerifyError> {
+    match (cose_key_algorithm(key), policy) {
+        (Err(VerifyError::MissingAlgorithm), MissingAlgorithmPolicy::InferFromKeyType) => {
+            let algorithm = infer_algorithm(key).ok_or(VerifyError::MissingAlgorithm)?;
+            log::warn!(
+                target: LOG_TARGET,
+                "COSE key has no `alg` parameter, inferred {:?} from its key type and curve",
+                algorithm
+            );
+            Ok(algorithm)
+        }
+        (result, _) => result,
+    }
+}
+
+fn infer_algorithm(key: &CoseKey) -> Option<CoseAlgorithm> {
+    (cfg!(feature = "es256") && is_ec2_p256(key)).then_some(CoseAlgorithm::ES256)
+}
+
+/// Whether `key` is an EC2 key on the P-256 curve, the only kind ES256 allows.
+fn is_ec2_p256(key: &CoseKey) -> bool {
+    let curve = key.params.iter().find_map(|(label, value)| {
+        (*label == Label::Int(iana::Ec2KeyParameter::Crv as i64))
+            .then(|| value.as_integer())
+            .flatten()
+    });
+
+    key.kty == KeyType::Assigned(iana::KeyType::EC2)
+        && curve == Some((iana::EllipticCurve::P_256 as i64).into())
+}
+
+fn ec2_param(key: &CoseKey, param: iana::Ec2KeyParameter) -> Option<&[u8]> {
+    key.params.iter().find_map(|(label, value)| {
+        (*label == Label::Int(param as i64))
+            .then(|| value.as_bytes())
+            .flatten()
+            .map(Vec::as_slice)
+    })
+}
+
+/// Converts a COSE public key into the DER-encoded `SubjectPublicKeyInfo` form
+/// [`webauthn_verify`](crate::webauthn_verify) expects.
+///
+/// The point is checked to be on the curve before being encoded. Keys without an `alg`
+/// parameter are rejected; see [`cose_key_to_der_with_policy`] to infer it instead.
+pub fn cose_key_to_der(key: &CoseKey) -> Result<Vec<u8>, VerifyError> {
+    cose_key_to_der_with_policy(key, MissingAlgorithmPolicy::Reject)
+}
+
+/// Like [`cose_key_to_der`], but applies `policy` to keys without an `alg` parameter.
+pub fn cose_key_to_der_with_policy(
+    key: &CoseKey,
+    policy: MissingAlgorithmPolicy,
+) -> Result<Vec<u8>, VerifyError> {
+    let point = es256_point(key, policy)?;
+    decode_point(&point)?;
+
+    Ok([&P256_SPKI_HEADER[..], point.as_bytes()].concat())
+}
+
+/// Decodes an ES256 COSE public key straight from its coordinates, without the DER encoding
+/// [`cose_key_to_der`] produces only to have it parsed again.
+///
+/// Keys without an `alg` parameter are rejected, like in [`cose_key_to_der`].
+pub fn cose_key_to_public_key(key: &CoseKey) -> Result<PublicKey, VerifyError> {
+    decode_point(&es256_point(key, MissingAlgorithmPolicy::Reject)?)
+}
+
+/// Encodes a P-256 public key as the ES256 COSE key authenticators attest it as, the inverse of
+/// [`cose_key_to_public_key`].
+pub fn cose_key_from_public_key(key: &PublicKey) -> CoseKey {
+    let point = key.to_encoded_point(false);
+    // Uncompressed points of keys on the curve always have both coordinates.
+    let (x, y) = (
+        point.x().expect("uncompressed"),
+        point.y().expect("uncompressed"),
+    );
+    CoseKeyBuilder::new_ec2_pub_key(iana::EllipticCurve::P_256, x.to_vec(), y.to_vec())
+        .algorithm(iana::Algorithm::ES256)
+        .build()
+}
+
+fn es256_point(key: &CoseKey, policy: MissingAlgorithmPolicy) -> Result<EncodedPoint, VerifyError> {
+    match cose_key_algorithm_with_policy(key, policy)? {
+        CoseAlgorithm::ES256 => {
+            if !is_ec2_p256(key) {
+                return Err(VerifyError::ExtractPublicKey);
+            }
+
+            let x =
+                ec2_param(key, iana::Ec2KeyParameter::X).ok_or(VerifyError::ExtractPublicKey)?;
+            let y =
+                ec2_param(key, iana::Ec2KeyParameter::Y).ok_or(VerifyError::ExtractPublicKey)?;
+            if x.len() != 32 || y.len() != 32 {
+                return Err(VerifyError::ExtractPublicKey);
+            }
+
+            Ok(EncodedPoint::from_affine_coordinates(
+                x.into(),
+                y.into(),
+                false,
+            ))
+        }
+    }
+}
+
+/// Checks the point is on the curve.
+fn decode_point(point: &EncodedPoint) -> Result<PublicKey, VerifyError> {
+    Option::from(PublicKey::from_encoded_point(point)).ok_or(VerifyError::ExtractPublicKey)
+}
@@ -0,0 +1,117 @@
+//! Assertions straight from CTAP2 authenticators, for native clients talking to security keys
+//! without a browser in between.
+//!
+//! See <https://fidoalliance.org/specs/fido-v2.1-ps-20210615/fido-client-to-authenticator-protocol-v2.1-ps-20210615.html#authenticatorGetAssertion>.
+
+use alloc::vec::Vec;
+use coset::cbor::{de::from_reader, value::Value};
+use p256::{elliptic_curve::PublicKey, pkcs8::DecodePublicKey, NistP256};
+
+use crate::{verify_with_client_data_hash, VerifyError, VerifyLimits};
+
+const LOG_TARGET: &str = "verifier::ctap2";
+
+/// The status byte CTAP HID prepends to successful responses.
+const CTAP2_OK: u8 = 0x00;
+
+// Keys of the `authenticatorGetAssertion` response map.
+const CREDENTIAL: i128 = 0x01;
+const AUTH_DATA: i128 = 0x02;
+const SIGNATURE: i128 = 0x03;
+const USER: i128 = 0x04;
+
+/// A decoded `authenticatorGetAssertion` response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ctap2Assertion {
+    /// The `id` of the `credential` descriptor, which authenticators may omit when the request
+    /// allowed a single credential.
+    pub credential_id: Option<Vec<u8>>,
+    pub auth_data: Vec<u8>,
+    /// DER-encoded, for ES256.
+    pub signature: Vec<u8>,
+    /// The `id` of the `user` entity, only returned for discoverable credentials.
+    pub user_id: Option<Vec<u8>>,
+}
+
+fn map_entry(map: &[(Value, Value)], key: i128) -> Option<&Value> {
+    map.iter()
+        .find_map(|(k, v)| (k.as_integer().map(i128::from) == Some(key)).then_some(v))
+}
+
+/// The `id` of a `credential` descriptor or `user` entity.
+fn id_of(map: &[(Value, Value)], key: i128) -> Result<Option<Vec<u8>>, VerifyError> {
+    map_entry(map, key)
+        .map(|entity| {
+            entity
+                .as_map()?
+                .iter()
+                .find_map(|(k, v)| (k.as_text() == Some("id")).then_some(v))?
+                .as_bytes()
+                .cloned()
+        })
+        .map(|id| id.ok_or(VerifyError::ParseCtap2Response))
+        .transpose()
+}
+
+/// Decodes the CBOR response of `authenticatorGetAssertion`, with or without the leading
+/// `CTAP2_OK` status byte of CTAP HID.
+///
+/// Fails with [`VerifyError::ParseCtap2Response`] when the response isn't a CBOR map with
+/// `authData` and `signature` byte strings, or is an error status.
+pub fn parse_ctap2_assertion(response_cbor: &[u8]) -> Result<Ctap2Assertion, VerifyError> {
+    // A CBOR map never starts with a zero byte, so a leading one can only be the status.
+    let response_cbor = response_cbor
+        .strip_prefix(&[CTAP2_OK])
+        .unwrap_or(response_cbor);
+
+    let response = from_reader::<Value, _>(response_cbor)
+        .ok()
+        .and_then(|value| value.into_map().ok())
+        .ok_or_else(|| {
+            log::error!(target: LOG_TARGET, "CTAP2 response isn't a CBOR map, or has an error status");
+            VerifyError::ParseCtap2Response
+        })?;
+    let bytes_at = |key| {
+        map_entry(&response, key)
+            .and_then(Value::as_bytes)
+            .cloned()
+            .ok_or_else(|| {
+                log::error!(target: LOG_TARGET, "CTAP2 response has no {key:#04x} byte string");
+                VerifyError::ParseCtap2Response
+            })
+    };
+
+    Ok(Ctap2Assertion {
+        credential_id: id_of(&response, CREDENTIAL)?,
+        auth_data: bytes_at(AUTH_DATA)?,
+        signature: bytes_at(SIGNATURE)?,
+        user_id: id_of(&response, USER)?,
+    })
+}
+
+/// Verifies the signature of an `authenticatorGetAssertion` response, returning the decoded
+/// response for the caller to check the credential, user and authenticator data of.
+///
+/// `client_data_hash` is the hash the client sent the authenticator in the request. As for
+/// [`webauthn_verify`](crate::webauthn_verify), `credential_public_key_der` is the DER-encoded
+/// P-256 key of the credential, and the authenticator data can't exceed
+/// [`VerifyLimits::DEFAULT`].
+#[must_use = "the signature is only verified if the result is checked"]
+pub fn verify_ctap2_assertion(
+    response_cbor: &[u8],
+    client_data_hash: &[u8; 32],
+    credential_public_key_der: &[u8],
+) -> Result<Ctap2Assertion, VerifyError> {
+    let assertion = parse_ctap2_assertion(response_cbor)?;
+    VerifyLimits::DEFAULT.check(&assertion.auth_data, &[])?;
+
+    let public_key: PublicKey<NistP256> =
+        DecodePublicKey::from_public_key_der(credential_public_key_der)?;
+    verify_with_client_data_hash(
+        &assertion.auth_data,
+        client_data_hash,
+        &assertion.signature,
+        public_key,
+    )?;
+    Ok(assertion)
+}
@@ -0,0 +1,60 @@
+//! Typed access to the extension outputs of an authenticator data.
+//!
+//! See <https://www.w3.org/TR/webauthn/#sctn-extensions>.
+
+use coset::cbor::{de::from_reader, value::Value};
+
+use crate::AuthenticatorData;
+
+/// Identifier of the PRF extension.
+const PRF: &str = "prf";
+
+/// The outputs of the pseudo-random function of a credential, evaluated on the inputs the
+/// relying party asked for, e.g. to derive encryption keys.
+///
+/// See <https://www.w3.org/TR/webauthn-3/#dictdef-authenticationextensionsprfvalues>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrfOutputs {
+    pub first: [u8; 32],
+    /// Only there when the relying party gave a second input.
+    pub second: Option<[u8; 32]>,
+}
+
+/// The member `key` of a CBOR map with text keys.
+fn member<'v>(map: &'v Value, key: &str) -> Option<&'v Value> {
+    map.as_map()?
+        .iter()
+        .find(|(k, _)| k.as_text() == Some(key))
+        .map(|(_, value)| value)
+}
+
+fn output(value: &Value) -> Option<[u8; 32]> {
+    value.as_bytes()?.as_slice().try_into().ok()
+}
+
+impl AuthenticatorData<'_> {
+    /// The evaluated outputs of the `prf` extension, as `{"prf": {"results": {"first": h'..',
+    /// "second": h'..'}}}` in the extension outputs.
+    ///
+    /// `None` when the authenticator data has no such output, or when it's malformed: the
+    /// extension outputs aren't a single CBOR map, or the outputs aren't 32-byte strings. Clients
+    /// usually return PRF results in the client extension outputs instead, having decrypted the
+    /// `hmac-secret` output of the authenticator, so this finds them in authenticator data of
+    /// authenticators (or bridges) putting the evaluated outputs there.
+    pub fn prf_results(&self) -> Option<PrfOutputs> {
+        let mut bytes = self.extensions?;
+        let extensions: Value = from_reader(&mut bytes).ok()?;
+        if !bytes.is_empty() {
+            return None;
+        }
+
+        let results = member(member(&extensions, PRF)?, "results")?;
+        Some(PrfOutputs {
+            first: output(member(results, "first")?)?,
+            second: match member(results, "second") {
+                Some(second) => Some(output(second)?),
+                None => None,
+            },
+        })
+    }
+}
@@ -0,0 +1,56 @@
+//! Public keys stored as JSON Web Keys, as `SubtleCrypto.exportKey("jwk", ..)` exports them.
+//!
+//! See <https://www.rfc-editor.org/rfc/rfc7518#section-6.2>.
+
+use base64::prelude::BASE64_URL_SAFE_NO_PAD;
+use p256::{
+    elliptic_curve::{sec1::FromEncodedPoint, PublicKey},
+    EncodedPoint, FieldBytes, NistP256,
+};
+use serde::Deserialize;
+
+use crate::VerifyError;
+
+const LOG_TARGET: &str = "verifier::jwk";
+
+/// The members of an EC JWK a public key is made of. Other members, like the `ext` and `key_ops`
+/// of browser exports, are ignored.
+#[derive(Deserialize)]
+struct EcJwk<'a> {
+    kty: &'a str,
+    crv: &'a str,
+    x: &'a str,
+    y: &'a str,
+}
+
+/// Decodes a base64url-encoded coordinate of a P-256 point.
+fn coordinate(encoded: &str) -> Option<FieldBytes> {
+    // `base64` wants room for the padding bits of the last quantum too.
+    let mut coordinate = [0; 33];
+    let len =
+        base64::decode_engine_slice(encoded, &mut coordinate, &BASE64_URL_SAFE_NO_PAD).ok()?;
+    (len == 32).then(|| FieldBytes::clone_from_slice(&coordinate[..32]))
+}
+
+/// Parses an EC JWK of a P-256 public key (`"kty": "EC"`, `"crv": "P-256"`), checking its point
+/// is on the curve.
+pub(crate) fn public_key_from_jwk(jwk: &str) -> Result<PublicKey<NistP256>, VerifyError> {
+    let jwk: EcJwk = serde_json::from_str(jwk).map_err(|e| {
+        log::error!(target: LOG_TARGET, "The JWK can't be parsed, reason={}", e);
+        VerifyError::ExtractPublicKey
+    })?;
+    if jwk.kty != "EC" || jwk.crv != "P-256" {
+        log::error!(target: LOG_TARGET, "Unsupported JWK, kty={} crv={}", jwk.kty, jwk.crv);
+        return Err(VerifyError::ExtractPublicKey);
+    }
+
+    let (Some(x), Some(y)) = (coordinate(jwk.x), coordinate(jwk.y)) else {
+        log::error!(target: LOG_TARGET, "The JWK coordinates aren't 32 bytes of base64url");
+        return Err(VerifyError::ExtractPublicKey);
+    };
+    let point = EncodedPoint::from_affine_coordinates(&x, &y, false);
+    Option::from(PublicKey::from_encoded_point(&point)).ok_or_else(|| {
+        log::error!(target: LOG_TARGET, "The JWK point isn't on the curve");
+        VerifyError::ExtractPublicKey
+    })
+}
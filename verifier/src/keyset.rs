@@ -0,0 +1,62 @@
+//! Verification against a set of candidate public keys, e.g. every passkey of an account, without
+//! revealing through timing which one signed.
+
+use p256::{
+    ecdsa::{signature::hazmat::PrehashVerifier, DerSignature, VerifyingKey},
+    elliptic_curve::PublicKey,
+    pkcs8::DecodePublicKey,
+    NistP256,
+};
+use subtle::{Choice, ConditionallySelectable};
+
+use crate::{client_data_hash, sha256_two_part, VerifyError, VerifyLimits};
+
+const LOG_TARGET: &str = "verifier::keyset";
+
+/// `a` when `condition` is set, `b` otherwise, without branching on `condition`.
+pub fn ct_select<T: ConditionallySelectable>(condition: Choice, a: T, b: T) -> T {
+    T::conditional_select(&b, &a, condition)
+}
+
+/// Like [`webauthn_verify`](crate::webauthn_verify), against each of `credential_public_keys_der`,
+/// returning the index of the first one the signature verifies with.
+///
+/// Every key is tried, even after a match, and the index is selected with [`ct_select`], so the
+/// time taken doesn't depend on which key matched: for accounts with several devices, that would
+/// reveal which one was used. Keys that can't be decoded never match.
+#[must_use = "the signature is only verified if the result is checked"]
+pub fn webauthn_verify_keyset(
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    signature_der: &[u8],
+    credential_public_keys_der: &[&[u8]],
+) -> Result<usize, VerifyError> {
+    VerifyLimits::DEFAULT.check(authenticator_data, client_data_json)?;
+
+    let message_hash = sha256_two_part(authenticator_data, &client_data_hash(client_data_json));
+    let signature = DerSignature::try_from(signature_der).map_err(|e| {
+        log::error!(target: LOG_TARGET, "WebAuthn verification failed with ParseSignature error, reason={}", e);
+        VerifyError::ParseSignature
+    })?;
+
+    let mut found = Choice::from(0);
+    let mut index = 0u64;
+    for (i, public_key_der) in credential_public_keys_der.iter().enumerate() {
+        let verified =
+            PublicKey::<NistP256>::from_public_key_der(public_key_der).is_ok_and(|public_key| {
+                VerifyingKey::from(public_key)
+                    .verify_prehash(&message_hash, &signature)
+                    .is_ok()
+            });
+        let is_match = Choice::from(u8::from(verified));
+        index = ct_select(is_match & !found, i as u64, index);
+        found |= is_match;
+    }
+
+    if bool::from(found) {
+        Ok(index as usize)
+    } else {
+        log::error!(target: LOG_TARGET, "WebAuthn verification failed with VerifySignature error, reason=no key matched");
+        Err(VerifyError::VerifySignature)
+    }
+}
@@ -5,39 +5,104 @@
 //! This function validates the signature of a WebAuthn authentication response by:
 //!
 //! 1. Concatenating the `authenticator_data` and the hashed `client_data_json` to form the message.
-//! 2. Verifying the `signature_der` against the message using the provided `credential_public_key_cbor`.
+//! 2. Verifying the `signature_der` against the message using the credential public key.
 //!
-//! The `credential_public_key_cbor` should be in COSE format and correspond to an ECDSA P-256 public key,
-//! as specified in the WebAuthn standard.
+//! The credential public key is registered in COSE format, and must be an ECDSA P-256 key, as
+//! specified in the WebAuthn standard. [`cose_key_to_der`] turns it into the DER key verified
+//! with.
 //!
 //! # Arguments
 //!
 //! * `authenticator_data` - The raw bytes of the authenticator data provided by the authenticator.
 //! * `client_data_json` - The client data JSON.
 //! * `signature_der` - The signature generated by the authenticator.
-//! * `credential_public_key_cbor` - The public key in COSE format extracted from the authenticator's attestation data.
+//! * `credential_public_key_der` - The DER-encoded public key of the credential, from the COSE key of its attestation data.
 //!
 //! # Returns
 //!
-//! * `true` if the signature is valid.
-//! * `false` if the signature is invalid.
-//!
-//! # Example
+//! [`webauthn_verify`] returns `Ok(())` if the signature is valid, and otherwise the
+//! [`VerifyError`] telling why it isn't, so callers can't mistake a failed verification for a
+//! successful one by ignoring a boolean:
 //!
 //! ```ignore
 //! let authenticator_data = /* ... */;
 //! let client_data_json = /* ... */;
 //! let signature_der = /* ... */;
-//! let credential_public_key_cbor = /* ... */;
+//! let credential_public_key_der = cose_key_to_der(&credential_public_key_cose)?;
 //!
-//! let is_valid = verify_webauthn_response(
+//! match webauthn_verify(
 //!     &authenticator_data,
 //!     &client_data_json,
 //!     &signature_der,
-//!     &credential_public_key_cbor,
-//! );
+//!     &credential_public_key_der,
+//! ) {
+//!     Ok(()) => println!("Valid assertion"),
+//!     Err(e) => println!("Invalid assertion: {e:?}"),
+//! }
+//! ```
+//!
+//! The `webauthn-verifier` command line tool (`cli/`) runs these checks on captured responses,
+//! exiting with `0` when they pass and `1` when one fails.
+//!
+//! # Verification Flow
 //!
-//! assert!(is_valid);
+//! The browser serializes the `client_data_json`, the authenticator produces the
+//! `authenticator_data` and signs it along the client data hash, and the relying party holds the
+//! public key it got, COSE-encoded, when the credential was registered:
+//!
+//! ```text
+//!   Browser        client_data_json ---> SHA-256 ---> hash
+//!                                                      |
+//!   Authenticator  authenticator_data ---------------+ |
+//!                                                    v v
+//!                                   message = authenticator_data || hash
+//!                                                     |
+//!                                                     v
+//!   Authenticator  signature_der -------------> ECDSA P-256 verify ---> Ok(()) / VerifyError
+//!                                                     ^
+//!                                                     |
+//!   Registration   COSE public key ---> cose_key_to_der ---> public_key_der
+//! ```
+//!
+//! The same, for renderers supporting Mermaid:
+//!
+//! ```mermaid
+//! flowchart LR
+//!     cd[client_data_json] --> sha[SHA-256] --> hash
+//!     ad[authenticator_data] --> msg["message = authenticator_data || hash"]
+//!     hash --> msg
+//!     msg --> verify[ECDSA P-256 verify]
+//!     sig[signature_der] --> verify
+//!     cose[COSE public key] --> der[cose_key_to_der] --> verify
+//!     verify --> result["Ok(()) / VerifyError"]
+//! ```
+//!
+//! Step by step, as an authenticator and [`webauthn_verify`] do it:
+//!
+//! ```
+//! use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+//! use verifier::{client_data_hash, sec1_public_key_to_der, webauthn_verify};
+//!
+//! let authenticator_data = [0u8; 37];
+//! let client_data_json =
+//!     br#"{"type":"webauthn.get","challenge":"dGVzdA","origin":"https://example.com"}"#;
+//!
+//! // The authenticator signs `authenticator_data || SHA-256(client_data_json)`.
+//! let signing_key = SigningKey::from_slice(&[0x11; 32]).unwrap();
+//! let message = [&authenticator_data[..], &client_data_hash(client_data_json)].concat();
+//! let signature: Signature = signing_key.sign(&message);
+//!
+//! let public_key = signing_key.verifying_key().to_encoded_point(false);
+//! let public_key_der = sec1_public_key_to_der(public_key.as_bytes()).unwrap();
+//! assert_eq!(
+//!     webauthn_verify(
+//!         &authenticator_data,
+//!         client_data_json,
+//!         signature.to_der().as_bytes(),
+//!         &public_key_der,
+//!     ),
+//!     Ok(())
+//! );
 //! ```
 //!
 //! # References
@@ -48,47 +113,475 @@
 //! * <https://www.w3.org/TR/webauthn/images/fido-signature-formats-figure2.svg>
 
 extern crate alloc;
+#[cfg(feature = "scale")]
+use codec::{Decode, Encode, MaxEncodedLen};
 use p256::{
-    ecdsa::{signature::Verifier, DerSignature, VerifyingKey},
+    ecdsa::{
+        signature::{hazmat::PrehashVerifier, DigestVerifier},
+        DerSignature, VerifyingKey,
+    },
     elliptic_curve::PublicKey,
     pkcs8::DecodePublicKey,
     NistP256,
 };
-use sha2::{Digest, Sha256};
+#[cfg(feature = "scale")]
+use scale_info::TypeInfo;
+use sha2::{
+    digest::{consts::U32, FixedOutput},
+    Digest,
+};
 
+mod attestation;
+mod authenticator_data;
+mod cache;
+mod certificate;
+mod client_data;
+mod client_data_lite;
+mod compact_signature;
+mod cose;
+mod ctap2;
+mod extensions;
+#[cfg(feature = "jwk")]
+mod jwk;
+mod keyset;
+mod limits;
+mod observer;
+mod origin;
+mod public_key_input;
+#[cfg(feature = "key-recovery")]
+mod recovery;
+mod revocation;
+mod sec1;
+mod sha256;
+#[cfg(any(feature = "test-vectors", test))]
+pub mod test_vectors;
 #[cfg(test)]
 mod tests;
+#[cfg(feature = "webauthn-rs-interop")]
+mod webauthn_rs_interop;
+
+pub use attestation::{
+    all_supported_formats, parse_attestation_object, verify_algorithm_offered,
+    verify_packed_x5c_attestation, verify_packed_x5c_attestation_at, AttestationFormat,
+    AttestationObject, AttestationStatement,
+};
+pub use authenticator_data::{
+    credential_id_from_authenticator_data, parse_authenticator_data,
+    parse_authenticator_data_partially, sign_count_from_authenticator_data,
+    verify_backup_state_consistency, AttestedCredentialData, AuthenticatorData,
+    AuthenticatorDataFlags, ParseError, PartialAuthenticatorData,
+};
+#[cfg(feature = "std")]
+pub use cache::InMemoryCache;
+pub use cache::{webauthn_verify_cached, NoopCache, VerificationCache};
+pub use client_data::{canonical_client_data, check_client_data, verify_challenge};
+pub use client_data_lite::ClientDataLite;
+pub use compact_signature::{compact_signature_to_der, SignatureError};
+pub use cose::{
+    cose_key_algorithm, cose_key_algorithm_with_policy, cose_key_from_public_key, cose_key_to_der,
+    cose_key_to_der_with_policy, cose_key_to_public_key, CoseAlgorithm, MissingAlgorithmPolicy,
+};
+pub use ctap2::{parse_ctap2_assertion, verify_ctap2_assertion, Ctap2Assertion};
+pub use extensions::PrfOutputs;
+pub use keyset::{ct_select, webauthn_verify_keyset};
+pub use limits::VerifyLimits;
+pub use observer::{webauthn_verify_observed, VerifyObserver};
+pub use origin::{validate_webauthn_origin, validate_webauthn_rp_id, OriginError, OriginRpIdError};
+pub use public_key_input::{PublicKeyInput, UNCOMPRESSED_P256_KEY_LEN};
+#[cfg(feature = "key-recovery")]
+pub use recovery::recover_public_key_from_signature;
+pub use revocation::{
+    verify_packed_x5c_attestation_with_revocation, NoOpRevocationChecker, RevocationChecker,
+    RevocationError,
+};
+pub use sec1::{compress_public_key_der, sec1_public_key_to_der, COMPRESSED_P256_KEY_LEN};
+pub use sha256::{sha256_fixed, sha256_two_part};
+#[cfg(feature = "webauthn-rs-interop")]
+pub use webauthn_rs_interop::{cose_key_from_webauthn_rs, webauthn_rs_key_to_der};
 
-#[derive(Debug)]
+/// Why a verification failed.
+///
+/// The discriminants are the [`code`](VerifyError::code) of each error and its SCALE encoding,
+/// which runtimes emit in events and contracts read: they are a stable ABI, never renumbered, and
+/// new errors only take new values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "scale", derive(Encode, Decode, MaxEncodedLen, TypeInfo))]
+#[repr(u8)]
 pub enum VerifyError {
-    ExtractPublicKey,
-    ParseSignature,
-    VerifySignature,
+    AaguidMismatch = 18,
+    AlgorithmMismatch = 0,
+    /// The algorithm of the credential public key isn't one of those the relying party offered.
+    AlgorithmNotOffered = 23,
+    AttestationCertificateRevoked = 20,
+    /// The authenticator model, by its AAGUID, is blocked by the relying party's device policy.
+    AuthenticatorBlocked = 22,
+    BackupStateInconsistency = 1,
+    /// The challenge in the client data isn't the expected one.
+    ChallengeMismatch = 21,
+    ClientDataMalformed = 2,
+    ClientDataModified = 3,
+    CredentialAlreadyRegistered = 4,
+    ExtractPublicKey = 5,
+    InputTooLarge = 6,
+    InvalidAttestationCertificate = 17,
+    MissingAlgorithm = 7,
+    ParseAttestationCertificate = 16,
+    ParseAttestationObject = 8,
+    ParseAuthenticatorData = 9,
+    ParseClientData = 10,
+    ParseCtap2Response = 15,
+    ParseSignature = 11,
+    /// The public key isn't the credential public key the authenticator data attests.
+    PublicKeyMismatch = 24,
+    SelfAttestationNotAllowed = 19,
+    UnsupportedAlgorithm = 12,
+    UnsupportedAttestationFormat = 13,
+    VerifySignature = 14,
+}
+
+impl VerifyError {
+    /// The stable numeric code of the error.
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    /// The error with the given [`code`](VerifyError::code), if any.
+    pub fn from_code(code: u8) -> Option<Self> {
+        use VerifyError::*;
+        Some(match code {
+            0 => AlgorithmMismatch,
+            1 => BackupStateInconsistency,
+            2 => ClientDataMalformed,
+            3 => ClientDataModified,
+            4 => CredentialAlreadyRegistered,
+            5 => ExtractPublicKey,
+            6 => InputTooLarge,
+            7 => MissingAlgorithm,
+            8 => ParseAttestationObject,
+            9 => ParseAuthenticatorData,
+            10 => ParseClientData,
+            11 => ParseSignature,
+            12 => UnsupportedAlgorithm,
+            13 => UnsupportedAttestationFormat,
+            14 => VerifySignature,
+            15 => ParseCtap2Response,
+            16 => ParseAttestationCertificate,
+            17 => InvalidAttestationCertificate,
+            18 => AaguidMismatch,
+            19 => SelfAttestationNotAllowed,
+            20 => AttestationCertificateRevoked,
+            21 => ChallengeMismatch,
+            22 => AuthenticatorBlocked,
+            23 => AlgorithmNotOffered,
+            24 => PublicKeyMismatch,
+            _ => return None,
+        })
+    }
+}
+
+impl From<VerifyError> for u32 {
+    fn from(value: VerifyError) -> Self {
+        value.code().into()
+    }
 }
 
 const LOG_TARGET: &str = "verifier::verify_signature";
 
+/// COSE is only used for public keys.
+impl From<coset::CoseError> for VerifyError {
+    fn from(value: coset::CoseError) -> Self {
+        log::error!(target: LOG_TARGET, "WebAuthn verification failed with ExtractPublicKey error, reason={:?}", value);
+        VerifyError::ExtractPublicKey
+    }
+}
+
+impl From<p256::pkcs8::Error> for VerifyError {
+    fn from(value: p256::pkcs8::Error) -> Self {
+        log::error!(target: LOG_TARGET, "WebAuthn verification failed with ExtractPublicKey error, reason={}", value);
+        VerifyError::ExtractPublicKey
+    }
+}
+
+/// Returned when decoding DER-encoded public keys.
+impl From<p256::pkcs8::spki::Error> for VerifyError {
+    fn from(value: p256::pkcs8::spki::Error) -> Self {
+        log::error!(target: LOG_TARGET, "WebAuthn verification failed with ExtractPublicKey error, reason={}", value);
+        VerifyError::ExtractPublicKey
+    }
+}
+
+/// The signature doesn't verify. Signature parsing errors are of the same type, but are mapped to
+/// [`VerifyError::ParseSignature`] explicitly where they happen.
+impl From<p256::ecdsa::Error> for VerifyError {
+    fn from(value: p256::ecdsa::Error) -> Self {
+        log::error!(target: LOG_TARGET, "WebAuthn verification failed with VerifySignature error, reason={}", value);
+        VerifyError::VerifySignature
+    }
+}
+
+/// Computes the `clientDataHash` exactly as [`webauthn_verify`] does when composing the message.
+///
+/// The hash is taken over the raw `client_data_json` bytes verbatim: no JSON re-canonicalization
+/// happens, so any change in whitespace or key order yields a different hash. Useful for
+/// comparing against the hash the signer used when a verification fails unexpectedly.
+pub fn client_data_hash(client_data_json: &[u8]) -> [u8; 32] {
+    sha256_fixed(client_data_json)
+}
+
+/// Verifies an assertion signature, rejecting inputs beyond [`VerifyLimits::DEFAULT`].
+///
+/// Like every verification function, the result must be used: ignoring it is a warning, and an
+/// error under `#![deny(unused_must_use)]`.
+///
+/// ```compile_fail
+/// #![deny(unused_must_use)]
+/// verifier::webauthn_verify(b"", b"", b"", b"");
+/// ```
+#[must_use = "the signature is only verified if the result is checked"]
 pub fn webauthn_verify(
     authenticator_data: &[u8],
     client_data_json: &[u8],
     signature_der: &[u8],
     credential_public_key_der: &[u8],
 ) -> Result<(), VerifyError> {
-    // Step 1: Compute the SHA-256 hash of the client data JSON
-    let client_data_hash: [u8; 32] = Sha256::digest(client_data_json).into();
+    webauthn_verify_with_limits(
+        &VerifyLimits::DEFAULT,
+        authenticator_data,
+        client_data_json,
+        signature_der,
+        credential_public_key_der,
+    )
+}
 
-    // Step 2: Concatenate authenticator data and client data hash
-    log::trace!(target: LOG_TARGET, "Composing verify message");
-    let message = [authenticator_data, &client_data_hash].concat();
+/// Like [`webauthn_verify`], with the input size limits of a deployment.
+#[must_use = "the signature is only verified if the result is checked"]
+pub fn webauthn_verify_with_limits(
+    limits: &VerifyLimits,
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    signature_der: &[u8],
+    credential_public_key_der: &[u8],
+) -> Result<(), VerifyError> {
+    limits.check(authenticator_data, client_data_json)?;
 
-    // Step 3: Extract public key from DER format
+    // Extract public key from DER format
     log::trace!(target: LOG_TARGET, "Obtaining public key");
     let public_key: PublicKey<NistP256> =
-        DecodePublicKey::from_public_key_der(credential_public_key_der).map_err(|e| {
+        DecodePublicKey::from_public_key_der(credential_public_key_der)?;
+
+    verify_with_public_key(
+        authenticator_data,
+        client_data_json,
+        signature_der,
+        public_key,
+    )
+}
+
+/// Like [`webauthn_verify`], also checking the client data carries `expected_challenge`, the
+/// challenge issued for the ceremony, with [`verify_challenge`].
+///
+/// The signature is checked first, and the first failure is returned.
+#[must_use = "the signature is only verified if the result is checked"]
+pub fn webauthn_verify_with_challenge(
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    signature_der: &[u8],
+    credential_public_key_der: &[u8],
+    expected_challenge: &[u8],
+) -> Result<(), VerifyError> {
+    webauthn_verify(
+        authenticator_data,
+        client_data_json,
+        signature_der,
+        credential_public_key_der,
+    )?;
+    verify_challenge(client_data_json, expected_challenge)
+}
+
+/// Like [`webauthn_verify`], for a public key given as a SEC1 point, compressed or not.
+///
+/// Decoding a compressed point is cheaper than a DER `SubjectPublicKeyInfo`, and it takes a third
+/// of the space to store.
+#[must_use = "the signature is only verified if the result is checked"]
+pub fn webauthn_verify_sec1(
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    signature_der: &[u8],
+    credential_public_key_sec1: &[u8],
+) -> Result<(), VerifyError> {
+    VerifyLimits::DEFAULT.check(authenticator_data, client_data_json)?;
+
+    log::trace!(target: LOG_TARGET, "Obtaining public key");
+    let public_key = PublicKey::<NistP256>::from_sec1_bytes(credential_public_key_sec1)
+        .map_err(|e| {
             log::error!(target: LOG_TARGET, "WebAuthn verification failed with ExtractPublicKey error, reason={}", e);
             VerifyError::ExtractPublicKey
         })?;
 
+    verify_with_public_key(
+        authenticator_data,
+        client_data_json,
+        signature_der,
+        public_key,
+    )
+}
+
+/// Like [`webauthn_verify`], for a public key stored as a PEM-encoded `SubjectPublicKeyInfo`
+/// (`-----BEGIN PUBLIC KEY-----`), failing with [`VerifyError::ExtractPublicKey`] when it can't
+/// be decoded.
+#[cfg(feature = "pem")]
+#[must_use = "the signature is only verified if the result is checked"]
+pub fn webauthn_verify_pem(
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    signature_der: &[u8],
+    credential_public_key_pem: &str,
+) -> Result<(), VerifyError> {
+    VerifyLimits::DEFAULT.check(authenticator_data, client_data_json)?;
+
+    log::trace!(target: LOG_TARGET, "Obtaining public key");
+    let public_key: PublicKey<NistP256> =
+        DecodePublicKey::from_public_key_pem(credential_public_key_pem)?;
+
+    verify_with_public_key(
+        authenticator_data,
+        client_data_json,
+        signature_der,
+        public_key,
+    )
+}
+
+/// Like [`webauthn_verify`], for a public key stored as an EC JSON Web Key (`{"kty": "EC",
+/// "crv": "P-256", "x": .., "y": ..}`), as web backends and OIDC providers keep them. Fails with
+/// [`VerifyError::ExtractPublicKey`] when it isn't a P-256 public key.
+#[cfg(feature = "jwk")]
+#[must_use = "the signature is only verified if the result is checked"]
+pub fn webauthn_verify_jwk(
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    signature_der: &[u8],
+    credential_public_key_jwk: &str,
+) -> Result<(), VerifyError> {
+    VerifyLimits::DEFAULT.check(authenticator_data, client_data_json)?;
+
+    log::trace!(target: LOG_TARGET, "Obtaining public key");
+    let public_key = jwk::public_key_from_jwk(credential_public_key_jwk)?;
+
+    verify_with_public_key(
+        authenticator_data,
+        client_data_json,
+        signature_der,
+        public_key,
+    )
+}
+
+/// Like [`webauthn_verify`], for the COSE public key found in the attested credential data.
+///
+/// The key is decoded straight from its coordinates, skipping the DER encoding and decoding of
+/// calling [`cose_key_to_der`] before [`webauthn_verify`], with the same result.
+#[must_use = "the signature is only verified if the result is checked"]
+pub fn webauthn_verify_cose(
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    signature_der: &[u8],
+    credential_public_key: &coset::CoseKey,
+) -> Result<(), VerifyError> {
+    VerifyLimits::DEFAULT.check(authenticator_data, client_data_json)?;
+
+    log::trace!(target: LOG_TARGET, "Obtaining public key");
+    let public_key = cose_key_to_public_key(credential_public_key)?;
+
+    verify_with_public_key(
+        authenticator_data,
+        client_data_json,
+        signature_der,
+        public_key,
+    )
+}
+
+/// Like [`webauthn_verify`], for a public key in any of the encodings of [`PublicKeyInput`].
+#[must_use = "the signature is only verified if the result is checked"]
+pub fn webauthn_verify_public_key(
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    signature_der: &[u8],
+    credential_public_key: PublicKeyInput,
+) -> Result<(), VerifyError> {
+    VerifyLimits::DEFAULT.check(authenticator_data, client_data_json)?;
+
+    log::trace!(target: LOG_TARGET, "Obtaining public key");
+    let public_key = credential_public_key.public_key()?;
+
+    verify_with_public_key(
+        authenticator_data,
+        client_data_json,
+        signature_der,
+        public_key,
+    )
+}
+
+/// Like [`webauthn_verify`], hashing with `D` rather than the software [`sha2::Sha256`], e.g. to use a
+/// hardware accelerated SHA-256 where the platform has one.
+///
+/// `D` computes both the `clientDataHash` and the digest of the signed message, so it must be a
+/// SHA-256 implementation: any other hash makes every signature fail to verify.
+#[must_use = "the signature is only verified if the result is checked"]
+pub fn webauthn_verify_with_hasher<D>(
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    signature_der: &[u8],
+    credential_public_key_der: &[u8],
+) -> Result<(), VerifyError>
+where
+    D: Digest + FixedOutput<OutputSize = U32>,
+{
+    VerifyLimits::DEFAULT.check(authenticator_data, client_data_json)?;
+
+    log::trace!(target: LOG_TARGET, "Obtaining public key");
+    let public_key: PublicKey<NistP256> =
+        DecodePublicKey::from_public_key_der(credential_public_key_der)?;
+
+    log::trace!(target: LOG_TARGET, "Parsing signature");
+    let signature =
+        DerSignature::try_from(signature_der).map_err(|e| {
+            log::error!(target: LOG_TARGET, "WebAuthn verification failed with ParseSignature error, reason={}", e);
+            VerifyError::ParseSignature
+        })?;
+
+    let message_digest = D::new()
+        .chain_update(authenticator_data)
+        .chain_update(D::digest(client_data_json));
+    Ok(VerifyingKey::from(public_key).verify_digest(message_digest, &signature)?)
+}
+
+fn verify_with_public_key(
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    signature_der: &[u8],
+    public_key: PublicKey<NistP256>,
+) -> Result<(), VerifyError> {
+    // Step 1: Compute the SHA-256 hash of the client data JSON
+    let client_data_hash = client_data_hash(client_data_json);
+
+    verify_with_client_data_hash(
+        authenticator_data,
+        &client_data_hash,
+        signature_der,
+        public_key,
+    )
+}
+
+fn verify_with_client_data_hash(
+    authenticator_data: &[u8],
+    client_data_hash: &[u8; 32],
+    signature_der: &[u8],
+    public_key: PublicKey<NistP256>,
+) -> Result<(), VerifyError> {
+    // Step 2: Hash authenticator data and client data hash, without concatenating them
+    log::trace!(target: LOG_TARGET, "Hashing verify message");
+    let message_hash = sha256_two_part(authenticator_data, client_data_hash);
+
+    // Step 3: Obtain the verifying key
     let verifying_key = VerifyingKey::from(public_key);
 
     // Step 4: Parse the DER signature
@@ -101,17 +594,70 @@ pub fn webauthn_verify(
 
     log::trace!(
         target: LOG_TARGET,
-        "Run WebAuthn verify_signature: message={:?}, public_key={:?}, signature={:?}",
-        &message,
+        "Run WebAuthn verify_signature: message_hash={:?}, public_key={:?}, signature={:?}",
+        &message_hash,
         &public_key,
         &signature
     );
     // Step 5: Verify the signature
-    verifying_key
-        .verify(&message, &signature)
-        .map(|_| ())
-        .map_err(|e| {
-            log::error!(target: LOG_TARGET, "WebAuthn verification failed with VerifySignature error, reason={}", e);
-            VerifyError::VerifySignature
-        })
+    Ok(verifying_key.verify_prehash(&message_hash, &signature)?)
+}
+
+/// Like [`webauthn_verify`], but first runs [`check_client_data`] on `client_data_json`, so a
+/// re-serialized client data fails with a precise error rather than a signature mismatch.
+#[must_use = "the signature is only verified if the result is checked"]
+pub fn webauthn_verify_client_data(
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    expected_client_data_json: Option<&[u8]>,
+    signature_der: &[u8],
+    credential_public_key_der: &[u8],
+) -> Result<(), VerifyError> {
+    check_client_data(client_data_json, expected_client_data_json)?;
+    webauthn_verify(
+        authenticator_data,
+        client_data_json,
+        signature_der,
+        credential_public_key_der,
+    )
+}
+
+/// Like [`webauthn_verify`], for a credential registered with `algorithm`.
+///
+/// Fails with [`VerifyError::AlgorithmMismatch`] when the signature isn't encoded as
+/// `algorithm` produces them (e.g. a raw EdDSA signature presented for an ES256 credential),
+/// rather than attempting to verify it against the stored key.
+#[must_use = "the signature is only verified if the result is checked"]
+pub fn webauthn_verify_with_algorithm(
+    algorithm: CoseAlgorithm,
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    signature: &[u8],
+    credential_public_key_der: &[u8],
+) -> Result<(), VerifyError> {
+    check_signature_encoding(algorithm, signature)?;
+    match algorithm {
+        CoseAlgorithm::ES256 => webauthn_verify(
+            authenticator_data,
+            client_data_json,
+            signature,
+            credential_public_key_der,
+        ),
+    }
+}
+
+/// Checks `signature` is encoded as `algorithm` produces them, failing with
+/// [`VerifyError::AlgorithmMismatch`] otherwise.
+pub fn check_signature_encoding(
+    algorithm: CoseAlgorithm,
+    signature: &[u8],
+) -> Result<(), VerifyError> {
+    match algorithm {
+        // ECDSA signatures are always an ASN.1 DER `SEQUENCE` in WebAuthn.
+        CoseAlgorithm::ES256 if signature.first() != Some(&0x30) => {
+            log::error!(target: LOG_TARGET, "Signature is not an ECDSA signature, as expected for ES256");
+            Err(VerifyError::AlgorithmMismatch)
+        }
+        CoseAlgorithm::ES256 => Ok(()),
+    }
 }
@@ -0,0 +1,59 @@
+//! Bounds on the size of the inputs of a verification.
+
+use crate::VerifyError;
+
+const LOG_TARGET: &str = "verifier::limits";
+
+/// Longest inputs a verification accepts, checked before anything is hashed or copied, so an
+/// untrusted caller can't force arbitrarily large work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifyLimits {
+    pub max_authenticator_data_len: usize,
+    pub max_client_data_len: usize,
+}
+
+impl VerifyLimits {
+    /// Generous enough for any real authenticator and browser: authenticator data is a few
+    /// hundred bytes even with extensions, and client data a few hundred more.
+    pub const DEFAULT: Self = Self {
+        max_authenticator_data_len: 16 * 1024,
+        max_client_data_len: 64 * 1024,
+    };
+
+    pub fn with_max_authenticator_data_len(self, max_authenticator_data_len: usize) -> Self {
+        Self {
+            max_authenticator_data_len,
+            ..self
+        }
+    }
+
+    pub fn with_max_client_data_len(self, max_client_data_len: usize) -> Self {
+        Self {
+            max_client_data_len,
+            ..self
+        }
+    }
+
+    /// Fails with [`VerifyError::InputTooLarge`] if either input exceeds its limit.
+    pub fn check(
+        &self,
+        authenticator_data: &[u8],
+        client_data_json: &[u8],
+    ) -> Result<(), VerifyError> {
+        if authenticator_data.len() > self.max_authenticator_data_len {
+            log::error!(target: LOG_TARGET, "Authenticator data is too large: {} bytes", authenticator_data.len());
+            return Err(VerifyError::InputTooLarge);
+        }
+        if client_data_json.len() > self.max_client_data_len {
+            log::error!(target: LOG_TARGET, "Client data is too large: {} bytes", client_data_json.len());
+            return Err(VerifyError::InputTooLarge);
+        }
+        Ok(())
+    }
+}
+
+impl Default for VerifyLimits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
@@ -0,0 +1,85 @@
+//! Instrumentation hooks for signature verification.
+
+use core::time::Duration;
+
+use crate::{webauthn_verify_with_algorithm, CoseAlgorithm, VerifyError};
+
+/// Observes signature verifications, e.g. to export latency and failure rate metrics.
+pub trait VerifyObserver {
+    /// Whether the observer does anything at all. When `false`, the verification isn't timed.
+    const ENABLED: bool = true;
+
+    fn on_start(&mut self, _alg: CoseAlgorithm) {}
+
+    /// `elapsed` is measured with the system clock under `std`, and is always zero otherwise:
+    /// observers without `std` need to measure it themselves between both callbacks.
+    fn on_finish(
+        &mut self,
+        _alg: CoseAlgorithm,
+        _outcome: Result<(), VerifyError>,
+        _elapsed: Duration,
+    ) {
+    }
+}
+
+/// The no-op observer.
+impl VerifyObserver for () {
+    const ENABLED: bool = false;
+}
+
+#[cfg(feature = "std")]
+struct Stopwatch(Option<std::time::Instant>);
+
+#[cfg(feature = "std")]
+impl Stopwatch {
+    fn start(enabled: bool) -> Self {
+        Self(enabled.then(std::time::Instant::now))
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.0.map(|start| start.elapsed()).unwrap_or_default()
+    }
+}
+
+#[cfg(not(feature = "std"))]
+struct Stopwatch;
+
+#[cfg(not(feature = "std"))]
+impl Stopwatch {
+    fn start(_: bool) -> Self {
+        Self
+    }
+
+    fn elapsed(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+/// Like [`webauthn_verify_with_algorithm`], reporting the verification to `observer`.
+#[must_use = "the signature is only verified if the result is checked"]
+pub fn webauthn_verify_observed<O: VerifyObserver>(
+    observer: &mut O,
+    algorithm: CoseAlgorithm,
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    signature: &[u8],
+    credential_public_key_der: &[u8],
+) -> Result<(), VerifyError> {
+    if O::ENABLED {
+        observer.on_start(algorithm);
+    }
+    let stopwatch = Stopwatch::start(O::ENABLED);
+
+    let outcome = webauthn_verify_with_algorithm(
+        algorithm,
+        authenticator_data,
+        client_data_json,
+        signature,
+        credential_public_key_der,
+    );
+
+    if O::ENABLED {
+        observer.on_finish(algorithm, outcome, stopwatch.elapsed());
+    }
+    outcome
+}
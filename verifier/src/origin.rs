@@ -0,0 +1,129 @@
+//! Checks of the `origin` of a client data, for relying parties running them apart from the
+//! signature verification, e.g. in a middleware.
+//!
+//! See <https://www.w3.org/TR/webauthn/#sctn-validating-origin>.
+
+use alloc::{string::String, vec};
+use core::net::Ipv4Addr;
+
+use crate::{ClientDataLite, VerifyError, VerifyLimits};
+
+const LOG_TARGET: &str = "verifier::origin";
+
+/// Why the origin of a client data isn't the expected one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OriginError {
+    /// The client data couldn't be parsed, with the [`VerifyError`] parsing failed with.
+    ClientData(VerifyError),
+    /// The origin doesn't use `https`, or has no host.
+    Insecure { actual: String },
+    /// The origin isn't the expected one.
+    Mismatch { actual: String },
+}
+
+/// Why the origin of a client data can't have made a credential of an RP ID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OriginRpIdError {
+    /// The client data couldn't be parsed, with the [`VerifyError`] parsing failed with.
+    ClientData(VerifyError),
+    /// The origin doesn't use `https`, or has no host.
+    Insecure { actual: String },
+    /// The host of the origin is an IP address, which can't have an RP ID.
+    IpAddress { actual: String },
+    /// The RP ID is neither the host of the origin nor a domain it's a subdomain of.
+    Mismatch { actual: String },
+}
+
+/// Parses `client_data_json` and calls `check` with its origin.
+fn with_origin<T>(
+    client_data_json: &[u8],
+    check: impl FnOnce(&str) -> T,
+) -> Result<T, VerifyError> {
+    let mut unescape_buffer = vec![0; client_data_json.len()];
+    let client_data = ClientDataLite::parse(
+        client_data_json,
+        &mut unescape_buffer,
+        &VerifyLimits::DEFAULT,
+    )?;
+    Ok(check(client_data.origin))
+}
+
+/// The host of an `https` origin, without its port.
+fn https_host(origin: &str) -> Option<&str> {
+    let (scheme, authority) = origin.split_once("://")?;
+    if !scheme.eq_ignore_ascii_case("https") || authority.contains(['/', '?', '#', '@']) {
+        return None;
+    }
+    let host = match authority.strip_prefix('[') {
+        // An IPv6 address, brackets included.
+        Some(rest) => &authority[..rest.find(']')? + 2],
+        None => authority.split(':').next()?,
+    };
+    (!host.is_empty()).then_some(host)
+}
+
+/// Checks the `origin` of `client_data_json` is `expected_origin`, ignoring case.
+///
+/// Origins not using `https` are always rejected, even when they're the expected one.
+pub fn validate_webauthn_origin(
+    client_data_json: &[u8],
+    expected_origin: &str,
+) -> Result<(), OriginError> {
+    with_origin(client_data_json, |origin| {
+        if https_host(origin).is_none() {
+            log::error!(target: LOG_TARGET, "Origin {origin} doesn't use https");
+            return Err(OriginError::Insecure {
+                actual: origin.into(),
+            });
+        }
+        if !origin.eq_ignore_ascii_case(expected_origin) {
+            log::error!(target: LOG_TARGET, "Origin {origin} isn't {expected_origin}");
+            return Err(OriginError::Mismatch {
+                actual: origin.into(),
+            });
+        }
+        Ok(())
+    })
+    .map_err(OriginError::ClientData)?
+}
+
+/// Checks `expected_rp_id` is a valid RP ID for the `origin` of `client_data_json`: that it's
+/// the effective domain of the origin (its host, whatever its port), or a domain it's a
+/// subdomain of, ignoring case.
+///
+/// Origins not using `https`, and those whose host is an IP address, are always rejected.
+/// Whether `expected_rp_id` is a public suffix (like `com`), which browsers refuse as RP IDs,
+/// isn't checked: the relying party picks its RP ID.
+pub fn validate_webauthn_rp_id(
+    client_data_json: &[u8],
+    expected_rp_id: &str,
+) -> Result<(), OriginRpIdError> {
+    with_origin(client_data_json, |origin| {
+        let Some(host) = https_host(origin) else {
+            log::error!(target: LOG_TARGET, "Origin {origin} doesn't use https");
+            return Err(OriginRpIdError::Insecure {
+                actual: origin.into(),
+            });
+        };
+        if host.starts_with('[') || host.parse::<Ipv4Addr>().is_ok() {
+            log::error!(target: LOG_TARGET, "Origin {origin} is an IP address");
+            return Err(OriginRpIdError::IpAddress {
+                actual: origin.into(),
+            });
+        }
+
+        let (host, rp_id) = (host.as_bytes(), expected_rp_id.as_bytes());
+        let is_subdomain = host.len() > rp_id.len()
+            && host[host.len() - rp_id.len() - 1] == b'.'
+            && host[host.len() - rp_id.len()..].eq_ignore_ascii_case(rp_id);
+        if !rp_id.is_empty() && (host.eq_ignore_ascii_case(rp_id) || is_subdomain) {
+            Ok(())
+        } else {
+            log::error!(target: LOG_TARGET, "Origin {origin} can't use the RP ID {expected_rp_id}");
+            Err(OriginRpIdError::Mismatch {
+                actual: origin.into(),
+            })
+        }
+    })
+    .map_err(OriginRpIdError::ClientData)?
+}
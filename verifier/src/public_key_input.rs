@@ -0,0 +1,59 @@
+//! The encodings a credential public key is accepted in.
+
+use p256::{
+    ecdsa::VerifyingKey, elliptic_curve::PublicKey, pkcs8::DecodePublicKey, EncodedPoint, NistP256,
+};
+
+use crate::{cose_key_to_public_key, VerifyError, LOG_TARGET};
+
+/// Length of an uncompressed SEC1-encoded P-256 point, `04 || x || y`.
+pub const UNCOMPRESSED_P256_KEY_LEN: usize = 65;
+
+/// A credential public key, in any of the encodings authenticators and relying parties keep it
+/// in, for [`webauthn_verify_public_key`](crate::webauthn_verify_public_key).
+#[derive(Debug, Clone, Copy)]
+pub enum PublicKeyInput<'a> {
+    /// A DER-encoded `SubjectPublicKeyInfo`, as [`webauthn_verify`](crate::webauthn_verify)
+    /// takes.
+    Der(&'a [u8]),
+    /// The COSE key of the attested credential data.
+    Cose(&'a coset::CoseKey),
+    /// A SEC1 point, compressed or not.
+    Sec1(&'a [u8]),
+    /// A raw 65-byte uncompressed point, `04 || x || y`, as some custom authenticators encode
+    /// their keys. Anything else, compressed points included, is rejected.
+    UncompressedPoint(&'a [u8]),
+}
+
+impl PublicKeyInput<'_> {
+    /// Decodes the key, failing with [`VerifyError::ExtractPublicKey`] if it's malformed or its
+    /// point isn't on the curve.
+    pub fn public_key(&self) -> Result<PublicKey<NistP256>, VerifyError> {
+        match *self {
+            Self::Der(der) => Ok(PublicKey::from_public_key_der(der)?),
+            Self::Cose(key) => cose_key_to_public_key(key),
+            Self::Sec1(sec1) => PublicKey::from_sec1_bytes(sec1).map_err(|e| {
+                log::error!(target: LOG_TARGET, "WebAuthn verification failed with ExtractPublicKey error, reason={}", e);
+                VerifyError::ExtractPublicKey
+            }),
+            Self::UncompressedPoint(point) => uncompressed_point_to_public_key(point),
+        }
+    }
+}
+
+fn uncompressed_point_to_public_key(point: &[u8]) -> Result<PublicKey<NistP256>, VerifyError> {
+    if point.len() != UNCOMPRESSED_P256_KEY_LEN || point[0] != 0x04 {
+        log::error!(target: LOG_TARGET, "Public key is not a 65-byte uncompressed P-256 point");
+        return Err(VerifyError::ExtractPublicKey);
+    }
+    let encoded = EncodedPoint::from_bytes(point).map_err(|e| {
+        log::error!(target: LOG_TARGET, "WebAuthn verification failed with ExtractPublicKey error, reason={}", e);
+        VerifyError::ExtractPublicKey
+    })?;
+    // Not `?`: an `ecdsa::Error` would convert to `VerifySignature`.
+    let verifying_key = VerifyingKey::from_encoded_point(&encoded).map_err(|e| {
+        log::error!(target: LOG_TARGET, "WebAuthn verification failed with ExtractPublicKey error, reason={}", e);
+        VerifyError::ExtractPublicKey
+    })?;
+    Ok(verifying_key.into())
+}
@@ -0,0 +1,40 @@
+//! Public key recovery from ECDSA signatures.
+//!
+//! WebAuthn never needs this: assertions are always checked against a registered key. It's meant
+//! for recovery tooling that lost the key of a credential, but still has assertions made with it.
+
+use ecdsa::RecoveryId;
+use p256::ecdsa::{DerSignature, Signature, VerifyingKey};
+
+use crate::{client_data_hash, sha256_two_part, VerifyError};
+
+const LOG_TARGET: &str = "verifier::recovery";
+
+/// Recovers the two public keys `signature` may have been made with, over the message an
+/// assertion signs (`authenticator_data || sha256(client_data_json)`).
+///
+/// Both candidates verify the signature; which one is the credential key can't be told from it
+/// alone, so callers need another assertion or the credential ID to pick one.
+pub fn recover_public_key_from_signature(
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    signature: &[u8],
+) -> Result<[VerifyingKey; 2], VerifyError> {
+    let signature: Signature = DerSignature::try_from(signature)
+        .and_then(Signature::try_from)
+        .map_err(|_| VerifyError::ParseSignature)?;
+    let message_hash = sha256_two_part(authenticator_data, &client_data_hash(client_data_json));
+
+    let recover = |is_y_odd| {
+        VerifyingKey::recover_from_prehash(
+            &message_hash,
+            &signature,
+            RecoveryId::new(is_y_odd, false),
+        )
+        .map_err(|e| {
+            log::error!(target: LOG_TARGET, "Public key recovery failed, reason={}", e);
+            VerifyError::VerifySignature
+        })
+    };
+    Ok([recover(false)?, recover(true)?])
+}
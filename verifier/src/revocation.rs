@@ -0,0 +1,58 @@
+//! Revocation checks of attestation certificates, left to callers that can reach the OCSP
+//! responders of certificate authorities.
+
+use alloc::vec::Vec;
+
+use crate::{verify_packed_x5c_attestation, CoseAlgorithm, VerifyError};
+
+const LOG_TARGET: &str = "verifier::revocation";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevocationError {
+    /// The certificate was revoked by its issuer.
+    Revoked,
+    /// The revocation status of the certificate couldn't be established, e.g. because the
+    /// responder is unreachable.
+    StatusUnknown,
+}
+
+/// Checks whether attestation certificates are revoked, typically by querying (or reading a
+/// stapled response of) the OCSP responder of their issuer with the caller's own HTTP client.
+pub trait RevocationChecker {
+    fn check_ocsp(&self, cert_der: &[u8]) -> Result<(), RevocationError>;
+}
+
+/// A checker that considers no certificate revoked.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoOpRevocationChecker;
+
+impl RevocationChecker for NoOpRevocationChecker {
+    fn check_ocsp(&self, _: &[u8]) -> Result<(), RevocationError> {
+        Ok(())
+    }
+}
+
+/// Like [`verify_packed_x5c_attestation`], but also asks `checker` about every certificate in
+/// `x5c` once the attestation verifies, failing with
+/// [`VerifyError::AttestationCertificateRevoked`] on revoked ones and with
+/// [`VerifyError::InvalidAttestationCertificate`] on those whose status is unknown.
+pub fn verify_packed_x5c_attestation_with_revocation<R: RevocationChecker>(
+    checker: &R,
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    alg: CoseAlgorithm,
+    sig: &[u8],
+    x5c: &[Vec<u8>],
+) -> Result<(), VerifyError> {
+    verify_packed_x5c_attestation(authenticator_data, client_data_json, alg, sig, x5c)?;
+
+    x5c.iter().enumerate().try_for_each(|(index, certificate)| {
+        checker.check_ocsp(certificate).map_err(|e| {
+            log::warn!(target: LOG_TARGET, "Certificate {index} of the attestation chain: {e:?}");
+            match e {
+                RevocationError::Revoked => VerifyError::AttestationCertificateRevoked,
+                RevocationError::StatusUnknown => VerifyError::InvalidAttestationCertificate,
+            }
+        })
+    })
+}
@@ -0,0 +1,39 @@
+//! Conversions between DER-encoded public keys and their compressed SEC1 form, which takes a
+//! third of the space to store.
+
+use alloc::vec::Vec;
+use p256::{
+    elliptic_curve::{sec1::ToEncodedPoint, PublicKey},
+    pkcs8::DecodePublicKey,
+    NistP256,
+};
+
+use crate::{cose::P256_SPKI_HEADER, VerifyError};
+
+/// Length of a compressed SEC1-encoded P-256 point.
+pub const COMPRESSED_P256_KEY_LEN: usize = 33;
+
+/// Compresses a DER-encoded P-256 `SubjectPublicKeyInfo` into a SEC1 point.
+pub fn compress_public_key_der(
+    public_key_der: &[u8],
+) -> Result<[u8; COMPRESSED_P256_KEY_LEN], VerifyError> {
+    let public_key = PublicKey::<NistP256>::from_public_key_der(public_key_der)?;
+    public_key
+        .to_encoded_point(true)
+        .as_bytes()
+        .try_into()
+        .map_err(|_| VerifyError::ExtractPublicKey)
+}
+
+/// Encodes a SEC1 P-256 point, compressed or not, as a DER `SubjectPublicKeyInfo`.
+///
+/// The point is checked to be on the curve.
+pub fn sec1_public_key_to_der(public_key_sec1: &[u8]) -> Result<Vec<u8>, VerifyError> {
+    let public_key = PublicKey::<NistP256>::from_sec1_bytes(public_key_sec1)
+        .map_err(|_| VerifyError::ExtractPublicKey)?;
+    Ok([
+        &P256_SPKI_HEADER[..],
+        public_key.to_encoded_point(false).as_bytes(),
+    ]
+    .concat())
+}
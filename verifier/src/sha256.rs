@@ -0,0 +1,19 @@
+//! SHA-256 into fixed-size arrays, without allocating, for `no_std` runtimes.
+
+use sha2::{digest::FixedOutputReset, Digest, Sha256};
+
+/// The SHA-256 of `data`.
+pub fn sha256_fixed(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    Digest::update(&mut hasher, data);
+    hasher.finalize_fixed_reset().into()
+}
+
+/// The SHA-256 of `part1 || part2`, without concatenating them: e.g. the digest an assertion
+/// signature is made over, of the authenticator data and the client data hash.
+pub fn sha256_two_part(part1: &[u8], part2: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    Digest::update(&mut hasher, part1);
+    Digest::update(&mut hasher, part2);
+    hasher.finalize_fixed_reset().into()
+}
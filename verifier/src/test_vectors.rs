@@ -0,0 +1,152 @@
+//! Decoded WebAuthn responses of a software authenticator, for the tests of this crate and of
+//! the crates building on it, rather than each rebuilding its inputs or embedding base64.
+//!
+//! Every vector is for the relying party [`RP_ID`] at [`ORIGIN`], answering [`CHALLENGE`].
+//! Public keys are DER-encoded `SubjectPublicKeyInfo`s, and signatures DER-encoded ECDSA ones.
+
+/// The RP ID the vectors are scoped to.
+pub const RP_ID: &str = "example.com";
+/// The origin the client data of the vectors is collected at.
+pub const ORIGIN: &str = "https://example.com";
+/// The challenge the vectors sign, base64url-encoded in their client data.
+pub const CHALLENGE: [u8; 32] = [0x07; 32];
+
+/// A registration: the attestation object and client data of a `create()` response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Registration {
+    pub attestation_object: &'static [u8],
+    /// The `authData` of the attestation object.
+    pub authenticator_data: &'static [u8],
+    pub client_data_json: &'static [u8],
+    pub credential_id: &'static [u8],
+    /// The credential public key the attested credential data carries.
+    pub public_key: &'static [u8],
+}
+
+/// An assertion: the authenticator data, client data and signature of a `get()` response, along
+/// with the public key of the credential it's made with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Assertion {
+    pub authenticator_data: &'static [u8],
+    pub client_data_json: &'static [u8],
+    pub signature: &'static [u8],
+    pub public_key: &'static [u8],
+}
+
+/// An ES256 credential registered with `packed` self-attestation, the user verified (`UP`, `UV`
+/// and `AT` set), with the all-zero AAGUID.
+pub const ES256_REGISTRATION: Registration = Registration {
+    attestation_object: &[
+        0xa3, 0x63, 0x66, 0x6d, 0x74, 0x66, 0x70, 0x61, 0x63, 0x6b, 0x65, 0x64, 0x67, 0x61, 0x74,
+        0x74, 0x53, 0x74, 0x6d, 0x74, 0xa2, 0x63, 0x61, 0x6c, 0x67, 0x26, 0x63, 0x73, 0x69, 0x67,
+        0x58, 0x48, 0x30, 0x46, 0x02, 0x21, 0x00, 0xb4, 0xb2, 0xfb, 0x1b, 0xfe, 0xeb, 0x6e, 0xe1,
+        0x9a, 0xa4, 0x9b, 0xd2, 0xc7, 0x89, 0x71, 0x04, 0x66, 0x7b, 0x33, 0xdd, 0x6d, 0x1b, 0x50,
+        0x58, 0x57, 0xac, 0xf2, 0x47, 0xda, 0xac, 0xb0, 0x4a, 0x02, 0x21, 0x00, 0xad, 0xcb, 0xb7,
+        0xb9, 0x8a, 0x67, 0x7e, 0x27, 0x81, 0x4f, 0x23, 0x5a, 0xe6, 0x56, 0x8c, 0x6e, 0xa4, 0x9c,
+        0xc4, 0xfb, 0x9b, 0x9f, 0xbe, 0x9d, 0x06, 0x39, 0xa9, 0xae, 0x17, 0x11, 0x82, 0xc8, 0x68,
+        0x61, 0x75, 0x74, 0x68, 0x44, 0x61, 0x74, 0x61, 0x58, 0x94, 0xa3, 0x79, 0xa6, 0xf6, 0xee,
+        0xaf, 0xb9, 0xa5, 0x5e, 0x37, 0x8c, 0x11, 0x80, 0x34, 0xe2, 0x75, 0x1e, 0x68, 0x2f, 0xab,
+        0x9f, 0x2d, 0x30, 0xab, 0x13, 0xd2, 0x12, 0x55, 0x86, 0xce, 0x19, 0x47, 0x45, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x10, 0x97, 0xa6, 0x64, 0x81, 0x8d, 0xbe, 0x47, 0xa1, 0xd3, 0x8d,
+        0x54, 0xc3, 0x40, 0x1d, 0x45, 0x9d, 0xa5, 0x01, 0x02, 0x03, 0x26, 0x20, 0x01, 0x21, 0x58,
+        0x20, 0x4f, 0x6e, 0xff, 0x78, 0x93, 0x03, 0x63, 0x88, 0xe6, 0x64, 0xa0, 0x37, 0x12, 0xcd,
+        0xbd, 0x32, 0xf4, 0x69, 0x99, 0x64, 0xe7, 0x9f, 0x89, 0x4f, 0xc8, 0xe9, 0x0c, 0x62, 0x4b,
+        0xaa, 0x31, 0x4d, 0x22, 0x58, 0x20, 0xb8, 0xe1, 0x8f, 0xbe, 0x76, 0x02, 0x48, 0x90, 0xd9,
+        0x35, 0x6a, 0xa3, 0x34, 0x79, 0x82, 0x12, 0x89, 0x7e, 0x94, 0x7e, 0x8f, 0xfd, 0xca, 0x8a,
+        0x12, 0xd1, 0x7f, 0x7e, 0x1e, 0x6f, 0x75, 0x33,
+    ],
+    authenticator_data: &[
+        0xa3, 0x79, 0xa6, 0xf6, 0xee, 0xaf, 0xb9, 0xa5, 0x5e, 0x37, 0x8c, 0x11, 0x80, 0x34, 0xe2,
+        0x75, 0x1e, 0x68, 0x2f, 0xab, 0x9f, 0x2d, 0x30, 0xab, 0x13, 0xd2, 0x12, 0x55, 0x86, 0xce,
+        0x19, 0x47, 0x45, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x97, 0xa6, 0x64, 0x81, 0x8d,
+        0xbe, 0x47, 0xa1, 0xd3, 0x8d, 0x54, 0xc3, 0x40, 0x1d, 0x45, 0x9d, 0xa5, 0x01, 0x02, 0x03,
+        0x26, 0x20, 0x01, 0x21, 0x58, 0x20, 0x4f, 0x6e, 0xff, 0x78, 0x93, 0x03, 0x63, 0x88, 0xe6,
+        0x64, 0xa0, 0x37, 0x12, 0xcd, 0xbd, 0x32, 0xf4, 0x69, 0x99, 0x64, 0xe7, 0x9f, 0x89, 0x4f,
+        0xc8, 0xe9, 0x0c, 0x62, 0x4b, 0xaa, 0x31, 0x4d, 0x22, 0x58, 0x20, 0xb8, 0xe1, 0x8f, 0xbe,
+        0x76, 0x02, 0x48, 0x90, 0xd9, 0x35, 0x6a, 0xa3, 0x34, 0x79, 0x82, 0x12, 0x89, 0x7e, 0x94,
+        0x7e, 0x8f, 0xfd, 0xca, 0x8a, 0x12, 0xd1, 0x7f, 0x7e, 0x1e, 0x6f, 0x75, 0x33,
+    ],
+    client_data_json: concat!(
+        r#"{"type":"webauthn.create","challenge":"BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc","#,
+        r#""origin":"https://example.com","crossOrigin":false}"#
+    )
+    .as_bytes(),
+    credential_id: &[
+        0x97, 0xa6, 0x64, 0x81, 0x8d, 0xbe, 0x47, 0xa1, 0xd3, 0x8d, 0x54, 0xc3, 0x40, 0x1d, 0x45,
+        0x9d,
+    ],
+    public_key: &[
+        0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08,
+        0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00, 0x04, 0x4f, 0x6e, 0xff,
+        0x78, 0x93, 0x03, 0x63, 0x88, 0xe6, 0x64, 0xa0, 0x37, 0x12, 0xcd, 0xbd, 0x32, 0xf4, 0x69,
+        0x99, 0x64, 0xe7, 0x9f, 0x89, 0x4f, 0xc8, 0xe9, 0x0c, 0x62, 0x4b, 0xaa, 0x31, 0x4d, 0xb8,
+        0xe1, 0x8f, 0xbe, 0x76, 0x02, 0x48, 0x90, 0xd9, 0x35, 0x6a, 0xa3, 0x34, 0x79, 0x82, 0x12,
+        0x89, 0x7e, 0x94, 0x7e, 0x8f, 0xfd, 0xca, 0x8a, 0x12, 0xd1, 0x7f, 0x7e, 0x1e, 0x6f, 0x75,
+        0x33,
+    ],
+};
+
+/// An assertion of the credential of [`ES256_REGISTRATION`], the user verified (`UP` and `UV`
+/// set), with a signature counter of 1.
+pub const ES256_ASSERTION: Assertion = Assertion {
+    authenticator_data: &[
+        0xa3, 0x79, 0xa6, 0xf6, 0xee, 0xaf, 0xb9, 0xa5, 0x5e, 0x37, 0x8c, 0x11, 0x80, 0x34, 0xe2,
+        0x75, 0x1e, 0x68, 0x2f, 0xab, 0x9f, 0x2d, 0x30, 0xab, 0x13, 0xd2, 0x12, 0x55, 0x86, 0xce,
+        0x19, 0x47, 0x05, 0x00, 0x00, 0x00, 0x01,
+    ],
+    client_data_json: concat!(
+        r#"{"type":"webauthn.get","challenge":"BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc","#,
+        r#""origin":"https://example.com","crossOrigin":false}"#
+    )
+    .as_bytes(),
+    signature: &[
+        0x30, 0x45, 0x02, 0x21, 0x00, 0xc9, 0xbe, 0x63, 0xfa, 0x7b, 0xe0, 0xd3, 0x0f, 0x75, 0x3b,
+        0x67, 0xab, 0x2f, 0xfc, 0x1f, 0x8d, 0x73, 0xdb, 0x3d, 0x56, 0x6c, 0x1d, 0xb2, 0xa5, 0xa4,
+        0x0b, 0x01, 0xbe, 0x66, 0xb3, 0x92, 0xa1, 0x02, 0x20, 0x3b, 0xfb, 0xf9, 0x0a, 0x4d, 0x8f,
+        0x69, 0xdf, 0x09, 0x57, 0x67, 0x1a, 0x36, 0xe4, 0x5a, 0x54, 0x54, 0xa3, 0x87, 0xc6, 0xdf,
+        0x55, 0x2e, 0x61, 0x51, 0xc1, 0x17, 0x0b, 0x76, 0xea, 0xb3, 0x50,
+    ],
+    public_key: &[
+        0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08,
+        0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00, 0x04, 0x4f, 0x6e, 0xff,
+        0x78, 0x93, 0x03, 0x63, 0x88, 0xe6, 0x64, 0xa0, 0x37, 0x12, 0xcd, 0xbd, 0x32, 0xf4, 0x69,
+        0x99, 0x64, 0xe7, 0x9f, 0x89, 0x4f, 0xc8, 0xe9, 0x0c, 0x62, 0x4b, 0xaa, 0x31, 0x4d, 0xb8,
+        0xe1, 0x8f, 0xbe, 0x76, 0x02, 0x48, 0x90, 0xd9, 0x35, 0x6a, 0xa3, 0x34, 0x79, 0x82, 0x12,
+        0x89, 0x7e, 0x94, 0x7e, 0x8f, 0xfd, 0xca, 0x8a, 0x12, 0xd1, 0x7f, 0x7e, 0x1e, 0x6f, 0x75,
+        0x33,
+    ],
+};
+
+/// An assertion of another ES256 credential, the user only present (`UP` set, `UV` not), with a
+/// signature counter of 1. Its signature is valid: rejecting it is up to relying parties
+/// requiring user verification.
+pub const ES256_ASSERTION_USER_PRESENT: Assertion = Assertion {
+    authenticator_data: &[
+        0xa3, 0x79, 0xa6, 0xf6, 0xee, 0xaf, 0xb9, 0xa5, 0x5e, 0x37, 0x8c, 0x11, 0x80, 0x34, 0xe2,
+        0x75, 0x1e, 0x68, 0x2f, 0xab, 0x9f, 0x2d, 0x30, 0xab, 0x13, 0xd2, 0x12, 0x55, 0x86, 0xce,
+        0x19, 0x47, 0x01, 0x00, 0x00, 0x00, 0x01,
+    ],
+    client_data_json: concat!(
+        r#"{"type":"webauthn.get","challenge":"BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc","#,
+        r#""origin":"https://example.com","crossOrigin":false}"#
+    )
+    .as_bytes(),
+    signature: &[
+        0x30, 0x44, 0x02, 0x20, 0x24, 0xf5, 0x79, 0xc9, 0x31, 0x41, 0x52, 0x55, 0x6e, 0x77, 0x15,
+        0x46, 0x4a, 0xef, 0xae, 0x96, 0x84, 0xe0, 0x46, 0x15, 0xc0, 0x96, 0x24, 0xbe, 0x42, 0xb9,
+        0xc3, 0x3e, 0xad, 0x24, 0xf5, 0x93, 0x02, 0x20, 0x04, 0x74, 0x76, 0xfb, 0x87, 0xc1, 0xb8,
+        0x89, 0x6e, 0x8d, 0x17, 0x17, 0x5c, 0x49, 0xbc, 0xd9, 0x2a, 0x28, 0xf8, 0x7c, 0xbf, 0xf1,
+        0x3c, 0x75, 0xea, 0xb4, 0xd5, 0x4a, 0x58, 0xdc, 0xd5, 0x97,
+    ],
+    public_key: &[
+        0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08,
+        0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00, 0x04, 0xfb, 0x6b, 0x4b,
+        0xac, 0xd2, 0x91, 0x64, 0xfd, 0x55, 0xb1, 0xc8, 0xe5, 0xd2, 0x55, 0xa1, 0x3b, 0xaa, 0xb7,
+        0x4e, 0x05, 0xf0, 0x1d, 0xab, 0x9e, 0x9f, 0x50, 0x72, 0xee, 0x63, 0xa3, 0xfd, 0x82, 0x9f,
+        0xeb, 0x30, 0x36, 0x28, 0xcd, 0x04, 0xf6, 0x37, 0x0f, 0x82, 0xdb, 0x0d, 0x92, 0x1a, 0xfa,
+        0xe7, 0xac, 0x32, 0xe9, 0x9f, 0x9d, 0xaf, 0x59, 0xc5, 0xd3, 0x09, 0x68, 0xce, 0xf8, 0xf7,
+        0xf5,
+    ],
+};
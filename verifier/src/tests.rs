@@ -1,9 +1,12 @@
 use super::*;
 use coset::{
     iana::{Algorithm, EllipticCurve},
-    CoseKeyBuilder,
+    CborSerializable, CoseKeyBuilder,
+};
+use p256::ecdsa::{
+    signature::{Signer, Verifier},
+    Signature, SigningKey,
 };
-use p256::ecdsa::{signature::Signer, Signature, SigningKey};
 use passkey_authenticator::public_key_der_from_cose_key;
 use rand::rngs::OsRng;
 use sha2::{Digest, Sha256};
@@ -45,10 +48,8 @@ fn test_verify_webauthn_response_with_generated_data() {
     message.extend_from_slice(authenticator_data);
     message.extend_from_slice(&client_data_hash);
 
-    // Step 6: Sign the message to get the signature in DER format
-    // ? Should this be in COSE format? I couldn't get coset::CoseSign1 to work
-    // ? https://github.com/google/coset/blob/main/examples/signature.rs
-    // ? Check the above link for an example of how to sign a message in COSE format
+    // Step 6: Sign the message to get the signature in DER format, as authenticators do: WebAuthn
+    // signatures are plain ECDSA signatures, not `COSE_Sign1` structures
     let signature: Signature = private_key.sign(&message);
     let signature_der = signature.to_der();
 
@@ -118,3 +119,2736 @@ fn test_verify_webauthn_response_with_invalid_signature() {
         );
     }
 }
+
+#[test]
+fn test_client_data_hash_uses_raw_bytes() {
+    let client_data_json = br#"{"type":"webauthn.get","challenge":"test-challenge"}"#;
+    let reserialized = br#"{ "type": "webauthn.get", "challenge": "test-challenge" }"#;
+
+    assert_eq!(
+        client_data_hash(client_data_json),
+        <[u8; 32]>::from(Sha256::digest(client_data_json))
+    );
+    assert_ne!(
+        client_data_hash(client_data_json),
+        client_data_hash(reserialized)
+    );
+}
+
+#[test]
+fn test_cose_key_algorithm() {
+    let public_key = SigningKey::random(&mut OsRng)
+        .verifying_key()
+        .to_encoded_point(false);
+    let x = public_key.x().unwrap().to_vec();
+    let y = public_key.y().unwrap().to_vec();
+
+    let es256_key = CoseKeyBuilder::new_ec2_pub_key(EllipticCurve::P_256, x.clone(), y.clone())
+        .algorithm(Algorithm::ES256)
+        .build();
+    assert!(matches!(
+        cose_key_algorithm(&es256_key),
+        Ok(CoseAlgorithm::ES256)
+    ));
+
+    let no_alg_key =
+        CoseKeyBuilder::new_ec2_pub_key(EllipticCurve::P_256, x.clone(), y.clone()).build();
+    assert!(matches!(
+        cose_key_algorithm(&no_alg_key),
+        Err(VerifyError::MissingAlgorithm)
+    ));
+
+    let es384_key = CoseKeyBuilder::new_ec2_pub_key(EllipticCurve::P_256, x, y)
+        .algorithm(Algorithm::ES384)
+        .build();
+    assert!(matches!(
+        cose_key_algorithm(&es384_key),
+        Err(VerifyError::UnsupportedAlgorithm)
+    ));
+}
+
+#[test]
+fn test_parse_authenticator_data_with_attested_credential_data() {
+    let public_key = SigningKey::random(&mut OsRng)
+        .verifying_key()
+        .to_encoded_point(false);
+    let x = public_key.x().unwrap().to_vec();
+    let y = public_key.y().unwrap().to_vec();
+    let public_key_cose = CoseKeyBuilder::new_ec2_pub_key(EllipticCurve::P_256, x, y)
+        .algorithm(Algorithm::ES256)
+        .build();
+
+    let credential_id = [0xCA, 0xFE, 0xBA, 0xBE];
+    let authenticator_data = [
+        &[0x11; 32][..],
+        &[0x41],
+        &7u32.to_be_bytes(),
+        &[0x22; 16],
+        &(credential_id.len() as u16).to_be_bytes(),
+        &credential_id,
+        &public_key_cose.clone().to_vec().unwrap(),
+    ]
+    .concat();
+
+    let parsed = parse_authenticator_data(&authenticator_data).expect("valid authenticator data");
+    assert_eq!(parsed.rp_id_hash, [0x11; 32]);
+    assert_eq!(parsed.flags, AuthenticatorDataFlags::from(0x41));
+    assert_eq!(parsed.sign_count, 7);
+    assert_eq!(parsed.extensions, None);
+    assert_eq!(parsed.encode(), authenticator_data);
+
+    let attested_credential_data = parsed
+        .attested_credential_data
+        .expect("AT flag is set; qed");
+    assert_eq!(attested_credential_data.aaguid, [0x22; 16]);
+    assert_eq!(attested_credential_data.credential_id, credential_id);
+    assert_eq!(
+        attested_credential_data.credential_public_key,
+        public_key_cose
+    );
+
+    assert_eq!(
+        cose_key_to_der(&public_key_cose).expect("valid ES256 key"),
+        public_key_der_from_cose_key(&public_key_cose).expect("valid ES256 key")
+    );
+
+    // Truncated attested credential data
+    assert!(matches!(
+        parse_authenticator_data(&authenticator_data[..authenticator_data.len() - 1]),
+        Err(VerifyError::ParseAuthenticatorData)
+    ));
+}
+
+#[test]
+fn test_parse_authenticator_data_boundaries() {
+    let mut authenticator_data = [0x42; 38];
+    authenticator_data[32] = AuthenticatorDataFlags::USER_PRESENT.bits();
+    authenticator_data[33..37].copy_from_slice(&7u32.to_be_bytes());
+
+    for len in 31..=38 {
+        let parsed = parse_authenticator_data(&authenticator_data[..len]);
+        if len == 37 {
+            let parsed = parsed.expect("The RP ID hash, flags and counter are all there");
+            assert_eq!(parsed.rp_id_hash, [0x42; 32]);
+            assert_eq!(parsed.flags, AuthenticatorDataFlags::USER_PRESENT);
+            assert_eq!(parsed.sign_count, 7);
+        } else {
+            assert_eq!(
+                parsed,
+                Err(VerifyError::ParseAuthenticatorData),
+                "{len} bytes"
+            );
+        }
+    }
+
+    // With extensions, whatever follows the counter is theirs.
+    authenticator_data[32] |= AuthenticatorDataFlags::EXTENSION_DATA.bits();
+    for len in 31..=36 {
+        assert_eq!(
+            parse_authenticator_data(&authenticator_data[..len]),
+            Err(VerifyError::ParseAuthenticatorData),
+            "{len} bytes"
+        );
+    }
+    assert_eq!(
+        parse_authenticator_data(&authenticator_data)
+            .map(|parsed| parsed.extensions)
+            .expect("The extensions follow the counter"),
+        Some(&[0x42][..])
+    );
+}
+
+#[test]
+fn test_sign_count_from_authenticator_data() {
+    for sign_count in [0, 1, 0xff_ff_ff_ff, 0x01_00_00_00] {
+        let authenticator_data = AuthenticatorData {
+            rp_id_hash: [0x42; 32],
+            flags: AuthenticatorDataFlags::USER_PRESENT,
+            sign_count,
+            attested_credential_data: None,
+            extensions: None,
+        };
+        let encoded = authenticator_data.encode();
+        assert_eq!(encoded[33..37], sign_count.to_be_bytes());
+        assert_eq!(sign_count_from_authenticator_data(&encoded), Ok(sign_count));
+        assert_eq!(
+            parse_authenticator_data(&encoded).map(|parsed| parsed.sign_count()),
+            Ok(sign_count)
+        );
+    }
+
+    // Whatever follows the counter isn't looked at.
+    let mut authenticator_data = [0xff; 40];
+    authenticator_data[33..37].copy_from_slice(&[0x01, 0x00, 0x00, 0x00]);
+    assert_eq!(
+        sign_count_from_authenticator_data(&authenticator_data),
+        Ok(0x01_00_00_00)
+    );
+    assert_eq!(
+        sign_count_from_authenticator_data(&authenticator_data[..36]),
+        Err(ParseError::InsufficientData)
+    );
+    assert_eq!(
+        sign_count_from_authenticator_data(&[]),
+        Err(ParseError::InsufficientData)
+    );
+}
+
+#[test]
+fn test_parse_authenticator_data_partially() {
+    let credential_id = [0xCA, 0xFE, 0xBA, 0xBE];
+    let authenticator_data = [
+        &[0x11; 32][..],
+        &[0x41],
+        &7u32.to_be_bytes(),
+        &[0x22; 16],
+        &(credential_id.len() as u16).to_be_bytes(),
+        &credential_id,
+        // A CBOR map of one entry, without it.
+        &[0xa1],
+    ]
+    .concat();
+
+    let (partial, error) = parse_authenticator_data_partially(&authenticator_data)
+        .expect_err("The public key is truncated");
+    assert_eq!(error, VerifyError::ParseAuthenticatorData);
+    assert_eq!(
+        partial,
+        PartialAuthenticatorData {
+            rp_id_hash: Some([0x11; 32]),
+            flags: Some(AuthenticatorDataFlags::from(0x41)),
+            sign_count: Some(7),
+            aaguid: Some([0x22; 16]),
+            credential_id: Some(&credential_id),
+        }
+    );
+
+    let (partial, _) = parse_authenticator_data_partially(&authenticator_data[..34])
+        .expect_err("The counter is truncated");
+    assert_eq!(
+        partial,
+        PartialAuthenticatorData {
+            rp_id_hash: Some([0x11; 32]),
+            flags: Some(AuthenticatorDataFlags::from(0x41)),
+            ..Default::default()
+        }
+    );
+
+    let mut assertion = authenticator_data[..37].to_vec();
+    assertion[32] = AuthenticatorDataFlags::USER_PRESENT.bits();
+    assert_eq!(
+        parse_authenticator_data_partially(&assertion),
+        Ok(parse_authenticator_data(&assertion).unwrap())
+    );
+}
+
+#[test]
+fn test_prf_results() {
+    use coset::cbor::{ser::into_writer, value::Value};
+
+    let text = |s: &str| Value::Text(s.into());
+    let prf_extension = |results: Vec<(Value, Value)>| {
+        let extensions = Value::Map(vec![(
+            text("prf"),
+            Value::Map(vec![(text("results"), Value::Map(results))]),
+        )]);
+        let mut encoded = Vec::new();
+        into_writer(&extensions, &mut encoded).unwrap();
+        encoded
+    };
+    let authenticator_data = |extensions: &[u8]| {
+        AuthenticatorData {
+            rp_id_hash: Sha256::digest(b"example.com").into(),
+            // `UP` and `ED`.
+            flags: AuthenticatorDataFlags::from(0x81),
+            sign_count: 7,
+            attested_credential_data: None,
+            extensions: Some(extensions),
+        }
+        .encode()
+    };
+    let prf_results = |extensions: &[u8]| {
+        parse_authenticator_data(&authenticator_data(extensions))
+            .unwrap()
+            .prf_results()
+    };
+
+    let both = prf_extension(vec![
+        (text("first"), Value::Bytes(vec![1; 32])),
+        (text("second"), Value::Bytes(vec![2; 32])),
+    ]);
+    assert_eq!(
+        prf_results(&both),
+        Some(PrfOutputs {
+            first: [1; 32],
+            second: Some([2; 32]),
+        })
+    );
+    let first = prf_extension(vec![(text("first"), Value::Bytes(vec![1; 32]))]);
+    assert_eq!(
+        prf_results(&first),
+        Some(PrfOutputs {
+            first: [1; 32],
+            second: None,
+        })
+    );
+
+    // Outputs that aren't 32 bytes, a missing `first`, other extensions, trailing bytes.
+    let short = prf_extension(vec![(text("first"), Value::Bytes(vec![1; 31]))]);
+    let only_second = prf_extension(vec![(text("second"), Value::Bytes(vec![2; 32]))]);
+    let bad_second = prf_extension(vec![
+        (text("first"), Value::Bytes(vec![1; 32])),
+        (text("second"), Value::Bytes(vec![2; 33])),
+    ]);
+    let mut cred_protect = Vec::new();
+    into_writer(
+        &Value::Map(vec![(text("credProtect"), 2.into())]),
+        &mut cred_protect,
+    )
+    .unwrap();
+    let trailing = [both.as_slice(), &[0]].concat();
+    for extensions in [short, only_second, bad_second, cred_protect, trailing] {
+        assert_eq!(prf_results(&extensions), None);
+    }
+    assert_eq!(
+        parse_authenticator_data(&authenticator_data(&both)[..37])
+            .unwrap()
+            .prf_results(),
+        None
+    );
+}
+
+#[test]
+fn test_cose_key_without_algorithm() {
+    let public_key = SigningKey::random(&mut OsRng)
+        .verifying_key()
+        .to_encoded_point(false);
+    let x = public_key.x().unwrap().to_vec();
+    let y = public_key.y().unwrap().to_vec();
+
+    let no_alg_key =
+        CoseKeyBuilder::new_ec2_pub_key(EllipticCurve::P_256, x.clone(), y.clone()).build();
+    let es256_key = CoseKeyBuilder::new_ec2_pub_key(EllipticCurve::P_256, x.clone(), y.clone())
+        .algorithm(Algorithm::ES256)
+        .build();
+
+    // Rejected by default
+    assert!(matches!(
+        cose_key_to_der(&no_alg_key),
+        Err(VerifyError::MissingAlgorithm)
+    ));
+
+    // Inferred from the key type and curve when allowed
+    assert!(matches!(
+        cose_key_algorithm_with_policy(&no_alg_key, MissingAlgorithmPolicy::InferFromKeyType),
+        Ok(CoseAlgorithm::ES256)
+    ));
+    assert_eq!(
+        cose_key_to_der_with_policy(&no_alg_key, MissingAlgorithmPolicy::InferFromKeyType)
+            .expect("inferred as ES256"),
+        cose_key_to_der(&es256_key).expect("valid ES256 key")
+    );
+
+    // An unexpected `alg` is never overridden by inference
+    let unexpected_alg_key = CoseKeyBuilder::new_ec2_pub_key(EllipticCurve::P_256, x, y)
+        .algorithm(Algorithm::EdDSA)
+        .build();
+    assert!(matches!(
+        cose_key_to_der_with_policy(
+            &unexpected_alg_key,
+            MissingAlgorithmPolicy::InferFromKeyType
+        ),
+        Err(VerifyError::UnsupportedAlgorithm)
+    ));
+}
+
+#[test]
+fn test_parse_attestation_object() {
+    use coset::cbor::{ser::into_writer, value::Value};
+
+    let encode = |fmt: &str, att_stmt: Vec<(Value, Value)>| {
+        let mut encoded = Vec::new();
+        into_writer(
+            &Value::Map(vec![
+                (Value::Text("fmt".into()), Value::Text(fmt.into())),
+                (Value::Text("attStmt".into()), Value::Map(att_stmt)),
+                (Value::Text("authData".into()), Value::Bytes(vec![0xaa; 37])),
+            ]),
+            &mut encoded,
+        )
+        .expect("Writing to a vector doesn't fail");
+        encoded
+    };
+
+    let none = parse_attestation_object(&encode("none", vec![])).expect("valid `none`");
+    assert_eq!(none.auth_data, vec![0xaa; 37]);
+    assert_eq!(none.statement, AttestationStatement::None);
+
+    let signing_key = SigningKey::random(&mut OsRng);
+    let signature: Signature = signing_key.sign(b"authData || clientDataHash");
+    let sig = signature.to_der().as_bytes().to_vec();
+    let packed = parse_attestation_object(&encode(
+        "packed",
+        vec![
+            (
+                Value::Text("alg".into()),
+                Value::Integer((Algorithm::ES256 as i64).into()),
+            ),
+            (Value::Text("sig".into()), Value::Bytes(sig.clone())),
+        ],
+    ))
+    .expect("valid `packed` self-attestation");
+    assert_eq!(
+        packed.statement,
+        AttestationStatement::Packed {
+            alg: CoseAlgorithm::ES256,
+            sig
+        }
+    );
+
+    assert!(matches!(
+        parse_attestation_object(&encode(
+            "none",
+            vec![(Value::Text("sig".into()), Value::Bytes(vec![]))]
+        )),
+        Err(VerifyError::ParseAttestationObject)
+    ));
+    assert!(matches!(
+        parse_attestation_object(&encode("tpm", vec![])),
+        Err(VerifyError::UnsupportedAttestationFormat)
+    ));
+    assert!(matches!(
+        parse_attestation_object(b"not cbor"),
+        Err(VerifyError::ParseAttestationObject)
+    ));
+}
+
+#[test]
+fn test_verify_algorithm_offered() {
+    let public_key = SigningKey::random(&mut OsRng)
+        .verifying_key()
+        .to_encoded_point(false);
+    let x = public_key.x().unwrap().to_vec();
+    let y = public_key.y().unwrap().to_vec();
+    let registration = |alg: Option<Algorithm>| {
+        let mut key = CoseKeyBuilder::new_ec2_pub_key(EllipticCurve::P_256, x.clone(), y.clone());
+        if let Some(alg) = alg {
+            key = key.algorithm(alg);
+        }
+        AuthenticatorData {
+            rp_id_hash: Sha256::digest(b"example.com").into(),
+            // `UP`, `UV` and `AT`.
+            flags: AuthenticatorDataFlags::from(0x45),
+            sign_count: 0,
+            attested_credential_data: Some(AttestedCredentialData {
+                aaguid: [0; 16],
+                credential_id: &[0x01; 16],
+                credential_public_key: key.build(),
+            }),
+            extensions: None,
+        }
+        .encode()
+    };
+
+    let es256 = registration(Some(Algorithm::ES256));
+    assert_eq!(
+        verify_algorithm_offered(&es256, &[Algorithm::ES256]),
+        Ok(())
+    );
+    assert_eq!(
+        verify_algorithm_offered(&es256, &[Algorithm::EdDSA, Algorithm::ES256]),
+        Ok(())
+    );
+
+    // The authenticator registered with an algorithm the relying party didn't offer.
+    assert_eq!(
+        verify_algorithm_offered(&es256, &[Algorithm::EdDSA, Algorithm::ES384]),
+        Err(VerifyError::AlgorithmNotOffered)
+    );
+    assert_eq!(
+        verify_algorithm_offered(&es256, &[]),
+        Err(VerifyError::AlgorithmNotOffered)
+    );
+    assert_eq!(
+        verify_algorithm_offered(&registration(Some(Algorithm::ES384)), &[Algorithm::ES256]),
+        Err(VerifyError::AlgorithmNotOffered)
+    );
+
+    assert_eq!(
+        verify_algorithm_offered(&registration(None), &[Algorithm::ES256]),
+        Err(VerifyError::MissingAlgorithm)
+    );
+    assert_eq!(
+        verify_algorithm_offered(&es256[..37], &[Algorithm::ES256]),
+        Err(VerifyError::ParseAuthenticatorData)
+    );
+}
+
+#[test]
+fn test_supported_attestation_formats() {
+    let formats: Vec<_> = all_supported_formats().collect();
+    for format in [AttestationFormat::None, AttestationFormat::Packed] {
+        assert_eq!(formats.iter().filter(|&&f| f == format).count(), 1);
+    }
+    assert_eq!(formats.len(), 2);
+
+    let names: Vec<_> = formats.iter().map(ToString::to_string).collect();
+    assert_eq!(names, ["none", "packed"]);
+    assert_eq!(AttestationStatement::None.format(), AttestationFormat::None);
+}
+
+#[cfg(feature = "webauthn-rs-interop")]
+#[test]
+fn test_webauthn_rs_key_to_der() {
+    use webauthn_rs_core::proto::{COSEAlgorithm, COSEEC2Key, COSEKey, COSEKeyType, ECDSACurve};
+
+    let public_key = SigningKey::random(&mut OsRng)
+        .verifying_key()
+        .to_encoded_point(false);
+    let x = public_key.x().unwrap().to_vec();
+    let y = public_key.y().unwrap().to_vec();
+
+    let webauthn_rs_key = COSEKey {
+        type_: COSEAlgorithm::ES256,
+        key: COSEKeyType::EC_EC2(COSEEC2Key {
+            curve: ECDSACurve::SECP256R1,
+            x: x.clone().into(),
+            y: y.clone().into(),
+        }),
+    };
+    let cose_key = CoseKeyBuilder::new_ec2_pub_key(EllipticCurve::P_256, x, y)
+        .algorithm(Algorithm::ES256)
+        .build();
+
+    assert_eq!(
+        webauthn_rs_key_to_der(&webauthn_rs_key).unwrap(),
+        cose_key_to_der(&cose_key).unwrap()
+    );
+
+    let rs256_key = COSEKey {
+        type_: COSEAlgorithm::RS256,
+        ..webauthn_rs_key
+    };
+    assert!(matches!(
+        webauthn_rs_key_to_der(&rs256_key),
+        Err(VerifyError::UnsupportedAlgorithm)
+    ));
+}
+
+#[test]
+fn test_check_client_data() {
+    let client_data_json =
+        br#"{"type":"webauthn.get","challenge":"dGVzdA","origin":"https://example.com"}"#;
+    let reserialized = br#"{
+        "challenge": "dGVzdA",
+        "origin": "https://example.com",
+        "type": "webauthn.get"
+    }"#;
+
+    assert!(check_client_data(client_data_json, None).is_ok());
+    assert!(check_client_data(client_data_json, Some(client_data_json)).is_ok());
+    assert!(check_client_data(reserialized, None).is_ok());
+    assert_eq!(
+        check_client_data(reserialized, Some(client_data_json)),
+        Err(VerifyError::ClientDataModified)
+    );
+    assert_eq!(
+        check_client_data(
+            br#"{"type":"webauthn.get","origin":"https://example.com"}"#,
+            None
+        ),
+        Err(VerifyError::ParseClientData)
+    );
+    assert_eq!(
+        check_client_data(
+            br#"{"type":"webauthn.get","challenge":42,"origin":"https://example.com"}"#,
+            None
+        ),
+        Err(VerifyError::ParseClientData)
+    );
+    assert_eq!(
+        check_client_data(
+            br#"{"type":"webauthn.get","note":"\"challenge\":\"x\"","origin":"o"}"#,
+            None
+        ),
+        Err(VerifyError::ParseClientData)
+    );
+    assert_eq!(
+        check_client_data(b"\xff", None),
+        Err(VerifyError::ParseClientData)
+    );
+}
+
+#[test]
+fn test_webauthn_verify_with_challenge() {
+    let authenticator_data = b"example authenticator data";
+    // "dGVzdA" is "test" in base64url.
+    let client_data_json = br#"{"type":"webauthn.get","challenge":"dGVzdA","origin":"o"}"#;
+
+    let signing_key = SigningKey::random(&mut OsRng);
+    let public_key = signing_key.verifying_key().to_encoded_point(false);
+    let public_key_der = sec1_public_key_to_der(public_key.as_bytes()).unwrap();
+    let signature: Signature =
+        signing_key.sign(&[&authenticator_data[..], &client_data_hash(client_data_json)].concat());
+    let signature = signature.to_der();
+    let verify = |signature: &[u8], expected_challenge: &[u8]| {
+        webauthn_verify_with_challenge(
+            authenticator_data,
+            client_data_json,
+            signature,
+            &public_key_der,
+            expected_challenge,
+        )
+    };
+
+    assert_eq!(verify(signature.as_bytes(), b"test"), Ok(()));
+    assert_eq!(
+        verify(signature.as_bytes(), b"tesT"),
+        Err(VerifyError::ChallengeMismatch)
+    );
+    assert_eq!(
+        verify(signature.as_bytes(), b"test "),
+        Err(VerifyError::ChallengeMismatch)
+    );
+
+    let mut tampered = signature.as_bytes().to_vec();
+    let last = tampered.len() - 1;
+    tampered[last] ^= 1;
+    assert_eq!(
+        verify(&tampered, b"test"),
+        Err(VerifyError::VerifySignature)
+    );
+
+    assert_eq!(
+        verify_challenge(
+            br#"{"type":"webauthn.get","challenge":"dGVzdA==","origin":"o"}"#,
+            b"test"
+        ),
+        Err(VerifyError::ChallengeMismatch)
+    );
+}
+
+#[test]
+fn test_webauthn_verify_with_algorithm() {
+    let authenticator_data = b"example authenticator data";
+    let client_data_json = br#"{"type":"webauthn.get","challenge":"dGVzdA","origin":"o"}"#;
+
+    let signing_key = SigningKey::random(&mut OsRng);
+    let public_key = signing_key.verifying_key().to_encoded_point(false);
+    let public_key_der = cose_key_to_der(
+        &CoseKeyBuilder::new_ec2_pub_key(
+            EllipticCurve::P_256,
+            public_key.x().unwrap().to_vec(),
+            public_key.y().unwrap().to_vec(),
+        )
+        .algorithm(Algorithm::ES256)
+        .build(),
+    )
+    .unwrap();
+
+    let message = [
+        authenticator_data.as_slice(),
+        &client_data_hash(client_data_json),
+    ]
+    .concat();
+    let signature: Signature = signing_key.sign(&message);
+
+    assert_eq!(
+        webauthn_verify_with_algorithm(
+            CoseAlgorithm::ES256,
+            authenticator_data,
+            client_data_json,
+            signature.to_der().as_bytes(),
+            &public_key_der
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        webauthn_verify_with_algorithm(
+            CoseAlgorithm::ES256,
+            authenticator_data,
+            client_data_json,
+            // A raw, 64 byte signature, as EdDSA produces them.
+            &[0x42; 64],
+            &public_key_der
+        ),
+        Err(VerifyError::AlgorithmMismatch)
+    );
+}
+
+#[test]
+fn test_verify_backup_state_consistency() {
+    use AuthenticatorDataFlags as F;
+
+    let not_eligible = F::from(F::USER_PRESENT.bits());
+    let eligible = F::from(F::USER_PRESENT.bits() | F::BACKUP_ELIGIBLE.bits());
+    let backed_up =
+        F::from(F::USER_PRESENT.bits() | F::BACKUP_ELIGIBLE.bits() | F::BACKED_UP.bits());
+    let backed_up_but_not_eligible = F::from(F::USER_PRESENT.bits() | F::BACKED_UP.bits());
+
+    // BE: 0 -> 0, 0 -> 1, 1 -> 1 are fine
+    assert_eq!(
+        verify_backup_state_consistency(not_eligible, not_eligible),
+        Ok(())
+    );
+    assert_eq!(
+        verify_backup_state_consistency(not_eligible, eligible),
+        Ok(())
+    );
+    assert_eq!(verify_backup_state_consistency(eligible, eligible), Ok(()));
+    assert_eq!(verify_backup_state_consistency(eligible, backed_up), Ok(()));
+    assert_eq!(verify_backup_state_consistency(backed_up, eligible), Ok(()));
+
+    // BE: 1 -> 0 is not
+    assert_eq!(
+        verify_backup_state_consistency(eligible, not_eligible),
+        Err(VerifyError::BackupStateInconsistency)
+    );
+
+    // BS implies BE
+    assert_eq!(
+        verify_backup_state_consistency(not_eligible, backed_up_but_not_eligible),
+        Err(VerifyError::BackupStateInconsistency)
+    );
+    assert_eq!(
+        verify_backup_state_consistency(backed_up_but_not_eligible, backed_up),
+        Err(VerifyError::BackupStateInconsistency)
+    );
+}
+
+#[test]
+fn test_verify_observer() {
+    #[derive(Default)]
+    struct CountingObserver {
+        started: usize,
+        succeeded: usize,
+        failed: usize,
+    }
+
+    impl VerifyObserver for CountingObserver {
+        fn on_start(&mut self, alg: CoseAlgorithm) {
+            assert_eq!(alg, CoseAlgorithm::ES256);
+            self.started += 1;
+        }
+
+        fn on_finish(
+            &mut self,
+            _alg: CoseAlgorithm,
+            outcome: Result<(), VerifyError>,
+            _elapsed: core::time::Duration,
+        ) {
+            match outcome {
+                Ok(()) => self.succeeded += 1,
+                Err(_) => self.failed += 1,
+            }
+        }
+    }
+
+    let authenticator_data = b"example authenticator data";
+    let client_data_json = br#"{"type":"webauthn.get","challenge":"dGVzdA","origin":"o"}"#;
+    let signing_key = SigningKey::random(&mut OsRng);
+    let public_key = signing_key.verifying_key().to_encoded_point(false);
+    let public_key_der = cose_key_to_der(
+        &CoseKeyBuilder::new_ec2_pub_key(
+            EllipticCurve::P_256,
+            public_key.x().unwrap().to_vec(),
+            public_key.y().unwrap().to_vec(),
+        )
+        .algorithm(Algorithm::ES256)
+        .build(),
+    )
+    .unwrap();
+    let signature: Signature = signing_key.sign(
+        &[
+            authenticator_data.as_slice(),
+            &client_data_hash(client_data_json),
+        ]
+        .concat(),
+    );
+    let signature = signature.to_der();
+
+    let mut observer = CountingObserver::default();
+    let verify = |observer: &mut CountingObserver, signature: &[u8]| {
+        webauthn_verify_observed(
+            observer,
+            CoseAlgorithm::ES256,
+            authenticator_data,
+            client_data_json,
+            signature,
+            &public_key_der,
+        )
+    };
+
+    assert_eq!(verify(&mut observer, signature.as_bytes()), Ok(()));
+    assert_eq!(verify(&mut observer, signature.as_bytes()), Ok(()));
+    assert!(verify(&mut observer, &[0x30, 0x00]).is_err());
+
+    assert_eq!(observer.started, 3);
+    assert_eq!(observer.succeeded, 2);
+    assert_eq!(observer.failed, 1);
+
+    // The no-op observer doesn't change the outcome
+    assert_eq!(
+        webauthn_verify_observed(
+            &mut (),
+            CoseAlgorithm::ES256,
+            authenticator_data,
+            client_data_json,
+            signature.as_bytes(),
+            &public_key_der,
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_compressed_public_key() {
+    let authenticator_data = b"example authenticator data";
+    let client_data_json = br#"{"type":"webauthn.get","challenge":"dGVzdA","origin":"o"}"#;
+
+    let signing_key = SigningKey::random(&mut OsRng);
+    let public_key = signing_key.verifying_key().to_encoded_point(false);
+    let public_key_der = sec1_public_key_to_der(public_key.as_bytes()).unwrap();
+
+    let compressed = compress_public_key_der(&public_key_der).unwrap();
+    assert_eq!(compressed.len(), COMPRESSED_P256_KEY_LEN);
+    assert_eq!(sec1_public_key_to_der(&compressed), Ok(public_key_der));
+
+    let message = [
+        authenticator_data.as_slice(),
+        &client_data_hash(client_data_json),
+    ]
+    .concat();
+    let signature: Signature = signing_key.sign(&message);
+
+    assert_eq!(
+        webauthn_verify_sec1(
+            authenticator_data,
+            client_data_json,
+            signature.to_der().as_bytes(),
+            &compressed
+        ),
+        Ok(())
+    );
+    // The x coordinate exceeds the field modulus.
+    let mut off_curve = [0xff; COMPRESSED_P256_KEY_LEN];
+    off_curve[0] = 0x02;
+    assert_eq!(
+        sec1_public_key_to_der(&off_curve),
+        Err(VerifyError::ExtractPublicKey)
+    );
+}
+
+#[test]
+fn test_uncompressed_point_public_key() {
+    let authenticator_data = b"example authenticator data";
+    let client_data_json = br#"{"type":"webauthn.get","challenge":"dGVzdA","origin":"o"}"#;
+
+    let signing_key = SigningKey::random(&mut OsRng);
+    let encoded = signing_key.verifying_key().to_encoded_point(false);
+    let point: [u8; UNCOMPRESSED_P256_KEY_LEN] = encoded.as_bytes().try_into().unwrap();
+
+    let message = [
+        authenticator_data.as_slice(),
+        &client_data_hash(client_data_json),
+    ]
+    .concat();
+    let signature: Signature = signing_key.sign(&message);
+    let verify = |input| {
+        webauthn_verify_public_key(
+            authenticator_data,
+            client_data_json,
+            signature.to_der().as_bytes(),
+            input,
+        )
+    };
+
+    assert_eq!(verify(PublicKeyInput::UncompressedPoint(&point)), Ok(()));
+    let der = sec1_public_key_to_der(&point).unwrap();
+    assert_eq!(verify(PublicKeyInput::Der(&der)), Ok(()));
+
+    // Wrong lengths, compressed points included.
+    let compressed = signing_key.verifying_key().to_encoded_point(true);
+    let extended = [point.as_slice(), &[0]].concat();
+    for input in [&point[..64], &extended, compressed.as_bytes()] {
+        assert_eq!(
+            verify(PublicKeyInput::UncompressedPoint(input)),
+            Err(VerifyError::ExtractPublicKey)
+        );
+    }
+    // Not tagged as an uncompressed point.
+    let mut untagged = point;
+    untagged[0] = 0x02;
+    assert_eq!(
+        verify(PublicKeyInput::UncompressedPoint(&untagged)),
+        Err(VerifyError::ExtractPublicKey)
+    );
+    // Not on the curve.
+    let mut off_curve = point;
+    off_curve[64] ^= 0x01;
+    assert_eq!(
+        verify(PublicKeyInput::UncompressedPoint(&off_curve)),
+        Err(VerifyError::ExtractPublicKey)
+    );
+}
+
+#[test]
+fn test_cose_key_from_public_key() {
+    let signing_key = SigningKey::random(&mut OsRng);
+    let public_key = p256::PublicKey::from(signing_key.verifying_key());
+    let cose_key = cose_key_from_public_key(&public_key);
+
+    assert_eq!(cose_key_algorithm(&cose_key), Ok(CoseAlgorithm::ES256));
+    assert_eq!(cose_key_to_public_key(&cose_key), Ok(public_key));
+    assert_eq!(
+        cose_key_to_der(&cose_key),
+        sec1_public_key_to_der(
+            signing_key
+                .verifying_key()
+                .to_encoded_point(true)
+                .as_bytes()
+        )
+    );
+}
+
+#[cfg(feature = "pem")]
+#[test]
+fn test_webauthn_verify_pem() {
+    use p256::pkcs8::{EncodePublicKey, LineEnding};
+
+    let authenticator_data = b"example authenticator data";
+    let client_data_json = br#"{"type":"webauthn.get","challenge":"dGVzdA","origin":"o"}"#;
+
+    let signing_key = SigningKey::random(&mut OsRng);
+    let public_key_pem = signing_key
+        .verifying_key()
+        .to_public_key_pem(LineEnding::LF)
+        .unwrap();
+    assert!(public_key_pem.starts_with("-----BEGIN PUBLIC KEY-----\n"));
+
+    let message = [
+        authenticator_data.as_slice(),
+        &client_data_hash(client_data_json),
+    ]
+    .concat();
+    let signature: Signature = signing_key.sign(&message);
+    let verify = |pem: &str| {
+        webauthn_verify_pem(
+            authenticator_data,
+            client_data_json,
+            signature.to_der().as_bytes(),
+            pem,
+        )
+    };
+
+    assert_eq!(verify(&public_key_pem), Ok(()));
+    let other_key = SigningKey::random(&mut OsRng)
+        .verifying_key()
+        .to_public_key_pem(LineEnding::CRLF)
+        .unwrap();
+    assert_eq!(verify(&other_key), Err(VerifyError::VerifySignature));
+    assert_eq!(
+        verify(&public_key_pem.replace("PUBLIC KEY", "PRIVATE KEY")),
+        Err(VerifyError::ExtractPublicKey)
+    );
+    assert_eq!(verify("not a PEM"), Err(VerifyError::ExtractPublicKey));
+}
+
+#[cfg(feature = "jwk")]
+#[test]
+fn test_webauthn_verify_jwk() {
+    use base64::prelude::BASE64_URL_SAFE_NO_PAD;
+    use p256::{elliptic_curve::sec1::ToEncodedPoint, pkcs8::DecodePublicKey, PublicKey};
+
+    let assertion = crate::test_vectors::ES256_ASSERTION;
+    let point = PublicKey::from_public_key_der(assertion.public_key)
+        .unwrap()
+        .to_encoded_point(false);
+    let coordinate = |c: &[u8]| base64::encode_engine(c, &BASE64_URL_SAFE_NO_PAD);
+    let (x, y) = (
+        coordinate(point.x().unwrap()),
+        coordinate(point.y().unwrap()),
+    );
+    // As `crypto.subtle.exportKey("jwk", publicKey)` exports a key made by
+    // `crypto.subtle.generateKey({ name: "ECDSA", namedCurve: "P-256" }, true, ["sign", "verify"])`.
+    let browser_jwk = format!(
+        r#"{{"crv":"P-256","ext":true,"key_ops":["verify"],"kty":"EC","x":"{x}","y":"{y}"}}"#
+    );
+
+    let verify = |jwk: &str| {
+        webauthn_verify_jwk(
+            assertion.authenticator_data,
+            assertion.client_data_json,
+            assertion.signature,
+            jwk,
+        )
+    };
+    assert_eq!(verify(&browser_jwk), Ok(()));
+
+    let other_key = SigningKey::random(&mut OsRng)
+        .verifying_key()
+        .to_encoded_point(false);
+    let other_jwk = format!(
+        r#"{{"kty":"EC","crv":"P-256","x":"{}","y":"{}"}}"#,
+        coordinate(other_key.x().unwrap()),
+        coordinate(other_key.y().unwrap())
+    );
+    assert_eq!(verify(&other_jwk), Err(VerifyError::VerifySignature));
+
+    for invalid in [
+        browser_jwk.replace("P-256", "P-384"),
+        browser_jwk.replace(r#""kty":"EC""#, r#""kty":"OKP""#),
+        browser_jwk.replace(&y, &x),
+        browser_jwk.replace(&x, &x[1..]),
+        format!(r#"{{"kty":"EC","crv":"P-256","x":"{x}"}}"#),
+        "not a JWK".into(),
+    ] {
+        assert_eq!(verify(&invalid), Err(VerifyError::ExtractPublicKey));
+    }
+}
+
+#[cfg(feature = "key-recovery")]
+#[test]
+fn test_recover_public_key_from_signature() {
+    let authenticator_data = b"example authenticator data";
+    let client_data_json = br#"{"type":"webauthn.get","challenge":"dGVzdA","origin":"o"}"#;
+
+    let signing_key = SigningKey::random(&mut OsRng);
+    let message = [
+        authenticator_data.as_slice(),
+        &client_data_hash(client_data_json),
+    ]
+    .concat();
+    let signature: Signature = signing_key.sign(&message);
+
+    let candidates = recover_public_key_from_signature(
+        authenticator_data,
+        client_data_json,
+        signature.to_der().as_bytes(),
+    )
+    .unwrap();
+    assert!(candidates.contains(signing_key.verifying_key()));
+    assert_ne!(candidates[0], candidates[1]);
+
+    assert_eq!(
+        recover_public_key_from_signature(authenticator_data, client_data_json, &[0x42; 64]),
+        Err(VerifyError::ParseSignature)
+    );
+}
+
+#[test]
+fn test_webauthn_verify_cached() {
+    #[derive(Default)]
+    struct CountingCache {
+        cache: InMemoryCache,
+        inserts: usize,
+    }
+
+    impl VerificationCache for CountingCache {
+        fn lookup(&self, key: [u8; 32]) -> Option<bool> {
+            self.cache.lookup(key)
+        }
+
+        fn insert(&mut self, key: [u8; 32], result: bool) {
+            self.inserts += 1;
+            self.cache.insert(key, result)
+        }
+    }
+
+    let authenticator_data = b"example authenticator data";
+    let client_data_json = br#"{"type":"webauthn.get","challenge":"dGVzdA","origin":"o"}"#;
+
+    let signing_key = SigningKey::random(&mut OsRng);
+    let public_key = signing_key.verifying_key().to_encoded_point(false);
+    let public_key_der = sec1_public_key_to_der(public_key.as_bytes()).unwrap();
+    let message = [
+        authenticator_data.as_slice(),
+        &client_data_hash(client_data_json),
+    ]
+    .concat();
+    let signature: Signature = signing_key.sign(&message);
+    let signature = signature.to_der();
+
+    let mut cache = CountingCache::default();
+    for _ in 0..3 {
+        assert_eq!(
+            webauthn_verify_cached(
+                &mut cache,
+                authenticator_data,
+                client_data_json,
+                signature.as_bytes(),
+                &public_key_der
+            ),
+            Ok(())
+        );
+    }
+    assert_eq!(cache.inserts, 1);
+
+    // The signature is cached as valid, but not for another message.
+    assert_eq!(
+        webauthn_verify_cached(
+            &mut cache,
+            b"other authenticator data",
+            client_data_json,
+            signature.as_bytes(),
+            &public_key_der
+        ),
+        Err(VerifyError::VerifySignature)
+    );
+    assert_eq!(cache.inserts, 2);
+
+    assert_eq!(
+        webauthn_verify_cached(
+            &mut NoopCache,
+            authenticator_data,
+            client_data_json,
+            signature.as_bytes(),
+            &public_key_der
+        ),
+        Ok(())
+    );
+}
+
+mod key_round_trip {
+    use super::*;
+    use coset::{iana::Ec2KeyParameter, CoseKey, Label};
+    use proptest::prelude::*;
+
+    fn coordinate(key: &CoseKey, param: Ec2KeyParameter) -> Vec<u8> {
+        key.params
+            .iter()
+            .find_map(|(label, value)| {
+                (*label == Label::Int(param as i64)).then(|| value.as_bytes())
+            })
+            .flatten()
+            .expect("The key was built with both coordinates; qed")
+            .clone()
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(1000))]
+
+        #[test]
+        fn cose_to_der_preserves_coordinates(secret in any::<[u8; 32]>()) {
+            let signing_key = SigningKey::from_slice(&secret);
+            prop_assume!(signing_key.is_ok());
+            let point = signing_key.unwrap().verifying_key().to_encoded_point(false);
+
+            let cose_key = CoseKeyBuilder::new_ec2_pub_key(
+                EllipticCurve::P_256,
+                point.x().unwrap().to_vec(),
+                point.y().unwrap().to_vec(),
+            )
+            .algorithm(Algorithm::ES256)
+            .build();
+            let der = cose_key_to_der(&cose_key).unwrap();
+
+            let decoded = VerifyingKey::from_public_key_der(&der)
+                .unwrap()
+                .to_encoded_point(false);
+            prop_assert_eq!(
+                decoded.x().unwrap().to_vec(),
+                coordinate(&cose_key, Ec2KeyParameter::X)
+            );
+            prop_assert_eq!(
+                decoded.y().unwrap().to_vec(),
+                coordinate(&cose_key, Ec2KeyParameter::Y)
+            );
+
+            let compressed = compress_public_key_der(&der).unwrap();
+            let decompressed = VerifyingKey::from_sec1_bytes(&compressed)
+                .unwrap()
+                .to_encoded_point(false);
+            prop_assert_eq!(decompressed, point);
+            prop_assert_eq!(sec1_public_key_to_der(&compressed).unwrap(), der);
+        }
+    }
+}
+
+mod authenticator_data_round_trip {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(1000))]
+
+        /// Whatever the flags, the attested credential data and the extensions are present as
+        /// the `AT` and `ED` flags tell.
+        #[test]
+        fn parse_inverts_encode(
+            rp_id_hash in any::<[u8; 32]>(),
+            flags in any::<u8>(),
+            sign_count in any::<u32>(),
+            aaguid in any::<[u8; 16]>(),
+            credential_id in proptest::collection::vec(any::<u8>(), 0..128),
+            coordinates in any::<([u8; 32], [u8; 32])>(),
+            extensions in proptest::collection::vec(any::<u8>(), 0..64),
+        ) {
+            let flags = AuthenticatorDataFlags::from(flags);
+            let authenticator_data = AuthenticatorData {
+                rp_id_hash,
+                flags,
+                sign_count,
+                attested_credential_data: flags
+                    .contains(AuthenticatorDataFlags::ATTESTED_CREDENTIAL_DATA)
+                    .then(|| AttestedCredentialData {
+                        aaguid,
+                        credential_id: &credential_id,
+                        credential_public_key: CoseKeyBuilder::new_ec2_pub_key(
+                            EllipticCurve::P_256,
+                            coordinates.0.to_vec(),
+                            coordinates.1.to_vec(),
+                        )
+                        .algorithm(Algorithm::ES256)
+                        .build(),
+                    }),
+                extensions: flags
+                    .contains(AuthenticatorDataFlags::EXTENSION_DATA)
+                    .then_some(&extensions[..]),
+            };
+
+            let encoded = authenticator_data.encode();
+            let parsed = parse_authenticator_data(&encoded);
+            prop_assert_eq!(parsed.as_ref(), Ok(&authenticator_data));
+            prop_assert_eq!(parsed.unwrap().encode(), encoded);
+        }
+
+        #[test]
+        fn truncated_encodings_fail_to_parse(
+            flags in any::<u8>(),
+            credential_id in proptest::collection::vec(any::<u8>(), 0..128),
+            cut in any::<usize>(),
+        ) {
+            // Without extensions, which take whatever follows.
+            let flags = AuthenticatorDataFlags::from(
+                flags & !AuthenticatorDataFlags::EXTENSION_DATA.bits(),
+            );
+            let authenticator_data = AuthenticatorData {
+                rp_id_hash: [0x11; 32],
+                flags,
+                sign_count: 7,
+                attested_credential_data: flags
+                    .contains(AuthenticatorDataFlags::ATTESTED_CREDENTIAL_DATA)
+                    .then(|| AttestedCredentialData {
+                        aaguid: [0x22; 16],
+                        credential_id: &credential_id,
+                        credential_public_key: CoseKeyBuilder::new_ec2_pub_key(
+                            EllipticCurve::P_256,
+                            vec![0x33; 32],
+                            vec![0x44; 32],
+                        )
+                        .build(),
+                    }),
+                extensions: None,
+            };
+
+            let encoded = authenticator_data.encode();
+            prop_assert_eq!(
+                parse_authenticator_data(&encoded[..cut % encoded.len()]),
+                Err(VerifyError::ParseAuthenticatorData)
+            );
+        }
+    }
+}
+
+mod cose_fast_path {
+    use super::*;
+    use p256::elliptic_curve::sec1::ToEncodedPoint;
+    use proptest::prelude::*;
+
+    const AUTHENTICATOR_DATA: &[u8] = b"example authenticator data";
+    const CLIENT_DATA_JSON: &[u8] = br#"{"type":"webauthn.get","challenge":"dGVzdA","origin":"o"}"#;
+
+    fn es256_key(x: &[u8], y: &[u8]) -> coset::CoseKey {
+        CoseKeyBuilder::new_ec2_pub_key(EllipticCurve::P_256, x.to_vec(), y.to_vec())
+            .algorithm(Algorithm::ES256)
+            .build()
+    }
+
+    fn signed(signing_key: &SigningKey) -> (coset::CoseKey, Vec<u8>) {
+        let point = signing_key.verifying_key().to_encoded_point(false);
+        let message = [AUTHENTICATOR_DATA, &client_data_hash(CLIENT_DATA_JSON)].concat();
+        let signature: Signature = signing_key.sign(&message);
+        (
+            es256_key(point.x().unwrap(), point.y().unwrap()),
+            signature.to_der().as_bytes().to_vec(),
+        )
+    }
+
+    fn der_path(key: &coset::CoseKey, signature: &[u8]) -> Result<(), VerifyError> {
+        webauthn_verify(
+            AUTHENTICATOR_DATA,
+            CLIENT_DATA_JSON,
+            signature,
+            &cose_key_to_der(key)?,
+        )
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(500))]
+
+        #[test]
+        fn agrees_with_the_der_path_on_valid_keys(
+            secret in any::<[u8; 32]>(),
+            flip in any::<Option<(usize, u8)>>(),
+        ) {
+            let signing_key = SigningKey::from_slice(&secret);
+            prop_assume!(signing_key.is_ok());
+            let (key, mut signature) = signed(&signing_key.unwrap());
+            if let Some((index, bit)) = flip {
+                let len = signature.len();
+                signature[index % len] ^= 1 << (bit % 8);
+            }
+
+            prop_assert_eq!(
+                webauthn_verify_cose(AUTHENTICATOR_DATA, CLIENT_DATA_JSON, &signature, &key),
+                der_path(&key, &signature)
+            );
+        }
+
+        #[test]
+        fn agrees_with_the_der_path_on_arbitrary_coordinates(
+            x in proptest::collection::vec(any::<u8>(), 31..34),
+            y in proptest::collection::vec(any::<u8>(), 31..34),
+        ) {
+            let key = es256_key(&x, &y);
+            let (_, signature) = signed(&SigningKey::random(&mut OsRng));
+
+            prop_assert_eq!(
+                cose_key_to_public_key(&key).map(|key| key.to_encoded_point(false)),
+                cose_key_to_der(&key).map(|der| {
+                    PublicKey::<NistP256>::from_public_key_der(&der).unwrap().to_encoded_point(false)
+                })
+            );
+            prop_assert_eq!(
+                webauthn_verify_cose(AUTHENTICATOR_DATA, CLIENT_DATA_JSON, &signature, &key),
+                der_path(&key, &signature)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_what_the_der_path_rejects() {
+        let (key, signature) = signed(&SigningKey::random(&mut OsRng));
+        let mut without_algorithm = key.clone();
+        without_algorithm.alg = None;
+        let mut okp = key.clone();
+        okp.kty = coset::KeyType::Assigned(coset::iana::KeyType::OKP);
+
+        for (key, error) in [
+            (without_algorithm, VerifyError::MissingAlgorithm),
+            (okp, VerifyError::ExtractPublicKey),
+            (es256_key(&[1; 32], &[2; 32]), VerifyError::ExtractPublicKey),
+        ] {
+            assert_eq!(
+                webauthn_verify_cose(AUTHENTICATOR_DATA, CLIENT_DATA_JSON, &signature, &key),
+                Err(error)
+            );
+            assert_eq!(der_path(&key, &signature), Err(error));
+        }
+    }
+
+    #[test]
+    fn rejects_es256_keys_on_other_curves() {
+        let (key, signature) = signed(&SigningKey::random(&mut OsRng));
+        let crv = coset::Label::Int(coset::iana::Ec2KeyParameter::Crv as i64);
+        let mut p384 = key.clone();
+        p384.params
+            .iter_mut()
+            .filter(|(label, _)| *label == crv)
+            .for_each(|(_, value)| *value = (EllipticCurve::P_384 as i64).into());
+        let mut without_curve = key;
+        without_curve.params.retain(|(label, _)| *label != crv);
+
+        for key in [p384, without_curve] {
+            assert_eq!(
+                webauthn_verify_cose(AUTHENTICATOR_DATA, CLIENT_DATA_JSON, &signature, &key),
+                Err(VerifyError::ExtractPublicKey)
+            );
+            assert_eq!(cose_key_to_der(&key), Err(VerifyError::ExtractPublicKey));
+        }
+    }
+}
+
+#[test]
+fn test_verify_limits() {
+    let limits = VerifyLimits::DEFAULT
+        .with_max_authenticator_data_len(64)
+        .with_max_client_data_len(128);
+    let client_data_json = [b' '; 128];
+
+    let signing_key = SigningKey::random(&mut OsRng);
+    let public_key = signing_key.verifying_key().to_encoded_point(false);
+    let public_key_der = sec1_public_key_to_der(public_key.as_bytes()).unwrap();
+    let sign = |authenticator_data: &[u8], client_data_json: &[u8]| {
+        let signature: Signature =
+            signing_key.sign(&[authenticator_data, &client_data_hash(client_data_json)].concat());
+        signature.to_der()
+    };
+
+    let at_limit = sign(&[0; 64], &client_data_json);
+    assert_eq!(
+        webauthn_verify_with_limits(
+            &limits,
+            &[0; 64],
+            &client_data_json,
+            at_limit.as_bytes(),
+            &public_key_der
+        ),
+        Ok(())
+    );
+
+    let authenticator_data_above_limit = sign(&[0; 65], &client_data_json);
+    assert_eq!(
+        webauthn_verify_with_limits(
+            &limits,
+            &[0; 65],
+            &client_data_json,
+            authenticator_data_above_limit.as_bytes(),
+            &public_key_der
+        ),
+        Err(VerifyError::InputTooLarge)
+    );
+
+    let client_data_above_limit = sign(&[0; 64], &[b' '; 129]);
+    assert_eq!(
+        webauthn_verify_with_limits(
+            &limits,
+            &[0; 64],
+            &[b' '; 129],
+            client_data_above_limit.as_bytes(),
+            &public_key_der
+        ),
+        Err(VerifyError::InputTooLarge)
+    );
+
+    // The default limits are far above what the above uses.
+    assert_eq!(
+        webauthn_verify(
+            &[0; 65],
+            &[b' '; 129],
+            client_data_above_limit.as_bytes(),
+            &public_key_der
+        ),
+        Err(VerifyError::VerifySignature)
+    );
+    assert_eq!(
+        webauthn_verify(
+            &[0; 64],
+            &vec![b' '; VerifyLimits::DEFAULT.max_client_data_len + 1],
+            at_limit.as_bytes(),
+            &public_key_der
+        ),
+        Err(VerifyError::InputTooLarge)
+    );
+}
+
+#[test]
+fn test_error_conversions() {
+    use p256::pkcs8::{spki, DecodePublicKey};
+
+    let cose_error = coset::CoseKey::from_slice(&[0xff]).unwrap_err();
+    assert_eq!(VerifyError::from(cose_error), VerifyError::ExtractPublicKey);
+
+    let spki_error = VerifyingKey::from_public_key_der(&[0x30, 0x00]).unwrap_err();
+    assert_eq!(VerifyError::from(spki_error), VerifyError::ExtractPublicKey);
+    assert_eq!(
+        VerifyError::from(p256::pkcs8::Error::from(spki::Error::KeyMalformed)),
+        VerifyError::ExtractPublicKey
+    );
+
+    let signing_key = SigningKey::random(&mut OsRng);
+    let signature: Signature = signing_key.sign(b"message");
+    let ecdsa_error = signing_key
+        .verifying_key()
+        .verify(b"other message", &signature)
+        .unwrap_err();
+    assert_eq!(VerifyError::from(ecdsa_error), VerifyError::VerifySignature);
+}
+
+#[test]
+fn test_error_codes_are_stable() {
+    // Part of the ABI runtimes and contracts rely on: never change a code here.
+    const CODES: [(VerifyError, u8); 25] = [
+        (VerifyError::AlgorithmMismatch, 0),
+        (VerifyError::BackupStateInconsistency, 1),
+        (VerifyError::ClientDataMalformed, 2),
+        (VerifyError::ClientDataModified, 3),
+        (VerifyError::CredentialAlreadyRegistered, 4),
+        (VerifyError::ExtractPublicKey, 5),
+        (VerifyError::InputTooLarge, 6),
+        (VerifyError::MissingAlgorithm, 7),
+        (VerifyError::ParseAttestationObject, 8),
+        (VerifyError::ParseAuthenticatorData, 9),
+        (VerifyError::ParseClientData, 10),
+        (VerifyError::ParseSignature, 11),
+        (VerifyError::UnsupportedAlgorithm, 12),
+        (VerifyError::UnsupportedAttestationFormat, 13),
+        (VerifyError::VerifySignature, 14),
+        (VerifyError::ParseCtap2Response, 15),
+        (VerifyError::ParseAttestationCertificate, 16),
+        (VerifyError::InvalidAttestationCertificate, 17),
+        (VerifyError::AaguidMismatch, 18),
+        (VerifyError::SelfAttestationNotAllowed, 19),
+        (VerifyError::AttestationCertificateRevoked, 20),
+        (VerifyError::ChallengeMismatch, 21),
+        (VerifyError::AuthenticatorBlocked, 22),
+        (VerifyError::AlgorithmNotOffered, 23),
+        (VerifyError::PublicKeyMismatch, 24),
+    ];
+
+    for (e, code) in CODES {
+        assert_eq!(e.code(), code);
+        assert_eq!(u32::from(e), u32::from(code));
+        assert_eq!(VerifyError::from_code(code), Some(e));
+        #[cfg(feature = "scale")]
+        {
+            use codec::{Decode, Encode};
+            assert_eq!(e.encode(), [code]);
+            assert_eq!(VerifyError::decode(&mut &[code][..]), Ok(e));
+        }
+    }
+    assert_eq!(VerifyError::from_code(CODES.len() as u8), None);
+}
+
+#[test]
+fn test_canonical_client_data() {
+    assert_eq!(
+        canonical_client_data(
+            br#"{"type":"webauthn.get","challenge":"dGVzdA","origin":"https://example.com","crossOrigin":false}"#
+        ),
+        Ok(())
+    );
+    // Reordered members, as a re-serialization would leave them.
+    assert_eq!(
+        canonical_client_data(
+            br#"{"challenge":"dGVzdA","type":"webauthn.get","origin":"https://example.com"}"#
+        ),
+        Err(VerifyError::ClientDataMalformed)
+    );
+    assert_eq!(
+        canonical_client_data(br#"{"type":"webauthn.get","origin":"https://example.com"}"#),
+        Err(VerifyError::ClientDataMalformed)
+    );
+    assert_eq!(
+        canonical_client_data(
+            br#"{"type":"webauthn.get","challenge":42,"origin":"https://example.com"}"#
+        ),
+        Err(VerifyError::ClientDataMalformed)
+    );
+    assert_eq!(
+        canonical_client_data(b"\xff"),
+        Err(VerifyError::ClientDataMalformed)
+    );
+}
+
+mod test_vectors {
+    use super::*;
+    use crate::test_vectors::*;
+
+    #[test]
+    fn assertions_verify() {
+        for vector in [ES256_ASSERTION, ES256_ASSERTION_USER_PRESENT] {
+            assert_eq!(
+                webauthn_verify_with_challenge(
+                    vector.authenticator_data,
+                    vector.client_data_json,
+                    vector.signature,
+                    vector.public_key,
+                    &CHALLENGE,
+                ),
+                Ok(())
+            );
+            let authenticator_data = parse_authenticator_data(vector.authenticator_data).unwrap();
+            assert_eq!(
+                authenticator_data.rp_id_hash,
+                sha256_fixed(RP_ID.as_bytes())
+            );
+            assert_eq!(authenticator_data.sign_count, 1);
+            assert_eq!(
+                validate_webauthn_origin(vector.client_data_json, ORIGIN),
+                Ok(())
+            );
+        }
+
+        let flags = |vector: Assertion| {
+            parse_authenticator_data(vector.authenticator_data)
+                .unwrap()
+                .flags
+        };
+        assert!(flags(ES256_ASSERTION).contains(AuthenticatorDataFlags::USER_VERIFIED));
+        assert!(
+            !flags(ES256_ASSERTION_USER_PRESENT).contains(AuthenticatorDataFlags::USER_VERIFIED)
+        );
+    }
+
+    #[test]
+    fn the_registration_attests_the_assertion_key() {
+        let attestation_object =
+            parse_attestation_object(ES256_REGISTRATION.attestation_object).unwrap();
+        assert_eq!(
+            attestation_object.auth_data,
+            ES256_REGISTRATION.authenticator_data
+        );
+        let AttestationStatement::Packed { alg, sig } = attestation_object.statement else {
+            panic!("The registration is self-attested");
+        };
+        assert_eq!(alg, CoseAlgorithm::ES256);
+        assert_eq!(
+            webauthn_verify_with_challenge(
+                ES256_REGISTRATION.authenticator_data,
+                ES256_REGISTRATION.client_data_json,
+                &sig,
+                ES256_REGISTRATION.public_key,
+                &CHALLENGE,
+            ),
+            Ok(())
+        );
+
+        let attested = parse_authenticator_data(ES256_REGISTRATION.authenticator_data)
+            .unwrap()
+            .attested_credential_data
+            .unwrap();
+        assert_eq!(attested.credential_id, ES256_REGISTRATION.credential_id);
+        assert_eq!(
+            cose_key_to_der(&attested.credential_public_key).unwrap(),
+            ES256_REGISTRATION.public_key
+        );
+        assert_eq!(ES256_ASSERTION.public_key, ES256_REGISTRATION.public_key);
+    }
+}
+
+mod origin {
+    use super::*;
+
+    fn client_data(origin: &str) -> Vec<u8> {
+        format!(r#"{{"type":"webauthn.get","challenge":"dGVzdA","origin":"{origin}"}}"#).into()
+    }
+
+    fn mismatch(origin: &str) -> OriginError {
+        OriginError::Mismatch {
+            actual: origin.into(),
+        }
+    }
+
+    #[test]
+    fn https_origins_match_ignoring_case() {
+        let validate = |origin, expected| validate_webauthn_origin(&client_data(origin), expected);
+
+        assert_eq!(
+            validate("https://example.com", "https://example.com"),
+            Ok(())
+        );
+        assert_eq!(
+            validate("https://Example.COM", "https://example.com"),
+            Ok(())
+        );
+        assert_eq!(
+            validate("https://example.com:8443", "https://example.com:8443"),
+            Ok(())
+        );
+        assert_eq!(validate("https://192.0.2.1", "https://192.0.2.1"), Ok(()));
+
+        for (origin, expected) in [
+            ("https://example.org", "https://example.com"),
+            ("https://login.example.com", "https://example.com"),
+            // The port is part of the origin.
+            ("https://example.com:8443", "https://example.com"),
+            ("https://example.com", "https://example.com:443"),
+            ("https://192.0.2.1", "https://192.0.2.2"),
+        ] {
+            assert_eq!(validate(origin, expected), Err(mismatch(origin)));
+        }
+    }
+
+    #[test]
+    fn http_origins_always_fail() {
+        for origin in [
+            "http://example.com",
+            "http://localhost:8080",
+            "http://192.0.2.1",
+        ] {
+            assert_eq!(
+                validate_webauthn_origin(&client_data(origin), origin),
+                Err(OriginError::Insecure {
+                    actual: origin.into()
+                })
+            );
+            assert_eq!(
+                validate_webauthn_rp_id(&client_data(origin), "example.com"),
+                Err(OriginRpIdError::Insecure {
+                    actual: origin.into()
+                })
+            );
+        }
+        assert_eq!(
+            validate_webauthn_origin(b"{}", "https://example.com"),
+            Err(OriginError::ClientData(VerifyError::ParseClientData))
+        );
+    }
+
+    #[test]
+    fn rp_ids_are_the_origin_host_or_a_parent_domain() {
+        let validate = |origin, rp_id| validate_webauthn_rp_id(&client_data(origin), rp_id);
+
+        for (origin, rp_id) in [
+            ("https://example.com", "example.com"),
+            ("https://login.example.com", "example.com"),
+            ("https://a.login.example.com", "login.example.com"),
+            ("https://LOGIN.example.com", "login.EXAMPLE.com"),
+            // Whatever the port.
+            ("https://example.com:8443", "example.com"),
+            ("https://login.example.com:8443", "example.com"),
+        ] {
+            assert_eq!(validate(origin, rp_id), Ok(()), "{origin} for {rp_id}");
+        }
+
+        for (origin, rp_id) in [
+            ("https://example.com", "login.example.com"),
+            ("https://badexample.com", "example.com"),
+            ("https://example.com.evil.net", "example.com"),
+            ("https://example.com", ""),
+            ("https://example.com:8443", "example.com:8443"),
+        ] {
+            assert_eq!(
+                validate(origin, rp_id),
+                Err(OriginRpIdError::Mismatch {
+                    actual: origin.into()
+                }),
+                "{origin} for {rp_id}"
+            );
+        }
+    }
+
+    #[test]
+    fn ip_origins_have_no_rp_id() {
+        for (origin, rp_id) in [
+            ("https://192.0.2.1", "192.0.2.1"),
+            ("https://192.0.2.1:8443", "0.2.1"),
+            ("https://[2001:db8::1]", "[2001:db8::1]"),
+        ] {
+            assert_eq!(
+                validate_webauthn_rp_id(&client_data(origin), rp_id),
+                Err(OriginRpIdError::IpAddress {
+                    actual: origin.into()
+                })
+            );
+        }
+    }
+}
+
+mod client_data_lite {
+    use super::*;
+    use serde::Deserialize;
+
+    /// What `ClientDataLite` must agree with.
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct Expected {
+        r#type: String,
+        challenge: String,
+        origin: String,
+        #[serde(default)]
+        cross_origin: bool,
+    }
+
+    /// As sent by browsers and platforms, plus a few edge cases of the JSON grammar.
+    const CORPUS: &[&[u8]] = &[
+        // Chrome.
+        br#"{"type":"webauthn.create","challenge":"Zm9vYmFyYmF6cXV4","origin":"https://webauthn.io","crossOrigin":false}"#,
+        br#"{"type":"webauthn.get","challenge":"Zm9vYmFyYmF6cXV4","origin":"https://webauthn.io","crossOrigin":false,"other_keys_can_be_added_here":"do not compare clientDataJSON against a template. See https://goo.gl/yabPex"}"#,
+        // Chrome in a cross-origin iframe.
+        br#"{"type":"webauthn.get","challenge":"Zm9vYmFy","origin":"https://rp.example","crossOrigin":true,"topOrigin":"https://top.example"}"#,
+        // Safari, without `crossOrigin`.
+        br#"{"type":"webauthn.get","challenge":"Zm9vYmFyYmF6cXV4","origin":"https://webauthn.io"}"#,
+        // Firefox.
+        br#"{"type":"webauthn.create","challenge":"Zm9vYmFyYmF6cXV4","origin":"https://webauthn.io","crossOrigin":false,"tokenBinding":{"status":"not-supported"}}"#,
+        // Android apps.
+        br#"{"type":"webauthn.get","challenge":"Zm9vYmFy","origin":"android:apk-key-hash:Dd7RrNy3Zk3aa1T8fLd1-fbvwTUXIgJOhGkxJmGwcfE","androidPackageName":"com.example.app"}"#,
+        // Re-serialized, with escapes and nested unknown members.
+        br#" {
+            "origin": "https:\/\/r\u00e9sum\u00e9.example",
+            "challenge": "Zm9v",
+            "extra": [1, -2.5e3, 0, null, true, {"a": ["\ud83d\ude00"]}],
+            "type": "webauthn.get"
+        } "#,
+    ];
+
+    fn parse(json: &[u8]) -> Result<ClientDataLite<'_>, VerifyError> {
+        // Leaked so the parsed client data can borrow from it; these are tests.
+        let unescape_buffer = vec![0; json.len()].leak();
+        ClientDataLite::parse(json, unescape_buffer, &VerifyLimits::DEFAULT)
+    }
+
+    #[test]
+    fn parses_like_serde_json() {
+        for json in CORPUS {
+            let expected: Expected = serde_json::from_slice(json).unwrap();
+            let parsed = parse(json).unwrap();
+            assert_eq!(
+                Expected {
+                    r#type: parsed.r#type.into(),
+                    challenge: parsed.challenge.into(),
+                    origin: parsed.origin.into(),
+                    cross_origin: parsed.cross_origin,
+                },
+                expected
+            );
+        }
+        assert_eq!(parse(CORPUS[6]).unwrap().origin, "https://résumé.example");
+    }
+
+    #[test]
+    fn rejects_what_serde_json_rejects() {
+        let invalid: &[&[u8]] = &[
+            br#"{"type":"webauthn.get","origin":"o"}"#,
+            br#"{"type":"webauthn.get","challenge":42,"origin":"o"}"#,
+            br#"{"type":"webauthn.get","challenge":"Zm9v","origin":"o","crossOrigin":"false"}"#,
+            br#"{"type":"webauthn.get","challenge":"Zm9v","challenge":"YmFy","origin":"o"}"#,
+            br#"{"type":"webauthn.get","challenge":"Zm9v","origin":"o",}"#,
+            br#"{"type":"webauthn.get","challenge":"Zm9v","origin":"o","n":01}"#,
+            br#"{"type":"webauthn.get","challenge":"Zm9v","origin":"\x"}"#,
+            br#"{"type":"webauthn.get","challenge":"Zm9v","origin":"\udc00"}"#,
+            br#"{"type":"webauthn.get","challenge":"Zm9v","origin":"o"} {}"#,
+            b"{\"type\":\"webauthn.get\",\"challenge\":\"Zm9v\",\"origin\":\"o\n\"}",
+            b"\xff",
+        ];
+        for json in invalid {
+            assert!(serde_json::from_slice::<Expected>(json).is_err());
+            assert_eq!(parse(json), Err(VerifyError::ParseClientData));
+        }
+    }
+
+    #[test]
+    fn bounds_input_and_nesting() {
+        let json = CORPUS[0];
+        assert_eq!(
+            ClientDataLite::parse(
+                json,
+                &mut [],
+                &VerifyLimits::DEFAULT.with_max_client_data_len(json.len() - 1)
+            ),
+            Err(VerifyError::InputTooLarge)
+        );
+
+        let nested = format!(
+            r#"{{"type":"webauthn.get","challenge":"Zm9v","origin":"o","n":{}{}}}"#,
+            "[".repeat(64),
+            "]".repeat(64)
+        );
+        assert_eq!(parse(nested.as_bytes()), Err(VerifyError::ParseClientData));
+    }
+}
+
+/// A CTAP HID `authenticatorGetAssertion` response from a security key holding the P-256 key
+/// `[0x11; 32]`, for credential `[0xc7; 16]` and user `b"user"`, over the client data hash
+/// `[0x42; 32]`. The user is only present, and the signature counter is 7.
+const CTAP2_GET_ASSERTION_RESPONSE: [u8; 164] = [
+    0x00, 0xa4, 0x01, 0xa2, 0x62, 0x69, 0x64, 0x50, 0xc7, 0xc7, 0xc7, 0xc7, 0xc7, 0xc7, 0xc7, 0xc7,
+    0xc7, 0xc7, 0xc7, 0xc7, 0xc7, 0xc7, 0xc7, 0xc7, 0x64, 0x74, 0x79, 0x70, 0x65, 0x6a, 0x70, 0x75,
+    0x62, 0x6c, 0x69, 0x63, 0x2d, 0x6b, 0x65, 0x79, 0x02, 0x58, 0x25, 0x49, 0x96, 0x0d, 0xe5, 0x88,
+    0x0e, 0x8c, 0x68, 0x74, 0x34, 0x17, 0x0f, 0x64, 0x76, 0x60, 0x5b, 0x8f, 0xe4, 0xae, 0xb9, 0xa2,
+    0x86, 0x32, 0xc7, 0x99, 0x5c, 0xf3, 0xba, 0x83, 0x1d, 0x97, 0x63, 0x01, 0x00, 0x00, 0x00, 0x07,
+    0x03, 0x58, 0x47, 0x30, 0x45, 0x02, 0x20, 0x72, 0xa4, 0x1b, 0x02, 0xc4, 0xef, 0xd0, 0xbc, 0x48,
+    0xc2, 0xf3, 0x5c, 0xb0, 0xb3, 0xa4, 0x9f, 0x8a, 0x35, 0x4f, 0xd5, 0x01, 0xb1, 0x74, 0xcb, 0xd9,
+    0x26, 0x0e, 0x04, 0x41, 0x8c, 0x1c, 0xd0, 0x02, 0x21, 0x00, 0x99, 0x8d, 0xf9, 0x4d, 0xcd, 0xe3,
+    0xad, 0xbe, 0xed, 0x3c, 0xec, 0xc9, 0x2c, 0x65, 0xdc, 0x02, 0x11, 0x37, 0x4f, 0x18, 0xae, 0xa7,
+    0x4e, 0x5c, 0x91, 0xb3, 0xe6, 0x8f, 0x66, 0x91, 0x1b, 0x79, 0x04, 0xa1, 0x62, 0x69, 0x64, 0x44,
+    0x75, 0x73, 0x65, 0x72,
+];
+
+#[test]
+fn test_verify_ctap2_assertion() {
+    let signing_key = SigningKey::from_slice(&[0x11; 32]).unwrap();
+    let public_key_der = sec1_public_key_to_der(
+        signing_key
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes(),
+    )
+    .unwrap();
+
+    let assertion =
+        verify_ctap2_assertion(&CTAP2_GET_ASSERTION_RESPONSE, &[0x42; 32], &public_key_der)
+            .unwrap();
+    assert_eq!(assertion.credential_id, Some(vec![0xc7; 16]));
+    assert_eq!(assertion.user_id, Some(b"user".to_vec()));
+    let auth_data = parse_authenticator_data(&assertion.auth_data).unwrap();
+    assert_eq!(auth_data.sign_count, 7);
+    assert_eq!(auth_data.flags, AuthenticatorDataFlags::USER_PRESENT);
+
+    // Without the CTAP HID status byte, as CTAP2 libraries usually hand it over.
+    assert_eq!(
+        parse_ctap2_assertion(&CTAP2_GET_ASSERTION_RESPONSE[1..]),
+        Ok(assertion)
+    );
+    assert_eq!(
+        verify_ctap2_assertion(&CTAP2_GET_ASSERTION_RESPONSE, &[0x24; 32], &public_key_der),
+        Err(VerifyError::VerifySignature)
+    );
+    // `CTAP2_ERR_NO_CREDENTIALS`.
+    assert_eq!(
+        parse_ctap2_assertion(&[0x2e]),
+        Err(VerifyError::ParseCtap2Response)
+    );
+    assert_eq!(
+        parse_ctap2_assertion(&CTAP2_GET_ASSERTION_RESPONSE[..100]),
+        Err(VerifyError::ParseCtap2Response)
+    );
+}
+
+mod keyset {
+    use super::*;
+    use std::time::{Duration, Instant};
+    use subtle::Choice;
+
+    const AUTHENTICATOR_DATA: &[u8] = b"example authenticator data";
+    const CLIENT_DATA_JSON: &[u8] = br#"{"type":"webauthn.get","challenge":"dGVzdA","origin":"o"}"#;
+
+    fn random_key() -> (SigningKey, Vec<u8>) {
+        let signing_key = SigningKey::random(&mut OsRng);
+        let public_key = signing_key.verifying_key().to_encoded_point(false);
+        let public_key_der = sec1_public_key_to_der(public_key.as_bytes()).unwrap();
+        (signing_key, public_key_der)
+    }
+
+    fn sign(signing_key: &SigningKey) -> Vec<u8> {
+        let message = [AUTHENTICATOR_DATA, &client_data_hash(CLIENT_DATA_JSON)].concat();
+        let signature: Signature = signing_key.sign(&message);
+        signature.to_der().as_bytes().to_vec()
+    }
+
+    #[test]
+    fn ct_select_picks_by_condition() {
+        assert_eq!(ct_select(Choice::from(1), 7u64, 9), 7);
+        assert_eq!(ct_select(Choice::from(0), 7u64, 9), 9);
+    }
+
+    #[test]
+    fn returns_the_index_of_the_matching_key() {
+        let keys: Vec<_> = (0..4).map(|_| random_key()).collect();
+        let keys_der: Vec<&[u8]> = keys.iter().map(|(_, der)| der.as_slice()).collect();
+
+        for (i, (signing_key, _)) in keys.iter().enumerate() {
+            assert_eq!(
+                webauthn_verify_keyset(
+                    AUTHENTICATOR_DATA,
+                    CLIENT_DATA_JSON,
+                    &sign(signing_key),
+                    &keys_der
+                ),
+                Ok(i)
+            );
+        }
+    }
+
+    #[test]
+    fn fails_when_no_key_matches() {
+        let (signing_key, _) = random_key();
+        let (_, other_key) = random_key();
+        let signature = sign(&signing_key);
+
+        assert_eq!(
+            webauthn_verify_keyset(
+                AUTHENTICATOR_DATA,
+                CLIENT_DATA_JSON,
+                &signature,
+                &[&other_key, b"not a key"]
+            ),
+            Err(VerifyError::VerifySignature)
+        );
+        assert_eq!(
+            webauthn_verify_keyset(AUTHENTICATOR_DATA, CLIENT_DATA_JSON, &signature, &[]),
+            Err(VerifyError::VerifySignature)
+        );
+        assert_eq!(
+            webauthn_verify_keyset(AUTHENTICATOR_DATA, CLIENT_DATA_JSON, b"", &[&other_key]),
+            Err(VerifyError::ParseSignature)
+        );
+    }
+
+    /// Timing is too noisy for CI, run with `cargo test --release -- --ignored keyset`.
+    #[test]
+    #[ignore]
+    fn matching_position_does_not_affect_timing() {
+        const KEYS: usize = 8;
+        const ROUNDS: u32 = 200;
+
+        let keys: Vec<_> = (0..KEYS).map(|_| random_key()).collect();
+        let keys_der: Vec<&[u8]> = keys.iter().map(|(_, der)| der.as_slice()).collect();
+        let time = |signature: &[u8]| {
+            let start = Instant::now();
+            for _ in 0..ROUNDS {
+                webauthn_verify_keyset(AUTHENTICATOR_DATA, CLIENT_DATA_JSON, signature, &keys_der)
+                    .unwrap();
+            }
+            start.elapsed()
+        };
+
+        let first = time(&sign(&keys[0].0));
+        let last = time(&sign(&keys[KEYS - 1].0));
+        // An early return would make matching the first key about `KEYS` times faster.
+        let (fast, slow) = (first.min(last), first.max(last));
+        assert!(
+            slow.saturating_sub(fast) < fast / 5 + Duration::from_millis(1),
+            "first key: {first:?}, last key: {last:?}"
+        );
+    }
+}
+
+mod packed_x5c {
+    use super::*;
+
+    const AAGUID: [u8; 16] = [0x22; 16];
+    const CLIENT_DATA_JSON: &[u8] =
+        br#"{"type":"webauthn.create","challenge":"dGVzdA","origin":"o"}"#;
+
+    /// DER-encodes an element.
+    fn der(tag: u8, contents: &[u8]) -> Vec<u8> {
+        let len = match contents.len() {
+            len @ 0..=0x7f => vec![len as u8],
+            len @ 0x80..=0xff => vec![0x81, len as u8],
+            len => [&[0x82][..], &(len as u16).to_be_bytes()].concat(),
+        };
+        [&[tag][..], &len, contents].concat()
+    }
+
+    fn extension(oid: &[u8], critical: bool, value: &[u8]) -> Vec<u8> {
+        let critical = if critical { der(0x01, &[0xff]) } else { vec![] };
+        der(0x30, &[der(0x06, oid), critical, der(0x04, value)].concat())
+    }
+
+    fn basic_constraints(is_ca: bool) -> Vec<u8> {
+        let ca = if is_ca { der(0x01, &[0xff]) } else { vec![] };
+        extension(&[0x55, 0x1d, 0x13], true, &der(0x30, &ca))
+    }
+
+    fn aaguid_extension(aaguid: [u8; 16], critical: bool) -> Vec<u8> {
+        let oid = [
+            0x2b, 0x06, 0x01, 0x04, 0x01, 0x82, 0xe5, 0x1c, 0x01, 0x01, 0x04,
+        ];
+        extension(&oid, critical, &der(0x04, &aaguid))
+    }
+
+    /// The validity of the certificates of the tests, 2025 to 2035, as `UTCTime`s.
+    fn validity() -> [Vec<u8>; 2] {
+        [der(0x17, b"250101000000Z"), der(0x17, b"350101000000Z")]
+    }
+
+    /// The `TBSCertificate` of an attestation certificate for `public_key_der`.
+    fn tbs_certificate(
+        version: u8,
+        public_key_der: &[u8],
+        extensions: &[Vec<u8>],
+        validity: [Vec<u8>; 2],
+    ) -> Vec<u8> {
+        let name = der(
+            0x30,
+            &der(
+                0x31,
+                &der(
+                    0x30,
+                    &[der(0x06, &[0x55, 0x04, 0x03]), der(0x0c, b"test")].concat(),
+                ),
+            ),
+        );
+        let extensions = if extensions.is_empty() {
+            vec![]
+        } else {
+            der(0xa3, &der(0x30, &extensions.concat()))
+        };
+        der(
+            0x30,
+            &[
+                der(0xa0, &der(0x02, &[version - 1])),
+                der(0x02, &[0x01]),
+                ecdsa_with_sha256(),
+                name.clone(),
+                der(0x30, &validity.concat()),
+                name,
+                public_key_der.to_vec(),
+                extensions,
+            ]
+            .concat(),
+        )
+    }
+
+    fn ecdsa_with_sha256() -> Vec<u8> {
+        der(
+            0x30,
+            &der(0x06, &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02]),
+        )
+    }
+
+    /// The certificate of `tbs_certificate`, signed by `issuer`, or with a made-up signature
+    /// without one.
+    fn signed(tbs_certificate: Vec<u8>, issuer: Option<&SigningKey>) -> Vec<u8> {
+        let signature = match issuer {
+            Some(issuer) => {
+                let signature: Signature = issuer.sign(&tbs_certificate);
+                signature.to_der().as_bytes().to_vec()
+            }
+            None => der(0x30, &[]),
+        };
+        der(
+            0x30,
+            &[
+                tbs_certificate,
+                ecdsa_with_sha256(),
+                der(0x03, &[&[0x00][..], &signature].concat()),
+            ]
+            .concat(),
+        )
+    }
+
+    /// An attestation certificate for `public_key_der`. Its signature is made up, as only the
+    /// signatures of certificates followed by their issuer's are checked.
+    fn certificate(version: u8, public_key_der: &[u8], extensions: &[Vec<u8>]) -> Vec<u8> {
+        signed(
+            tbs_certificate(version, public_key_der, extensions, validity()),
+            None,
+        )
+    }
+
+    /// A CA key, and its certificate.
+    fn issuer() -> (SigningKey, Vec<u8>) {
+        let key = SigningKey::random(&mut OsRng);
+        let public_key = key.verifying_key().to_encoded_point(false);
+        let public_key_der = sec1_public_key_to_der(public_key.as_bytes()).unwrap();
+        let certificate = certificate(3, &public_key_der, &[basic_constraints(true)]);
+        (key, certificate)
+    }
+
+    /// Authenticator data attesting a fresh credential of an authenticator with `AAGUID`.
+    fn authenticator_data() -> Vec<u8> {
+        let public_key = SigningKey::random(&mut OsRng)
+            .verifying_key()
+            .to_encoded_point(false);
+        let credential_public_key = CoseKeyBuilder::new_ec2_pub_key(
+            EllipticCurve::P_256,
+            public_key.x().unwrap().to_vec(),
+            public_key.y().unwrap().to_vec(),
+        )
+        .algorithm(Algorithm::ES256)
+        .build();
+
+        [
+            &[0x11; 32][..],
+            &[0x41],
+            &0u32.to_be_bytes(),
+            &AAGUID,
+            &4u16.to_be_bytes(),
+            &[0xca, 0xfe, 0xba, 0xbe],
+            &credential_public_key.to_vec().unwrap(),
+        ]
+        .concat()
+    }
+
+    /// A signature over `authenticator_data` by a fresh attestation key, and that key.
+    fn attest(authenticator_data: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let attestation_key = SigningKey::random(&mut OsRng);
+        let public_key = attestation_key.verifying_key().to_encoded_point(false);
+        let signature: Signature = attestation_key
+            .sign(&[authenticator_data, &client_data_hash(CLIENT_DATA_JSON)].concat());
+        (
+            signature.to_der().as_bytes().to_vec(),
+            sec1_public_key_to_der(public_key.as_bytes()).unwrap(),
+        )
+    }
+
+    fn verify(authenticator_data: &[u8], sig: &[u8], x5c: &[Vec<u8>]) -> Result<(), VerifyError> {
+        verify_packed_x5c_attestation(
+            authenticator_data,
+            CLIENT_DATA_JSON,
+            CoseAlgorithm::ES256,
+            sig,
+            x5c,
+        )
+    }
+
+    #[test]
+    fn verifies_with_the_leaf_certificate_key() {
+        let authenticator_data = authenticator_data();
+        let (sig, public_key_der) = attest(&authenticator_data);
+        let extensions = [basic_constraints(false), aaguid_extension(AAGUID, false)];
+        let (issuer_key, intermediate) = issuer();
+        let leaf = signed(
+            tbs_certificate(3, &public_key_der, &extensions, validity()),
+            Some(&issuer_key),
+        );
+
+        assert_eq!(
+            verify(&authenticator_data, &sig, std::slice::from_ref(&leaf)),
+            Ok(())
+        );
+        assert_eq!(
+            verify(
+                &authenticator_data,
+                &sig,
+                &[leaf.clone(), intermediate.clone()]
+            ),
+            Ok(())
+        );
+        // The AAGUID extension is optional.
+        let without_aaguid = certificate(3, &public_key_der, &[basic_constraints(false)]);
+        assert_eq!(verify(&authenticator_data, &sig, &[without_aaguid]), Ok(()));
+
+        // Only the first certificate is the attestation one.
+        assert_eq!(
+            verify(&authenticator_data, &sig, &[intermediate, leaf]),
+            Err(VerifyError::InvalidAttestationCertificate)
+        );
+        // Signed by another key
+        let other_key = certificate(3, &attest(b"").1, &[basic_constraints(false)]);
+        assert_eq!(
+            verify(&authenticator_data, &sig, &[other_key]),
+            Err(VerifyError::VerifySignature)
+        );
+    }
+
+    #[test]
+    fn verifies_each_certificate_with_the_key_of_the_next() {
+        let authenticator_data = authenticator_data();
+        let (sig, public_key_der) = attest(&authenticator_data);
+        let tbs_leaf = || tbs_certificate(3, &public_key_der, &[], validity());
+        let (root_key, root) = issuer();
+        let (intermediate_key, _) = issuer();
+        let intermediate_public_key = intermediate_key.verifying_key().to_encoded_point(false);
+        let intermediate = signed(
+            tbs_certificate(
+                3,
+                &sec1_public_key_to_der(intermediate_public_key.as_bytes()).unwrap(),
+                &[basic_constraints(true)],
+                validity(),
+            ),
+            Some(&root_key),
+        );
+        let leaf = signed(tbs_leaf(), Some(&intermediate_key));
+
+        assert_eq!(
+            verify(
+                &authenticator_data,
+                &sig,
+                &[leaf.clone(), intermediate.clone(), root.clone()]
+            ),
+            Ok(())
+        );
+        // The intermediate certificate doesn't vouch for the leaf.
+        assert_eq!(
+            verify(&authenticator_data, &sig, &[leaf.clone(), root.clone()]),
+            Err(VerifyError::InvalidAttestationCertificate)
+        );
+        assert_eq!(
+            verify(
+                &authenticator_data,
+                &sig,
+                &[signed(tbs_leaf(), None), intermediate.clone()]
+            ),
+            Err(VerifyError::ParseAttestationCertificate)
+        );
+        // Nor does a certificate that isn't a CA, even with the right key.
+        let not_ca = signed(
+            tbs_certificate(
+                3,
+                &sec1_public_key_to_der(intermediate_public_key.as_bytes()).unwrap(),
+                &[basic_constraints(false)],
+                validity(),
+            ),
+            Some(&root_key),
+        );
+        assert_eq!(
+            verify(&authenticator_data, &sig, &[leaf, not_ca, root]),
+            Err(VerifyError::InvalidAttestationCertificate)
+        );
+    }
+
+    #[test]
+    fn certificates_are_only_valid_within_their_validity_period() {
+        // 2025-01-01T00:00:00Z and 2035-01-01T00:00:00Z.
+        const VALID_FROM: u64 = 1_735_689_600;
+        const VALID_UNTIL: u64 = 2_051_222_400;
+
+        let authenticator_data = authenticator_data();
+        let (sig, public_key_der) = attest(&authenticator_data);
+        let verify_at = |x5c: &[Vec<u8>], at| {
+            verify_packed_x5c_attestation_at(
+                &authenticator_data,
+                CLIENT_DATA_JSON,
+                CoseAlgorithm::ES256,
+                &sig,
+                x5c,
+                at,
+            )
+        };
+        let (issuer_key, intermediate) = issuer();
+        let leaf = signed(
+            tbs_certificate(3, &public_key_der, &[], validity()),
+            Some(&issuer_key),
+        );
+        let x5c = [leaf, intermediate];
+
+        for at in [VALID_FROM, VALID_FROM + 86_400 * 365, VALID_UNTIL] {
+            assert_eq!(verify_at(&x5c, at), Ok(()));
+        }
+        for at in [0, VALID_FROM - 1, VALID_UNTIL + 1] {
+            assert_eq!(
+                verify_at(&x5c, at),
+                Err(VerifyError::InvalidAttestationCertificate)
+            );
+        }
+
+        // `GeneralizedTime`s, as for dates from 2050 on.
+        let long_lived = signed(
+            tbs_certificate(
+                3,
+                &public_key_der,
+                &[],
+                [der(0x18, b"20250101000000Z"), der(0x18, b"99991231235959Z")],
+            ),
+            None,
+        );
+        assert_eq!(verify_at(&[long_lived.clone()], VALID_FROM), Ok(()));
+        assert_eq!(
+            verify_at(&[long_lived], VALID_FROM - 1),
+            Err(VerifyError::InvalidAttestationCertificate)
+        );
+    }
+
+    #[test]
+    fn rejects_certificates_unfit_for_attestation() {
+        let authenticator_data = authenticator_data();
+        let (sig, public_key_der) = attest(&authenticator_data);
+
+        for (certificate, error) in [
+            (
+                certificate(3, &public_key_der, &[basic_constraints(true)]),
+                VerifyError::InvalidAttestationCertificate,
+            ),
+            (
+                certificate(2, &public_key_der, &[]),
+                VerifyError::InvalidAttestationCertificate,
+            ),
+            (
+                certificate(3, &public_key_der, &[aaguid_extension(AAGUID, true)]),
+                VerifyError::InvalidAttestationCertificate,
+            ),
+            (
+                certificate(3, &public_key_der, &[aaguid_extension([0x33; 16], false)]),
+                VerifyError::AaguidMismatch,
+            ),
+            (
+                certificate(3, b"\x30\x00", &[]),
+                VerifyError::ParseAttestationCertificate,
+            ),
+        ] {
+            assert_eq!(
+                verify(&authenticator_data, &sig, &[certificate]),
+                Err(error)
+            );
+        }
+
+        let leaf = certificate(3, &public_key_der, &[]);
+        assert_eq!(
+            verify(
+                &authenticator_data,
+                &sig,
+                &[leaf[..leaf.len() - 1].to_vec()]
+            ),
+            Err(VerifyError::ParseAttestationCertificate)
+        );
+        assert_eq!(
+            verify(&authenticator_data, &sig, &[]),
+            Err(VerifyError::ParseAttestationObject)
+        );
+    }
+
+    #[test]
+    fn attestation_objects_carry_the_chain() {
+        use coset::cbor::{ser::into_writer, value::Value};
+
+        let leaf = certificate(3, &attest(b"").1, &[]);
+        let mut encoded = Vec::new();
+        into_writer(
+            &Value::Map(vec![
+                (Value::Text("fmt".into()), Value::Text("packed".into())),
+                (
+                    Value::Text("attStmt".into()),
+                    Value::Map(vec![
+                        (
+                            Value::Text("alg".into()),
+                            Value::Integer((Algorithm::ES256 as i64).into()),
+                        ),
+                        (Value::Text("sig".into()), Value::Bytes(vec![0x30])),
+                        (
+                            Value::Text("x5c".into()),
+                            Value::Array(vec![Value::Bytes(leaf.clone())]),
+                        ),
+                    ]),
+                ),
+                (Value::Text("authData".into()), Value::Bytes(vec![0xaa; 37])),
+            ]),
+            &mut encoded,
+        )
+        .expect("Writing to a vector doesn't fail");
+
+        assert_eq!(
+            parse_attestation_object(&encoded).map(|object| object.statement),
+            Ok(AttestationStatement::PackedX5c {
+                alg: CoseAlgorithm::ES256,
+                sig: vec![0x30],
+                x5c: vec![leaf],
+            })
+        );
+    }
+
+    /// Revokes the certificates it was given.
+    struct Revoking(Vec<Vec<u8>>);
+
+    impl RevocationChecker for Revoking {
+        fn check_ocsp(&self, cert_der: &[u8]) -> Result<(), RevocationError> {
+            if self.0.iter().any(|revoked| revoked == cert_der) {
+                return Err(RevocationError::Revoked);
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn revoked_certificates_are_rejected() {
+        let authenticator_data = authenticator_data();
+        let (sig, public_key_der) = attest(&authenticator_data);
+        let (issuer_key, intermediate) = issuer();
+        let leaf = signed(
+            tbs_certificate(3, &public_key_der, &[basic_constraints(false)], validity()),
+            Some(&issuer_key),
+        );
+        let x5c = [leaf.clone(), intermediate.clone()];
+
+        let verify = |checker: &Revoking| {
+            verify_packed_x5c_attestation_with_revocation(
+                checker,
+                &authenticator_data,
+                CLIENT_DATA_JSON,
+                CoseAlgorithm::ES256,
+                &sig,
+                &x5c,
+            )
+        };
+        assert_eq!(verify(&Revoking(vec![])), Ok(()));
+        assert_eq!(
+            verify(&Revoking(vec![leaf])),
+            Err(VerifyError::AttestationCertificateRevoked)
+        );
+        assert_eq!(
+            verify(&Revoking(vec![intermediate])),
+            Err(VerifyError::AttestationCertificateRevoked)
+        );
+        assert_eq!(
+            verify_packed_x5c_attestation_with_revocation(
+                &NoOpRevocationChecker,
+                &authenticator_data,
+                CLIENT_DATA_JSON,
+                CoseAlgorithm::ES256,
+                &sig,
+                &x5c,
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn unknown_revocation_status_is_not_trusted() {
+        struct Unreachable;
+
+        impl RevocationChecker for Unreachable {
+            fn check_ocsp(&self, _: &[u8]) -> Result<(), RevocationError> {
+                Err(RevocationError::StatusUnknown)
+            }
+        }
+
+        let authenticator_data = authenticator_data();
+        let (sig, public_key_der) = attest(&authenticator_data);
+        let leaf = certificate(3, &public_key_der, &[]);
+
+        assert_eq!(
+            verify_packed_x5c_attestation_with_revocation(
+                &Unreachable,
+                &authenticator_data,
+                CLIENT_DATA_JSON,
+                CoseAlgorithm::ES256,
+                &sig,
+                &[leaf],
+            ),
+            Err(VerifyError::InvalidAttestationCertificate)
+        );
+    }
+
+    #[test]
+    fn only_statements_without_a_chain_are_self_attested() {
+        let self_attested = AttestationStatement::Packed {
+            alg: CoseAlgorithm::ES256,
+            sig: vec![0x30],
+        };
+        let basic = AttestationStatement::PackedX5c {
+            alg: CoseAlgorithm::ES256,
+            sig: vec![0x30],
+            x5c: vec![certificate(3, &attest(b"").1, &[])],
+        };
+
+        assert!(self_attested.is_self_attested());
+        assert!(!basic.is_self_attested());
+        assert!(!AttestationStatement::None.is_self_attested());
+    }
+}
+
+mod hasher {
+    use super::*;
+    use sha2::digest::{consts::U32, FixedOutput, HashMarker, Output, OutputSizeUser, Update};
+
+    /// A SHA-256 implemented elsewhere, as a hardware accelerated one would be.
+    #[derive(Default, Clone)]
+    struct ExternalSha256(Sha256);
+
+    impl HashMarker for ExternalSha256 {}
+
+    impl OutputSizeUser for ExternalSha256 {
+        type OutputSize = U32;
+    }
+
+    impl Update for ExternalSha256 {
+        fn update(&mut self, data: &[u8]) {
+            Update::update(&mut self.0, data);
+        }
+    }
+
+    impl FixedOutput for ExternalSha256 {
+        fn finalize_into(self, out: &mut Output<Self>) {
+            FixedOutput::finalize_into(self.0, out);
+        }
+    }
+
+    /// Reverses its input before hashing it, so it isn't SHA-256.
+    #[derive(Default, Clone)]
+    struct NotSha256(Vec<u8>);
+
+    impl HashMarker for NotSha256 {}
+
+    impl OutputSizeUser for NotSha256 {
+        type OutputSize = U32;
+    }
+
+    impl Update for NotSha256 {
+        fn update(&mut self, data: &[u8]) {
+            self.0.extend_from_slice(data);
+        }
+    }
+
+    impl FixedOutput for NotSha256 {
+        fn finalize_into(mut self, out: &mut Output<Self>) {
+            self.0.reverse();
+            out.copy_from_slice(&Sha256::digest(&self.0));
+        }
+    }
+
+    #[test]
+    fn equivalent_hashers_verify_alike() {
+        let signing_key = SigningKey::random(&mut OsRng);
+        let public_key = signing_key.verifying_key().to_encoded_point(false);
+        let public_key_der = sec1_public_key_to_der(public_key.as_bytes()).unwrap();
+        let authenticator_data = [0x42; 37];
+        let client_data_json = br#"{"type":"webauthn.get","challenge":"dGVzdA","origin":"o"}"#;
+        let message = [&authenticator_data[..], &client_data_hash(client_data_json)].concat();
+        let signature: Signature = signing_key.sign(&message);
+        let signature = signature.to_der();
+        let other_point = SigningKey::random(&mut OsRng)
+            .verifying_key()
+            .to_encoded_point(false);
+        let other_key = sec1_public_key_to_der(other_point.as_bytes()).unwrap();
+
+        for (signature, public_key_der) in [
+            (signature.as_bytes(), &public_key_der[..]),
+            (signature.as_bytes(), &other_key[..]),
+            (b"not a signature", &public_key_der[..]),
+            (signature.as_bytes(), b"not a key"),
+        ] {
+            assert_eq!(
+                webauthn_verify_with_hasher::<ExternalSha256>(
+                    &authenticator_data,
+                    client_data_json,
+                    signature,
+                    public_key_der
+                ),
+                webauthn_verify(
+                    &authenticator_data,
+                    client_data_json,
+                    signature,
+                    public_key_der
+                )
+            );
+        }
+        assert_eq!(
+            webauthn_verify_with_hasher::<Sha256>(
+                &authenticator_data,
+                client_data_json,
+                signature.as_bytes(),
+                &public_key_der
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            webauthn_verify_with_hasher::<NotSha256>(
+                &authenticator_data,
+                client_data_json,
+                signature.as_bytes(),
+                &public_key_der
+            ),
+            Err(VerifyError::VerifySignature)
+        );
+    }
+}
+
+mod sha256 {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn fixed_matches_digest(data in proptest::collection::vec(any::<u8>(), 0..512)) {
+            prop_assert_eq!(sha256_fixed(&data), <[u8; 32]>::from(Sha256::digest(&data)));
+        }
+
+        #[test]
+        fn two_part_matches_digest_of_concatenation(
+            part1 in proptest::collection::vec(any::<u8>(), 0..256),
+            part2 in proptest::collection::vec(any::<u8>(), 0..256),
+        ) {
+            prop_assert_eq!(
+                sha256_two_part(&part1, &part2),
+                <[u8; 32]>::from(Sha256::digest([part1, part2].concat()))
+            );
+        }
+    }
+}
+
+mod compact_signature {
+    use super::*;
+
+    /// `n`, the order of the P-256 curve.
+    const ORDER: [u8; 32] = [
+        0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xbc, 0xe6, 0xfa, 0xad, 0xa7, 0x17, 0x9e, 0x84, 0xf3, 0xb9, 0xca, 0xc2, 0xfc, 0x63,
+        0x25, 0x51,
+    ];
+
+    #[test]
+    fn compact_signatures_encode_as_p256_does() {
+        let signing_key = SigningKey::random(&mut OsRng);
+        let signature: Signature = signing_key.sign(b"authenticator data and client data hash");
+
+        let compact: [u8; 64] = signature.to_bytes().into();
+        let der = compact_signature_to_der(&compact).expect("The signature is valid");
+        assert_eq!(der, signature.to_der().as_bytes());
+        // And back.
+        let decoded = Signature::from_der(&der).expect("The encoding is valid DER");
+        assert_eq!(<[u8; 64]>::from(decoded.to_bytes()), compact);
+        assert!(signing_key
+            .verifying_key()
+            .verify(b"authenticator data and client data hash", &decoded)
+            .is_ok());
+    }
+
+    #[test]
+    fn out_of_range_scalars_are_rejected() {
+        let valid = [0x01; 32];
+        for invalid in [[0; 32], ORDER, [0xff; 32]] {
+            assert_eq!(
+                compact_signature_to_der(&[invalid, valid].concat().try_into().unwrap()),
+                Err(SignatureError::InvalidR)
+            );
+            assert_eq!(
+                compact_signature_to_der(&[valid, invalid].concat().try_into().unwrap()),
+                Err(SignatureError::InvalidS)
+            );
+        }
+    }
+}
+
+/// Run with each combination of the algorithm features, e.g.
+/// `cargo test -p verifier --no-default-features --features std algorithm_features`.
+mod algorithm_features {
+    use super::*;
+
+    fn es256_key() -> coset::CoseKey {
+        let public_key = SigningKey::random(&mut OsRng)
+            .verifying_key()
+            .to_encoded_point(false);
+        CoseKeyBuilder::new_ec2_pub_key(
+            EllipticCurve::P_256,
+            public_key.x().unwrap().to_vec(),
+            public_key.y().unwrap().to_vec(),
+        )
+        .build()
+    }
+
+    #[test]
+    fn algorithms_without_a_backend_are_unsupported() {
+        for alg in [
+            Algorithm::RS256,
+            Algorithm::EdDSA,
+            Algorithm::ES384,
+            Algorithm::ES512,
+        ] {
+            assert_eq!(
+                CoseAlgorithm::try_from(alg as i64),
+                Err(VerifyError::UnsupportedAlgorithm),
+                "{alg:?}"
+            );
+        }
+    }
+
+    #[cfg(feature = "es256")]
+    #[test]
+    fn es256_is_dispatched_with_its_feature() {
+        let mut key = es256_key();
+        assert_eq!(
+            cose_key_algorithm_with_policy(&key, MissingAlgorithmPolicy::InferFromKeyType),
+            Ok(CoseAlgorithm::ES256)
+        );
+
+        key.alg = Some(coset::RegisteredLabelWithPrivate::Assigned(
+            Algorithm::ES256,
+        ));
+        assert_eq!(cose_key_algorithm(&key), Ok(CoseAlgorithm::ES256));
+        assert_eq!(
+            CoseAlgorithm::try_from(Algorithm::ES256 as i64),
+            Ok(CoseAlgorithm::ES256)
+        );
+        assert!(cose_key_to_public_key(&key).is_ok());
+    }
+
+    #[cfg(not(feature = "es256"))]
+    #[test]
+    fn es256_is_unsupported_without_its_feature() {
+        let mut key = es256_key();
+        assert_eq!(
+            cose_key_algorithm_with_policy(&key, MissingAlgorithmPolicy::InferFromKeyType),
+            Err(VerifyError::MissingAlgorithm)
+        );
+
+        key.alg = Some(coset::RegisteredLabelWithPrivate::Assigned(
+            Algorithm::ES256,
+        ));
+        assert_eq!(
+            cose_key_algorithm(&key),
+            Err(VerifyError::UnsupportedAlgorithm)
+        );
+        assert_eq!(
+            CoseAlgorithm::try_from(Algorithm::ES256 as i64),
+            Err(VerifyError::UnsupportedAlgorithm)
+        );
+        assert_eq!(
+            cose_key_to_public_key(&key),
+            Err(VerifyError::UnsupportedAlgorithm)
+        );
+    }
+}
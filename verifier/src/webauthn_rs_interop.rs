@@ -0,0 +1,50 @@
+//! Conversions from [`webauthn-rs`](https://docs.rs/webauthn-rs/0.5) credential public keys.
+//!
+//! Targets `webauthn-rs` 0.5, whose key types live in `webauthn-rs-core` 0.5. A stored
+//! `webauthn_rs::prelude::Passkey` exposes its key through `Passkey::get_public_key()`, and a
+//! `Credential` through its `cred` field; either can be turned into the DER-encoded key
+//! [`webauthn_verify`](crate::webauthn_verify) expects.
+
+use alloc::vec::Vec;
+use coset::{iana, CoseKey, CoseKeyBuilder};
+use webauthn_rs_core::proto::{COSEAlgorithm, COSEEC2Key, COSEKey, COSEKeyType, ECDSACurve};
+
+use crate::{cose_key_to_der, CoseAlgorithm, VerifyError};
+
+impl TryFrom<&COSEKey> for CoseAlgorithm {
+    type Error = VerifyError;
+
+    fn try_from(key: &COSEKey) -> Result<Self, Self::Error> {
+        match key.type_ {
+            #[cfg(feature = "es256")]
+            COSEAlgorithm::ES256 => Ok(CoseAlgorithm::ES256),
+            _ => Err(VerifyError::UnsupportedAlgorithm),
+        }
+    }
+}
+
+/// Converts a `webauthn-rs` public key into its COSE representation.
+pub fn cose_key_from_webauthn_rs(key: &COSEKey) -> Result<CoseKey, VerifyError> {
+    CoseAlgorithm::try_from(key)?;
+
+    match &key.key {
+        COSEKeyType::EC_EC2(COSEEC2Key {
+            curve: ECDSACurve::SECP256R1,
+            x,
+            y,
+        }) => {
+            Ok(
+                CoseKeyBuilder::new_ec2_pub_key(iana::EllipticCurve::P_256, x.to_vec(), y.to_vec())
+                    .algorithm(iana::Algorithm::ES256)
+                    .build(),
+            )
+        }
+        _ => Err(VerifyError::ExtractPublicKey),
+    }
+}
+
+/// Converts a `webauthn-rs` public key into the DER-encoded key used by
+/// [`webauthn_verify`](crate::webauthn_verify).
+pub fn webauthn_rs_key_to_der(key: &COSEKey) -> Result<Vec<u8>, VerifyError> {
+    cose_key_to_der(&cose_key_from_webauthn_rs(key)?)
+}
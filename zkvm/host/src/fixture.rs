@@ -0,0 +1,29 @@
+//! A fixed assertion, signed with the P-256 key whose secret scalar is `[0x11; 32]` using
+//! deterministic (RFC 6979) ECDSA, so guest executions are reproducible.
+
+/// For the `localhost` RP ID, with the user present and verified, and a sign count of 1.
+pub const AUTHENTICATOR_DATA: [u8; 37] = [
+    0x49, 0x96, 0x0d, 0xe5, 0x88, 0x0e, 0x8c, 0x68, 0x74, 0x34, 0x17, 0x0f, 0x64, 0x76, 0x60, 0x5b,
+    0x8f, 0xe4, 0xae, 0xb9, 0xa2, 0x86, 0x32, 0xc7, 0x99, 0x5c, 0xf3, 0xba, 0x83, 0x1d, 0x97, 0x63,
+    0x05, 0x00, 0x00, 0x00, 0x01,
+];
+
+pub const CLIENT_DATA_JSON: &[u8] =
+    br#"{"type":"webauthn.get","challenge":"dGVzdA","origin":"https://localhost"}"#;
+
+pub const SIGNATURE_DER: [u8; 70] = [
+    0x30, 0x44, 0x02, 0x20, 0x2e, 0x79, 0x57, 0xe6, 0x13, 0xa8, 0x6c, 0x59, 0x38, 0x74, 0x4d, 0xad,
+    0x74, 0xd0, 0xe7, 0xc8, 0x0b, 0x6c, 0xef, 0x6d, 0xa3, 0x21, 0xf6, 0x54, 0x44, 0xaa, 0xb1, 0xf0,
+    0xfc, 0xb2, 0xc1, 0xe0, 0x02, 0x20, 0x56, 0xc7, 0x76, 0xe4, 0x47, 0x23, 0x6a, 0xa7, 0x9b, 0xc8,
+    0x62, 0x71, 0x1b, 0xd4, 0x4f, 0x42, 0xcd, 0x9f, 0xec, 0x31, 0x40, 0x62, 0x12, 0x27, 0x36, 0xfe,
+    0xef, 0xcc, 0xfe, 0x57, 0x34, 0x8f,
+];
+
+pub const PUBLIC_KEY_DER: [u8; 91] = [
+    0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a,
+    0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00, 0x04, 0x02, 0x17, 0xe6, 0x17, 0xf0,
+    0xb6, 0x44, 0x39, 0x28, 0x27, 0x8f, 0x96, 0x99, 0x9e, 0x69, 0xa2, 0x3a, 0x4f, 0x2c, 0x15, 0x2b,
+    0xdf, 0x6d, 0x6c, 0xdf, 0x66, 0xe5, 0xb8, 0x02, 0x82, 0xd4, 0xed, 0x19, 0x4a, 0x7d, 0xeb, 0xcb,
+    0x97, 0x71, 0x2d, 0x2d, 0xda, 0x3c, 0xa8, 0x5a, 0xa8, 0x76, 0x5a, 0x56, 0xf4, 0x5f, 0xc7, 0x58,
+    0x59, 0x96, 0x52, 0xf2, 0x89, 0x7c, 0x65, 0x30, 0x6e, 0x57, 0x94,
+];
@@ -0,0 +1,35 @@
+//! Runs [`webauthn_verify`](https://docs.rs/verifier) inside the RISC Zero zkVM.
+
+use methods::VERIFY_GUEST_ELF;
+use risc0_zkvm::{default_executor, ExecutorEnv};
+
+pub mod fixture;
+
+/// Executes the verification guest on an assertion, returning whether its signature verified.
+///
+/// Only executes the guest, without proving it, so it also runs in dev mode.
+pub fn verify_in_guest(
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    signature_der: &[u8],
+    public_key_der: &[u8],
+) -> bool {
+    let input = (
+        authenticator_data.to_vec(),
+        client_data_json.to_vec(),
+        signature_der.to_vec(),
+        public_key_der.to_vec(),
+    );
+    let env = ExecutorEnv::builder()
+        .write(&input)
+        .expect("Inputs are plain byte vectors; qed")
+        .build()
+        .expect("The environment only has inputs; qed");
+
+    default_executor()
+        .execute(env, VERIFY_GUEST_ELF)
+        .expect("The guest doesn't panic")
+        .journal
+        .decode()
+        .expect("The guest commits a bool; qed")
+}
@@ -0,0 +1,37 @@
+use zkvm_host::{fixture::*, verify_in_guest};
+
+#[test]
+fn fixture_verifies_natively() {
+    assert_eq!(
+        verifier::webauthn_verify(
+            &AUTHENTICATOR_DATA,
+            CLIENT_DATA_JSON,
+            &SIGNATURE_DER,
+            &PUBLIC_KEY_DER
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn fixture_verifies_in_guest() {
+    assert!(verify_in_guest(
+        &AUTHENTICATOR_DATA,
+        CLIENT_DATA_JSON,
+        &SIGNATURE_DER,
+        &PUBLIC_KEY_DER
+    ));
+}
+
+#[test]
+fn tampered_fixture_fails_in_guest() {
+    let mut authenticator_data = AUTHENTICATOR_DATA;
+    authenticator_data[36] = 2;
+
+    assert!(!verify_in_guest(
+        &authenticator_data,
+        CLIENT_DATA_JSON,
+        &SIGNATURE_DER,
+        &PUBLIC_KEY_DER
+    ));
+}
@@ -0,0 +1,29 @@
+//! Verifies an assertion signature, committing whether it verified to the journal.
+
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use risc0_zkvm::guest::env;
+
+risc0_zkvm::guest::entry!(main);
+
+fn main() {
+    let (authenticator_data, client_data_json, signature_der, public_key_der): (
+        Vec<u8>,
+        Vec<u8>,
+        Vec<u8>,
+        Vec<u8>,
+    ) = env::read();
+
+    let verified = verifier::webauthn_verify(
+        &authenticator_data,
+        &client_data_json,
+        &signature_der,
+        &public_key_der,
+    )
+    .is_ok();
+    env::commit(&verified);
+}
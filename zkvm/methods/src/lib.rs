@@ -0,0 +1,3 @@
+//! The guest programs, as `<NAME>_ELF` binaries and `<NAME>_ID` image ids.
+
+include!(concat!(env!("OUT_DIR"), "/methods.rs"));